@@ -1,23 +1,222 @@
-use std::{fs::read_to_string, path::PathBuf};
+use std::{
+    env, fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
 
-use fern::{ast, compiler::compile_program, parse::Parser, source::SourceId};
+use fern::{
+    compiler::{compile_program, CompileOptions, Type},
+    diagnostic::{Diagnostics, Renderer},
+    error::Error,
+    ir::{Interpreter, MemoryType},
+    parse::parse_file,
+    source::Sources,
+};
+use fern_cranelift::{emit_object, report_traps, Jit};
 
 #[derive(clap::Parser)]
 #[clap(author, version)]
 struct Args {
     path: PathBuf,
+    /// Compile the program with the JIT and run `main`.
+    #[clap(long)]
+    run: bool,
+    /// Run `main` with the reference interpreter instead of the JIT.
+    #[clap(long, conflicts_with = "run")]
+    interpret: bool,
+    /// Compile the program to a native executable at this path.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+    /// Only emit a relocatable object file to the output path, without linking.
+    #[clap(long, requires = "output")]
+    object: bool,
+    /// Warn when a variable shadows another variable in scope.
+    #[clap(long)]
+    warn_shadowing: bool,
+    /// Don't check that array indices are in bounds.
+    #[clap(long)]
+    no_bounds_checks: bool,
+    /// Show where in the compiler errors were created.
+    #[clap(long)]
+    error_locations: bool,
 }
 
-fn main() {
-    let args = <Args as clap::Parser>::parse();
+/// Runs `main` with the JIT, where `report` renders the error of a trap like the diagnostics
+/// reported on exit.
+fn run(ir: &fern::ir::Program, report: impl Fn(&Error) -> String) -> Result<(), Error> {
+    let jit = Jit::compile(ir)?;
+
+    // a failed check exits with the same report as the error of the interpreter
+    let reports = jit.traps().map(|(address, error)| (address, report(error)));
+    report_traps(reports.collect())?;
+
+    let id = (jit.function_id("main")).ok_or_else(|| Error::new("No main function"))?;
+    let function = ir.functions.get(id).unwrap();
+    let signature = ir.signatures.get_signature(function.signature);
+    let return_type = ir.types.get_type(signature.return_type);
+
+    if !signature.arguments.is_empty() {
+        return Err(Error::new("main must not take arguments"));
+    }
+
+    // integers are converted to the exit code like `as i32`, the same as in executables
+    let exit_code = unsafe {
+        match return_type {
+            Type::Void => {
+                call_main::<()>(&jit);
+                return Ok(());
+            }
+            Type::Memory(MemoryType::U8) => call_main::<u8>(&jit) as i32,
+            Type::Memory(MemoryType::U16) => call_main::<u16>(&jit) as i32,
+            Type::Memory(MemoryType::U32) => call_main::<u32>(&jit) as i32,
+            Type::Memory(MemoryType::U64) => call_main::<u64>(&jit) as i32,
+            Type::Memory(MemoryType::Usize) => call_main::<usize>(&jit) as i32,
+            Type::Memory(MemoryType::I8) => call_main::<i8>(&jit) as i32,
+            Type::Memory(MemoryType::I16) => call_main::<i16>(&jit) as i32,
+            Type::Memory(MemoryType::I32) => call_main::<i32>(&jit),
+            Type::Memory(MemoryType::I64) => call_main::<i64>(&jit) as i32,
+            Type::Memory(MemoryType::Isize) => call_main::<isize>(&jit) as i32,
+            _ => return Err(Error::new("main must return void or an integer")),
+        }
+    };
+
+    std::process::exit(exit_code);
+}
+
+/// Calls `main` of `jit`.
+///
+/// # Safety
+/// `main` *must* take no arguments and return `T`.
+unsafe fn call_main<T>(jit: &Jit) -> T {
+    let main = jit.function::<extern "C" fn() -> T>("main").unwrap();
+    main()
+}
+
+fn interpret(ir: &fern::ir::Program) -> Result<(), Error> {
+    let id = (ir.functions.get_by_label("main")).ok_or_else(|| Error::new("No main function"))?;
+    let value = Interpreter::new(ir).call(id, &[])?;
+
+    let function = ir.functions.get(id).unwrap();
+    let signature = ir.signatures.get_signature(function.signature);
+
+    // integers are sign or zero extended to 64 bits, so this truncates like `as i32`
+    match ir.types.get_type(signature.return_type) {
+        Type::Memory(ty) if ty.is_integer() => std::process::exit(value as i32),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    fs::write(path, contents)
+        .map_err(|error| Error::new(format!("Failed to write {}: {}", path.display(), error)))
+}
+
+fn build(ir: &fern::ir::Program, output: &Path, object_only: bool) -> Result<(), Error> {
+    let name = output.file_stem().unwrap_or_default().to_string_lossy();
+    let object = emit_object(ir, &name)?;
+
+    if object_only {
+        return write_file(output, &object);
+    }
+
+    let object_path = env::temp_dir().join(format!("{}-{}.o", name, std::process::id()));
+    write_file(&object_path, &object)?;
+
+    // link with the system C toolchain, which also provides the runtime calling `main`
+    let linker = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let status = Command::new(linker)
+        .arg(&object_path)
+        .arg("-o")
+        .arg(output)
+        .status();
 
-    let source = read_to_string(&args.path).unwrap();
+    let _ = fs::remove_file(&object_path);
 
-    let mut parser = Parser::new(&source, SourceId::default());
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(Error::new(format!("Linking failed with {}", status))),
+        Err(error) => Err(Error::new(format!("Failed to run the linker: {}", error))),
+    }
+}
+
+fn compile(args: &Args, sources: &mut Sources, diagnostics: &mut Diagnostics) -> Option<()> {
+    let program = parse_file(sources, &args.path, diagnostics)?;
+
+    if diagnostics.has_errors() {
+        return None;
+    }
+
+    let options = CompileOptions {
+        warn_shadowing: args.warn_shadowing,
+        bounds_checks: !args.no_bounds_checks,
+    };
+
+    let ir = compile_program(program, &options, diagnostics)?;
+
+    let renderer = renderer(args, sources);
+    let report = |error: &Error| {
+        let mut diagnostics = diagnostics.clone();
+        diagnostics.push(error.clone());
+        render(&renderer, &diagnostics)
+    };
+
+    let result = execute(args, &ir, report);
+    diagnostics.report(result)
+}
+
+fn execute(
+    args: &Args,
+    ir: &fern::ir::Program,
+    report: impl Fn(&Error) -> String,
+) -> Result<(), Error> {
+    if let Some(ref output) = args.output {
+        build(ir, output, args.object)?;
+    }
+
+    if args.run {
+        run(ir, report)?;
+    } else if args.interpret {
+        interpret(ir)?;
+    } else if args.output.is_none() {
+        println!("{:#?}", ir);
+    }
 
-    let program = parser.parse::<ast::Program>().unwrap();
+    Ok(())
+}
+
+fn renderer<'a>(args: &Args, sources: &'a Sources) -> Renderer<'a> {
+    let color = std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+
+    Renderer::new(sources)
+        .with_color(color)
+        .with_location(args.error_locations)
+}
+
+/// Renders `diagnostics` as they are reported on exit.
+fn render(renderer: &Renderer, diagnostics: &Diagnostics) -> String {
+    let mut out = renderer.render_all(diagnostics);
+    let errors = diagnostics.errors().count();
+
+    if errors > 1 {
+        out.push_str(&format!("\n{} errors emitted\n", errors));
+    }
+
+    out
+}
+
+fn main() -> ExitCode {
+    let args = <Args as clap::Parser>::parse();
+    let mut sources = Sources::new();
+    let mut diagnostics = Diagnostics::new();
 
-    let ir = compile_program(program).unwrap();
+    let result = compile(&args, &mut sources, &mut diagnostics);
+    eprint!("{}", render(&renderer(&args, &sources), &diagnostics));
 
-    println!("{:#?}", ir);
+    match (result, diagnostics.errors().count()) {
+        (Some(()), 0) => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    }
 }
@@ -0,0 +1,108 @@
+use std::{env, fs, process::Command};
+
+/// Runs `source` with the CLI and `flag`, returning the exit code and stderr.
+fn run(name: &str, source: &str, flag: &str) -> (Option<i32>, String) {
+    let path = env::temp_dir().join(format!("{}-{}.fn", name, std::process::id()));
+    fs::write(&path, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fern-cli"))
+        .arg(flag)
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    let _ = fs::remove_file(&path);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    (
+        output.status.code(),
+        stderr.replace(&*path.to_string_lossy(), "test.fn"),
+    )
+}
+
+// exit codes are truncated to 8 bits on unix
+#[cfg(unix)]
+#[test]
+fn exit_codes() {
+    let cases = [
+        ("fn main() {}", 0),
+        ("fn main() -> i32 { 3 }", 3),
+        ("fn main() -> u8 { 300 as u8 }", 44),
+        ("fn main() -> i64 { 256 + 7 }", 7),
+        ("fn main() -> i8 { -1 }", 255),
+    ];
+
+    for (source, exit_code) in cases {
+        for flag in ["--run", "--interpret"] {
+            let (code, stderr) = run("exit_codes", source, flag);
+
+            assert_eq!(code, Some(exit_code), "{} {}", source, flag);
+            assert_eq!(stderr, "", "{} {}", source, flag);
+        }
+    }
+}
+
+// traps are only reported on targets where the handler can find the trap address
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+#[test]
+fn index_out_of_bounds() {
+    let source = "\
+fn get(values: &[u8], index: usize) -> u8 {
+    values[index]
+}
+
+fn main() -> i32 {
+    let values = [1u8, 2, 3];
+    get(&values, 3) as i32
+}
+";
+    let report = "\
+error: Index out of bounds
+ --> test.fn:2:5
+  |
+2 |     values[index]
+  |     ^^^^^^^^^^^^^
+";
+
+    // the JIT reports the trap like the interpreter reports the error
+    for flag in ["--run", "--interpret"] {
+        let (code, stderr) = run("index_out_of_bounds", source, flag);
+
+        assert_eq!(code, Some(1), "{}", flag);
+        assert_eq!(stderr, report, "{}", flag);
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+#[test]
+fn division_by_zero() {
+    let source = "\
+fn divide(a: i32, b: i32) -> i32 {
+    a / b
+}
+
+fn main() -> i32 {
+    divide(1, 0)
+}
+";
+    let report = "\
+error: Division by zero
+ --> test.fn:2:5
+  |
+2 |     a / b
+  |     ^^^^^
+";
+
+    for flag in ["--run", "--interpret"] {
+        let (code, stderr) = run("division_by_zero", source, flag);
+
+        assert_eq!(code, Some(1), "{}", flag);
+        assert_eq!(stderr, report, "{}", flag);
+    }
+}
@@ -0,0 +1,379 @@
+use std::{collections::HashMap, mem};
+
+use cranelift::{
+    jit::{JITBuilder, JITModule},
+    module::{default_libcall_names, FuncId},
+};
+use fern::{error::Error, id::FunctionId, ir::Program};
+
+use crate::{module_error, translate_program};
+
+/// Compiles an [`ir::Program`](Program) to machine code in memory.
+pub struct Jit {
+    module: JITModule,
+    functions: HashMap<FunctionId, FuncId>,
+    names: HashMap<String, FunctionId>,
+    /// The error of each [`Trap`](crate::Trap), by the address of its trap instruction.
+    traps: HashMap<usize, Error>,
+}
+
+impl Jit {
+    pub fn compile(program: &Program) -> Result<Self, Error> {
+        let builder = JITBuilder::new(default_libcall_names()).map_err(module_error)?;
+        let mut module = JITModule::new(builder);
+
+        let translated = translate_program(&mut module, program, |_, _| None)?;
+        module.finalize_definitions().map_err(module_error)?;
+
+        let traps = (translated.traps.iter())
+            .map(|trap| {
+                let code = module.get_finalized_function(trap.function) as usize;
+                (code + trap.offset as usize, trap.error())
+            })
+            .collect();
+
+        let mut names = HashMap::new();
+
+        for (id, function) in program.functions.iter() {
+            if let Some(ref label) = function.label {
+                names.insert(label.clone(), id);
+            }
+        }
+
+        Ok(Self {
+            module,
+            functions: translated.functions,
+            names,
+            traps,
+        })
+    }
+
+    /// Gets the id of the function labeled `name`.
+    pub fn function_id(&self, name: &str) -> Option<FunctionId> {
+        self.names.get(name).copied()
+    }
+
+    /// Gets a pointer to the compiled code of `id`.
+    pub fn function_ptr(&self, id: FunctionId) -> Option<*const u8> {
+        let func_id = self.functions.get(&id)?;
+        Some(self.module.get_finalized_function(*func_id))
+    }
+
+    /// Gets a pointer to the compiled code of the function labeled `name`.
+    pub fn function_ptr_by_name(&self, name: &str) -> Option<*const u8> {
+        self.function_ptr(self.function_id(name)?)
+    }
+
+    /// Iterates over the addresses of the trap instructions of the program's checks, like bounds
+    /// checks, along with the error the interpreter reports when the check fails.
+    pub fn traps(&self) -> impl Iterator<Item = (usize, &Error)> {
+        self.traps.iter().map(|(&address, error)| (address, error))
+    }
+
+    /// Gets the function labeled `name` as a function pointer of type `F`.
+    ///
+    /// # Safety
+    /// `F` *must* be an `extern "C" fn` matching the signature of the function.
+    pub unsafe fn function<F: Copy>(&self, name: &str) -> Option<F> {
+        assert_eq!(mem::size_of::<F>(), mem::size_of::<*const u8>());
+
+        let ptr = self.function_ptr_by_name(name)?;
+        Some(mem::transmute_copy(&ptr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fern::{
+        compiler::{FunctionSignature, Type},
+        id::FunctionIds,
+        ir::{Function, InstructionBuilder, MemoryType, Program, RegisterAllocator, Stack},
+        test_support::compile,
+    };
+
+    use super::Jit;
+
+    #[test]
+    fn call_function() {
+        let program = compile("fn add(a: i32, b: i32) -> i32 { return a + b; }");
+        let jit = Jit::compile(&program).unwrap();
+        let add = unsafe { jit.function::<extern "C" fn(i32, i32) -> i32>("add") }.unwrap();
+
+        assert_eq!(add(2, 3), 5);
+        assert_eq!(add(-7, 3), -4);
+    }
+
+    #[test]
+    fn references() {
+        let program = compile(
+            "
+            fn main() -> i32 {
+                let x = foo(2);
+                let y = &x;
+                return *y;
+            }
+
+            fn foo(x: i32) -> i32 {
+                let y = &x;
+                return *y;
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+
+        let main = unsafe { jit.function::<extern "C" fn() -> i32>("main") }.unwrap();
+        assert_eq!(main(), 2);
+    }
+
+    #[test]
+    fn loops() {
+        let program = compile(
+            "
+            fn pick(a: bool, x: i32) -> i32 {
+                'outer: while a {
+                    loop {
+                        break 'outer;
+                    }
+                }
+
+                return loop {
+                    if a {
+                        break x + x;
+                    }
+
+                    break x;
+                };
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let pick = unsafe { jit.function::<extern "C" fn(bool, i32) -> i32>("pick") }.unwrap();
+
+        assert_eq!(pick(true, 3), 6);
+        assert_eq!(pick(false, 3), 3);
+    }
+
+    #[test]
+    fn structs() {
+        let program = compile(
+            "
+            struct Pair {
+                a: u8,
+                b: i64,
+            }
+
+            fn make(a: u8, b: i64) -> Pair {
+                return Pair { a: a, b: b };
+            }
+
+            fn sum(pair: &Pair) -> i64 {
+                let copy = *pair;
+                pair.b = copy.b + copy.b;
+                return pair.b;
+            }
+
+            fn run(a: u8, b: i64) -> i64 {
+                let pair = make(a, b);
+                return sum(&pair) + pair.b;
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let run = unsafe { jit.function::<extern "C" fn(u8, i64) -> i64>("run") }.unwrap();
+
+        assert_eq!(run(1, 3), 12);
+    }
+
+    #[test]
+    fn strings() {
+        let program = compile(
+            r#"
+            fn pick(a: bool) -> str {
+                if a {
+                    return "fern\u{e6}";
+                }
+
+                let s = "";
+                return s;
+            }
+            "#,
+        );
+        // the pointer and length are returned in two registers, like a two field struct
+        #[repr(C)]
+        struct Str {
+            pointer: *const u8,
+            length: usize,
+        }
+
+        let jit = Jit::compile(&program).unwrap();
+        let pick = unsafe { jit.function::<extern "C" fn(bool) -> Str>("pick") }.unwrap();
+
+        let string = |a| {
+            let Str { pointer, length } = pick(a);
+
+            let bytes = unsafe { std::slice::from_raw_parts(pointer, length) };
+            std::str::from_utf8(bytes).unwrap().to_string()
+        };
+
+        assert_eq!(string(true), "fern\u{e6}");
+        assert_eq!(string(false), "");
+    }
+
+    #[test]
+    fn floats() {
+        let program = compile(
+            "
+            fn add(a: f64, b: f64) -> f64 {
+                return a + b;
+            }
+
+            fn to_byte(x: f32) -> u8 {
+                return x as u8;
+            }
+
+            fn to_int(x: f64) -> i16 {
+                return x as i16;
+            }
+
+            fn from_int(x: u32) -> f32 {
+                return x as f32 + 0.5 as f32;
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let add = unsafe { jit.function::<extern "C" fn(f64, f64) -> f64>("add") }.unwrap();
+        let to_byte = unsafe { jit.function::<extern "C" fn(f32) -> u8>("to_byte") }.unwrap();
+        let to_int = unsafe { jit.function::<extern "C" fn(f64) -> i16>("to_int") }.unwrap();
+        let from_int = unsafe { jit.function::<extern "C" fn(u32) -> f32>("from_int") }.unwrap();
+
+        assert_eq!(add(1.25, 2.0), 3.25);
+        assert_eq!(to_byte(300.0), 255);
+        assert_eq!(to_byte(-1.0), 0);
+        assert_eq!(to_byte(f32::NAN), 0);
+        assert_eq!(to_int(-1e9), i16::MIN);
+        assert_eq!(to_int(-7.9), -7);
+        assert_eq!(from_int(u32::MAX), 4294967296.0);
+        assert_eq!(from_int(2), 2.5);
+    }
+
+    #[test]
+    fn operators() {
+        let program = compile(
+            "
+            fn signed(a: i8, b: i8) -> i8 {
+                let x = (a / b) + (a % b);
+                return (x >> 1) ^ (a << b);
+            }
+
+            fn unsigned(a: u16, b: u16) -> u16 {
+                let x = (a / b) + (a % b);
+                return (x >> 1) | !b;
+            }
+
+            fn compare(a: i32, b: u32, c: f64) -> bool {
+                return (a < -1 && b >= 3) || !(c == c);
+            }
+
+            fn negate(x: f64) -> f64 {
+                return -x;
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let signed = unsafe { jit.function::<extern "C" fn(i8, i8) -> i8>("signed") }.unwrap();
+        let unsigned = unsafe { jit.function::<extern "C" fn(u16, u16) -> u16>("unsigned") };
+        let unsigned = unsigned.unwrap();
+        let compare = unsafe { jit.function::<extern "C" fn(i32, u32, f64) -> bool>("compare") };
+        let compare = compare.unwrap();
+        let negate = unsafe { jit.function::<extern "C" fn(f64) -> f64>("negate") }.unwrap();
+
+        let expected = |a: i8, b: i8| (((a / b) + (a % b)) >> 1) ^ a.wrapping_shl(b as u32);
+        assert_eq!(signed(-7, 2), expected(-7, 2));
+        assert_eq!(signed(100, 3), expected(100, 3));
+
+        assert_eq!(unsigned(60000, 7), ((60000 / 7 + 60000 % 7) >> 1) | !7);
+
+        assert!(compare(-2, 3, 0.0));
+        assert!(!compare(-2, 2, 0.0));
+        assert!(!compare(0, 3, 0.0));
+        assert!(compare(0, 0, f64::NAN));
+
+        assert_eq!(negate(0.0).to_bits(), (-0.0f64).to_bits());
+        assert_eq!(negate(-2.5), 2.5);
+    }
+
+    #[test]
+    fn if_else() {
+        let program = compile(
+            "
+            fn pick(a: bool, b: bool, x: i64) -> i64 {
+                if a {
+                    return x;
+                } else if b {
+                    return foo(x);
+                }
+
+                return bar(x);
+            }
+
+            fn foo(x: i64) -> i64 {
+                return x + x;
+            }
+
+            fn bar(x: i64) -> i64 {
+                return x + x + x;
+            }
+        ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let pick =
+            unsafe { jit.function::<extern "C" fn(bool, bool, i64) -> i64>("pick") }.unwrap();
+
+        assert_eq!(pick(true, false, 3), 3);
+        assert_eq!(pick(false, true, 3), 6);
+        assert_eq!(pick(false, false, 3), 9);
+    }
+
+    #[test]
+    fn branch() {
+        let mut program = Program::default();
+        let u32_type = program.types.get_type_id(&Type::Memory(MemoryType::U32));
+        let signature = program.signatures.get_id(&FunctionSignature {
+            arguments: vec![u32_type, u32_type],
+            return_type: u32_type,
+        });
+
+        let mut registers = RegisterAllocator::new();
+        let a = registers.allocate();
+        let b = registers.allocate();
+
+        let blocks = &mut program.blocks;
+        let (entry, then_block, else_block) = (blocks.create(), blocks.create(), blocks.create());
+
+        let mut ins = InstructionBuilder::new(&mut registers, blocks.get_mut(entry).unwrap());
+        let condition = ins.gt(a, b, false);
+        ins.branch(condition, then_block, else_block);
+        InstructionBuilder::new(&mut registers, blocks.get_mut(then_block).unwrap()).ret([a]);
+        InstructionBuilder::new(&mut registers, blocks.get_mut(else_block).unwrap()).ret([b]);
+
+        let function = Function {
+            label: Some(String::from("max")),
+            signature,
+            return_pointer: None,
+            arguments: vec![a, b],
+            registers: registers.count(),
+            blocks: vec![entry, then_block, else_block],
+            stack: Stack::new(),
+        };
+        program
+            .functions
+            .insert(FunctionIds::default().generate(), function);
+
+        let jit = Jit::compile(&program).unwrap();
+        let max = unsafe { jit.function::<extern "C" fn(u32, u32) -> u32>("max") }.unwrap();
+
+        assert_eq!(max(2, 3), 3);
+        assert_eq!(max(7, 3), 7);
+        assert_eq!(max(u32::MAX, 1), u32::MAX);
+    }
+}
@@ -1,12 +1,10 @@
-pub use cranelift;
-use fern::ir::Program;
-
-pub struct Jit {}
+mod jit;
+mod object;
+mod translate;
+mod trap;
 
-impl Jit {
-    pub fn compile(program: &Program) -> Self {
-        let function = 
-
-        Self {}
-    }
-}
+pub use cranelift;
+pub use jit::*;
+pub use object::*;
+pub use translate::*;
+pub use trap::*;
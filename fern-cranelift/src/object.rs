@@ -0,0 +1,120 @@
+use cranelift::{
+    codegen::ir::UserFuncName,
+    module::{default_libcall_names, Linkage, Module},
+    object::{ObjectBuilder, ObjectModule},
+    prelude::*,
+};
+use fern::{compiler::Type, error::Error, ir::Program};
+
+use crate::{memory_type, module_error, translate_program};
+
+/// Compiles `program` to a relocatable object file for the host machine.
+///
+/// The function labeled `main` is called from an exported C `main` symbol, returning its result
+/// as the exit code if it returns an integer and `0` otherwise.
+pub fn emit_object(program: &Program, name: &str) -> Result<Vec<u8>, Error> {
+    let mut flags = settings::builder();
+    flags.set("is_pic", "true").unwrap();
+
+    let isa = cranelift::native::builder()
+        .map_err(Error::new)?
+        .finish(settings::Flags::new(flags))
+        .map_err(|error| Error::new(format!("{}", error)))?;
+
+    let builder = ObjectBuilder::new(isa, name, default_libcall_names()).map_err(module_error)?;
+    let mut module = ObjectModule::new(builder);
+
+    let main_id = (program.functions.get_by_label("main"))
+        .ok_or_else(|| Error::new("Program has no main function"))?;
+    let main = program.functions.get(main_id).unwrap();
+
+    let main_signature = program.signatures.get_signature(main.signature);
+
+    if !main_signature.arguments.is_empty() {
+        return Err(Error::new("Main function must not take arguments"));
+    }
+
+    if program
+        .types
+        .get_type(main_signature.return_type)
+        .is_aggregate()
+    {
+        return Err(Error::new("Main function must not return an aggregate"));
+    }
+
+    let functions = translate_program(&mut module, program, |_, _| None)?.functions;
+
+    let pointer_type = module.target_config().pointer_type();
+
+    let mut signature = module.make_signature();
+    signature.returns.push(AbiParam::new(types::I32));
+
+    let entry = module
+        .declare_function("main", Linkage::Export, &signature)
+        .map_err(module_error)?;
+
+    let mut context = module.make_context();
+    let mut function_context = FunctionBuilderContext::new();
+
+    context.func.signature = signature;
+    context.func.name = UserFuncName::user(0, entry.as_u32());
+
+    let mut builder = FunctionBuilder::new(&mut context.func, &mut function_context);
+    let block = builder.create_block();
+    builder.switch_to_block(block);
+
+    let func_ref = module.declare_func_in_func(functions[&main_id], builder.func);
+    let call = builder.ins().call(func_ref, &[]);
+
+    let exit_code = match program.types.get_type(main_signature.return_type) {
+        Type::Memory(ty) if ty.is_integer() => {
+            let value = builder.inst_results(call)[0];
+
+            match memory_type(ty, pointer_type).bits() {
+                bits if bits > 32 => builder.ins().ireduce(types::I32, value),
+                bits if bits < 32 && ty.is_signed() => builder.ins().sextend(types::I32, value),
+                bits if bits < 32 => builder.ins().uextend(types::I32, value),
+                _ => value,
+            }
+        }
+        _ => builder.ins().iconst(types::I32, 0),
+    };
+
+    builder.ins().return_(&[exit_code]);
+    builder.seal_all_blocks();
+    builder.finalize();
+
+    module
+        .define_function(entry, &mut context)
+        .map_err(module_error)?;
+
+    let product = module.finish();
+    product
+        .emit()
+        .map_err(|error| Error::new(format!("{}", error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use fern::test_support::compile;
+
+    use super::emit_object;
+
+    #[test]
+    fn emit_main() {
+        let source = "fn main() -> i32 { return 2; }";
+        let program = compile(source);
+
+        let object = emit_object(&program, "test").unwrap();
+
+        assert!(!object.is_empty());
+    }
+
+    #[test]
+    fn missing_main() {
+        let source = "fn foo() -> i32 { return 2; }";
+        let program = compile(source);
+
+        assert!(emit_object(&program, "test").is_err());
+    }
+}
@@ -0,0 +1,935 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use cranelift::{
+    codegen::{ir::UserFuncName, CompiledCode, Context},
+    module::{self, DataDescription, FuncId, Linkage, Module, ModuleError},
+    prelude::*,
+};
+use fern::{
+    compiler::{self, PTR_SIZE},
+    error::Error,
+    id::{BlockId, DataId, FunctionId, FunctionSignatureId},
+    ir::{self, Comparison, FloatComparison, Instruction, MemoryType, Program, Register},
+    span::Span,
+};
+
+/// The trap code of [`Instruction::Unreachable`].
+pub const UNREACHABLE_TRAP: TrapCode = TrapCode::unwrap_user(1);
+
+/// The trap code of [`ir::TrapCode::IndexOutOfBounds`].
+pub const INDEX_OUT_OF_BOUNDS_TRAP: TrapCode = TrapCode::unwrap_user(2);
+
+/// An [`Instruction::Trap`], lowered to a trap instruction at `offset` in the code of `function`.
+#[derive(Clone, Debug)]
+pub struct Trap {
+    pub function: FuncId,
+    pub offset: u32,
+    pub code: ir::TrapCode,
+    pub span: Span,
+}
+
+impl Trap {
+    /// Gets the error the interpreter reports for the same trap.
+    pub fn error(&self) -> Error {
+        Error::spanned(self.code.message(), self.span)
+    }
+}
+
+/// The functions of a program translated by [`translate_program`].
+pub struct TranslatedProgram {
+    pub functions: HashMap<FunctionId, FuncId>,
+    pub traps: Vec<Trap>,
+}
+
+pub(crate) fn module_error(error: ModuleError) -> Error {
+    Error::new(format!("{}", error))
+}
+
+/// Gets the cranelift type used to hold a value of `ty`.
+pub fn memory_type(ty: MemoryType, pointer_type: Type) -> Type {
+    match ty {
+        MemoryType::U8 | MemoryType::I8 | MemoryType::Bool => types::I8,
+        MemoryType::U16 | MemoryType::I16 => types::I16,
+        MemoryType::U32 | MemoryType::I32 => types::I32,
+        MemoryType::U64 | MemoryType::I64 => types::I64,
+        MemoryType::Usize | MemoryType::Isize => pointer_type,
+        MemoryType::F32 => types::F32,
+        MemoryType::F64 => types::F64,
+    }
+}
+
+/// Gets the cranelift condition code of `comparison`.
+pub fn int_cc(comparison: Comparison) -> IntCC {
+    match comparison {
+        Comparison::Equal => IntCC::Equal,
+        Comparison::NotEqual => IntCC::NotEqual,
+        Comparison::SignedLessThan => IntCC::SignedLessThan,
+        Comparison::SignedLessThanOrEqual => IntCC::SignedLessThanOrEqual,
+        Comparison::SignedGreaterThan => IntCC::SignedGreaterThan,
+        Comparison::SignedGreaterThanOrEqual => IntCC::SignedGreaterThanOrEqual,
+        Comparison::UnsignedLessThan => IntCC::UnsignedLessThan,
+        Comparison::UnsignedLessThanOrEqual => IntCC::UnsignedLessThanOrEqual,
+        Comparison::UnsignedGreaterThan => IntCC::UnsignedGreaterThan,
+        Comparison::UnsignedGreaterThanOrEqual => IntCC::UnsignedGreaterThanOrEqual,
+    }
+}
+
+/// Gets the cranelift condition code of `comparison`.
+pub fn float_cc(comparison: FloatComparison) -> FloatCC {
+    match comparison {
+        FloatComparison::Equal => FloatCC::Equal,
+        FloatComparison::NotEqual => FloatCC::NotEqual,
+        FloatComparison::LessThan => FloatCC::LessThan,
+        FloatComparison::LessThanOrEqual => FloatCC::LessThanOrEqual,
+        FloatComparison::GreaterThan => FloatCC::GreaterThan,
+        FloatComparison::GreaterThanOrEqual => FloatCC::GreaterThanOrEqual,
+    }
+}
+
+/// Gets the types of the values returned for `ty`, structs and arrays return nothing since
+/// they're written through a return pointer.
+fn return_types(ty: &compiler::Type) -> Vec<MemoryType> {
+    if ty.is_passed_by_address() {
+        Vec::new()
+    } else {
+        ty.abi_types()
+    }
+}
+
+/// Builds the cranelift signature of `id`.
+pub fn signature<M: Module>(module: &M, program: &Program, id: FunctionSignatureId) -> Signature {
+    let pointer_type = module.target_config().pointer_type();
+    let signature = program.signatures.get_signature(id);
+
+    let mut cranelift_signature = module.make_signature();
+    let return_type = program.types.get_type(signature.return_type);
+
+    // structs and arrays are returned through a pointer passed by the caller
+    if return_type.is_passed_by_address() {
+        cranelift_signature.params.push(AbiParam::new(pointer_type));
+    }
+
+    for &argument in signature.arguments.iter() {
+        for ty in program.types.get_type(argument).abi_types() {
+            let ty = memory_type(ty, pointer_type);
+            cranelift_signature.params.push(AbiParam::new(ty));
+        }
+    }
+
+    for ty in return_types(&return_type) {
+        let ty = memory_type(ty, pointer_type);
+        cranelift_signature.returns.push(AbiParam::new(ty));
+    }
+
+    cranelift_signature
+}
+
+/// Declares and defines every function in `program` in `module`.
+///
+/// Functions for which `export` returns a name are declared with [`Linkage::Export`] under that
+/// name, every other function is declared anonymously.
+pub fn translate_program<M: Module>(
+    module: &mut M,
+    program: &Program,
+    mut export: impl FnMut(FunctionId, &ir::Function) -> Option<String>,
+) -> Result<TranslatedProgram, Error> {
+    let pointer_size = module.target_config().pointer_bytes() as u64;
+
+    if pointer_size != PTR_SIZE {
+        return Err(Error::new(format!(
+            "Targets with {} byte pointers are not supported",
+            pointer_size
+        )));
+    }
+
+    let data = define_data(module, program)?;
+    let mut functions = HashMap::new();
+
+    for (id, function) in program.functions.iter() {
+        let signature = signature(module, program, function.signature);
+
+        let func_id = if let Some(name) = export(id, function) {
+            module.declare_function(&name, Linkage::Export, &signature)
+        } else {
+            module.declare_anonymous_function(&signature)
+        };
+
+        functions.insert(id, func_id.map_err(module_error)?);
+    }
+
+    let mut context = module.make_context();
+    let mut function_context = FunctionBuilderContext::new();
+    let mut traps = Vec::new();
+
+    for (id, function) in program.functions.iter() {
+        let func_id = functions[&id];
+
+        context.func.signature = signature(module, program, function.signature);
+        context.func.name = UserFuncName::user(0, func_id.as_u32());
+
+        let trap_sites = translate_function(
+            module,
+            program,
+            &functions,
+            &data,
+            &mut context,
+            &mut function_context,
+            function,
+        )?;
+
+        module
+            .define_function(func_id, &mut context)
+            .map_err(module_error)?;
+
+        let code = context.compiled_code().unwrap();
+        traps.extend(find_traps(code, func_id, &trap_sites));
+
+        module.clear_context(&mut context);
+    }
+
+    Ok(TranslatedProgram { functions, traps })
+}
+
+/// Finds the trap instructions lowered from [`Instruction::Trap`] in `code`, which have the index
+/// of their site in `trap_sites` as their source location.
+fn find_traps(
+    code: &CompiledCode,
+    function: FuncId,
+    trap_sites: &[(ir::TrapCode, Span)],
+) -> Vec<Trap> {
+    let srclocs = code.buffer.get_srclocs_sorted();
+
+    (code.buffer.traps().iter())
+        .filter_map(|trap| {
+            let srcloc = (srclocs.iter())
+                .find(|srcloc| (srcloc.start..srcloc.end).contains(&trap.offset))?;
+            let &(code, span) = trap_sites.get(srcloc.loc.bits() as usize)?;
+
+            Some(Trap {
+                function,
+                offset: trap.offset,
+                code,
+                span,
+            })
+        })
+        .collect()
+}
+
+/// Declares and defines the read-only data of `program` in `module`.
+fn define_data<M: Module>(
+    module: &mut M,
+    program: &Program,
+) -> Result<HashMap<DataId, module::DataId>, Error> {
+    let mut data = HashMap::new();
+
+    for (id, bytes) in program.data.iter() {
+        let data_id = (module.declare_anonymous_data(false, false)).map_err(module_error)?;
+
+        let mut description = DataDescription::new();
+        description.define(bytes.into());
+
+        (module.define_data(data_id, &description)).map_err(module_error)?;
+        data.insert(id, data_id);
+    }
+
+    Ok(data)
+}
+
+fn translate_function<M: Module>(
+    module: &mut M,
+    program: &Program,
+    functions: &HashMap<FunctionId, FuncId>,
+    data: &HashMap<DataId, module::DataId>,
+    context: &mut Context,
+    function_context: &mut FunctionBuilderContext,
+    function: &ir::Function,
+) -> Result<Vec<(ir::TrapCode, Span)>, Error> {
+    let pointer_type = module.target_config().pointer_type();
+    let signature = program.signatures.get_signature(function.signature);
+    let return_type = program.types.get_type(signature.return_type);
+
+    let translator = FunctionTranslator {
+        builder: FunctionBuilder::new(&mut context.func, function_context),
+        module,
+        program,
+        functions,
+        data,
+        pointer_type,
+        return_types: return_types(&return_type),
+        blocks: HashMap::new(),
+        stack_slots: Vec::new(),
+        variables: HashMap::new(),
+        register_types: HashMap::new(),
+        trap_sites: Vec::new(),
+    };
+
+    translator.translate(function)
+}
+
+struct FunctionTranslator<'a, M: Module> {
+    builder: FunctionBuilder<'a>,
+    module: &'a mut M,
+    program: &'a Program,
+    functions: &'a HashMap<FunctionId, FuncId>,
+    data: &'a HashMap<DataId, module::DataId>,
+    pointer_type: Type,
+    return_types: Vec<MemoryType>,
+    blocks: HashMap<BlockId, Block>,
+    stack_slots: Vec<codegen::ir::StackSlot>,
+    variables: HashMap<(Register, Type), Variable>,
+    register_types: HashMap<Register, MemoryType>,
+    /// The code and span of each [`Instruction::Trap`], indexed by the source location of the trap.
+    trap_sites: Vec<(ir::TrapCode, Span)>,
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, M> {
+    /// Translates `function`, returning its trap sites.
+    fn translate(mut self, function: &ir::Function) -> Result<Vec<(ir::TrapCode, Span)>, Error> {
+        let pointer_size = self.pointer_type.bytes() as u64;
+
+        for (_, allocation) in function.stack.iter() {
+            let ty = self.program.types.get_type(allocation.ty);
+            let size = self.program.types.size_of(ty, pointer_size);
+            let align = self.program.types.align_of(ty, pointer_size);
+
+            let data = StackSlotData::new(
+                StackSlotKind::ExplicitSlot,
+                size as u32,
+                align.trailing_zeros() as u8,
+            );
+
+            let slot = self.builder.create_sized_stack_slot(data);
+            self.stack_slots.push(slot);
+        }
+
+        for &block_id in function.blocks.iter() {
+            let block = self.builder.create_block();
+            self.blocks.insert(block_id, block);
+        }
+
+        let entry = self.blocks[&function.blocks[0]];
+        self.builder.append_block_params_for_function_params(entry);
+        self.builder.switch_to_block(entry);
+
+        let signature = self.program.signatures.get_signature(function.signature);
+        let mut params = self.builder.block_params(entry).to_vec().into_iter();
+
+        if let Some(register) = function.return_pointer {
+            let value = params.next().unwrap();
+            self.def(register, MemoryType::Usize, value);
+        }
+
+        let types = (signature.arguments.iter())
+            .flat_map(|&ty| self.program.types.get_type(ty).abi_types())
+            .collect::<Vec<_>>();
+
+        for ((&register, ty), value) in function.arguments.iter().zip(types).zip(params) {
+            self.def(register, ty, value);
+        }
+
+        // registers are reused with different types in different blocks, so blocks are
+        // translated in control flow order, starting with the register types of a predecessor
+        let mut queue = VecDeque::from([function.blocks[0]]);
+        let mut entry_types = HashMap::from([(function.blocks[0], self.register_types.clone())]);
+
+        while let Some(block_id) = queue.pop_front() {
+            let block = self.program.blocks.get(block_id).unwrap();
+
+            self.builder.switch_to_block(self.blocks[&block_id]);
+            self.register_types = entry_types[&block_id].clone();
+            self.translate_block(block)?;
+
+            let terminator = block.instructions().iter().find(|ins| ins.is_terminator());
+
+            for successor in terminator.map(Instruction::successors).unwrap_or_default() {
+                if let Entry::Vacant(entry) = entry_types.entry(successor) {
+                    entry.insert(self.register_types.clone());
+                    queue.push_back(successor);
+                }
+            }
+        }
+
+        // blocks that can't be reached still need a terminator, their registers may never be
+        // defined so they aren't translated
+        for &block_id in function.blocks.iter() {
+            if !entry_types.contains_key(&block_id) {
+                self.builder.switch_to_block(self.blocks[&block_id]);
+                self.builder.ins().trap(UNREACHABLE_TRAP);
+            }
+        }
+
+        self.builder.seal_all_blocks();
+        self.builder.finalize();
+
+        Ok(self.trap_sites)
+    }
+
+    fn translate_block(&mut self, block: &ir::Block) -> Result<(), Error> {
+        for instruction in block.instructions() {
+            if self.translate_instruction(instruction)? {
+                return Ok(());
+            }
+        }
+
+        // blocks must end with a terminator, if the compiler failed to emit one we trap
+        self.builder.ins().trap(UNREACHABLE_TRAP);
+
+        Ok(())
+    }
+
+    fn variable(&mut self, register: Register, ty: Type) -> Variable {
+        let next = Variable::new(self.variables.len());
+
+        *self.variables.entry((register, ty)).or_insert_with(|| {
+            self.builder.declare_var(next, ty);
+            next
+        })
+    }
+
+    fn def(&mut self, register: Register, ty: MemoryType, value: Value) {
+        let variable = self.variable(register, memory_type(ty, self.pointer_type));
+        self.builder.def_var(variable, value);
+        self.register_types.insert(register, ty);
+    }
+
+    /// Gets the type `register` was defined with by the instruction reaching the current block.
+    fn register_type(&self, register: Register) -> Result<MemoryType, Error> {
+        let ty = self.register_types.get(&register).copied();
+        ty.ok_or_else(|| Error::new(format!("{:?} used before it was defined", register)))
+    }
+
+    fn use_register(&mut self, register: Register) -> Result<Value, Error> {
+        let ty = self.register_type(register)?;
+        let variable = self.variable(register, memory_type(ty, self.pointer_type));
+        Ok(self.builder.use_var(variable))
+    }
+
+    /// Uses `register` converted to `ty`.
+    fn use_register_as(&mut self, register: Register, ty: MemoryType) -> Result<Value, Error> {
+        let from = self.register_type(register)?;
+        let value = self.use_register(register)?;
+
+        let from_type = memory_type(from, self.pointer_type);
+        let to_type = memory_type(ty, self.pointer_type);
+
+        Ok(if from_type.bits() > to_type.bits() {
+            self.builder.ins().ireduce(to_type, value)
+        } else if from_type.bits() < to_type.bits() && from.is_signed() {
+            self.builder.ins().sextend(to_type, value)
+        } else if from_type.bits() < to_type.bits() {
+            self.builder.ins().uextend(to_type, value)
+        } else {
+            value
+        })
+    }
+
+    fn translate_binary(
+        &mut self,
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+        f: impl FnOnce(&mut FunctionBuilder, MemoryType, Value, Value) -> Value,
+    ) -> Result<(), Error> {
+        let ty = self.register_type(lhs)?;
+        let lhs = self.use_register(lhs)?;
+        let rhs = self.use_register_as(rhs, ty)?;
+
+        let value = f(&mut self.builder, ty, lhs, rhs);
+        self.def(dst, ty, value);
+
+        Ok(())
+    }
+
+    /// Converts the float `src` to the integer type `ty`, saturating like
+    /// [`Instruction::FloatToInt`].
+    fn float_to_int(&mut self, src: Register, ty: MemoryType) -> Result<Value, Error> {
+        let value = self.use_register(src)?;
+        let bits = memory_type(ty, self.pointer_type).bits();
+
+        // convert to 64 bits, and clamp to the range of smaller types
+        let value = if ty.is_signed() {
+            let value = self.builder.ins().fcvt_to_sint_sat(types::I64, value);
+
+            if bits < 64 {
+                let max = (u64::MAX >> (65 - bits)) as i64;
+                let max = self.builder.ins().iconst(types::I64, max);
+                let min = self.builder.ins().iconst(types::I64, !0i64 << (bits - 1));
+                let value = self.builder.ins().smin(value, max);
+                self.builder.ins().smax(value, min)
+            } else {
+                value
+            }
+        } else {
+            let value = self.builder.ins().fcvt_to_uint_sat(types::I64, value);
+
+            if bits < 64 {
+                let max = u64::MAX >> (64 - bits);
+                let max = self.builder.ins().iconst(types::I64, max as i64);
+                self.builder.ins().umin(value, max)
+            } else {
+                value
+            }
+        };
+
+        if bits < 64 {
+            let ty = memory_type(ty, self.pointer_type);
+            Ok(self.builder.ins().ireduce(ty, value))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Translates `instruction`, returns true if `instruction` is a terminator.
+    fn translate_instruction(&mut self, instruction: &Instruction) -> Result<bool, Error> {
+        match *instruction {
+            Instruction::Noop {} => {}
+            Instruction::IConst { dst, imm, ty } => {
+                let cranelift_type = memory_type(ty, self.pointer_type);
+                let mask = u64::MAX >> (64 - cranelift_type.bits());
+                let imm = u64::from(imm) & mask;
+
+                let value = self.builder.ins().iconst(cranelift_type, imm as i64);
+                self.def(dst, ty, value);
+            }
+            Instruction::FConst { dst, imm, ty } => {
+                let value = match ty {
+                    MemoryType::F32 => self.builder.ins().f32const(f32::from(imm)),
+                    _ => self.builder.ins().f64const(f64::from(imm)),
+                };
+
+                self.def(dst, ty, value);
+            }
+            Instruction::Call {
+                ref dsts,
+                function,
+                ref arguments,
+            } => {
+                let func_id = self.functions[&function];
+                let func_ref = (self.module).declare_func_in_func(func_id, self.builder.func);
+
+                let callee = self.program.functions.get(function).unwrap();
+                let signature = self.program.signatures.get_signature(callee.signature);
+
+                let mut values = Vec::with_capacity(arguments.len());
+                let mut arguments = arguments.iter();
+
+                if callee.return_pointer.is_some() {
+                    let argument = *arguments.next().unwrap();
+                    values.push(self.use_register_as(argument, MemoryType::Usize)?);
+                }
+
+                let types = (signature.arguments.iter())
+                    .flat_map(|&ty| self.program.types.get_type(ty).abi_types())
+                    .collect::<Vec<_>>();
+
+                for (&argument, ty) in arguments.zip(types) {
+                    values.push(self.use_register_as(argument, ty)?);
+                }
+
+                let call = self.builder.ins().call(func_ref, &values);
+
+                let return_type = self.program.types.get_type(signature.return_type);
+                let results = self.builder.inst_results(call).to_vec();
+
+                for ((&dst, ty), value) in dsts.iter().zip(return_types(&return_type)).zip(results)
+                {
+                    self.def(dst, ty, value);
+                }
+            }
+            Instruction::Jump { block } => {
+                let block = self.blocks[&block];
+                self.builder.ins().jump(block, &[]);
+
+                return Ok(true);
+            }
+            Instruction::Return { ref srcs } => {
+                let mut values = Vec::with_capacity(srcs.len());
+
+                for (&src, ty) in srcs.iter().zip(self.return_types.clone()) {
+                    values.push(self.use_register_as(src, ty)?);
+                }
+
+                self.builder.ins().return_(&values);
+
+                return Ok(true);
+            }
+            Instruction::Branch {
+                condition,
+                then_block,
+                else_block,
+            } => {
+                let condition = self.use_register(condition)?;
+                let then_block = self.blocks[&then_block];
+                let else_block = self.blocks[&else_block];
+
+                (self.builder.ins()).brif(condition, then_block, &[], else_block, &[]);
+
+                return Ok(true);
+            }
+            Instruction::Unreachable {} => {
+                self.builder.ins().trap(UNREACHABLE_TRAP);
+
+                return Ok(true);
+            }
+            Instruction::Trap { code, span } => {
+                let cranelift_code = match code {
+                    ir::TrapCode::IndexOutOfBounds => INDEX_OUT_OF_BOUNDS_TRAP,
+                    ir::TrapCode::DivisionByZero => TrapCode::INTEGER_DIVISION_BY_ZERO,
+                    ir::TrapCode::DivisionOverflow => TrapCode::INTEGER_OVERFLOW,
+                };
+
+                // the location of the trap is its index in the trap sites, to find its span later
+                let site = codegen::ir::SourceLoc::new(self.trap_sites.len() as u32);
+                self.trap_sites.push((code, span));
+
+                (self.builder).set_srcloc(site);
+                self.builder.ins().trap(cranelift_code);
+                (self.builder).set_srcloc(codegen::ir::SourceLoc::default());
+
+                return Ok(true);
+            }
+            Instruction::Add { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().iadd(lhs, rhs))?;
+            }
+            Instruction::Sub { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().isub(lhs, rhs))?;
+            }
+            Instruction::Mul { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().imul(lhs, rhs))?;
+            }
+            Instruction::Div { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, ty, lhs, rhs| {
+                    if ty.is_signed() {
+                        b.ins().sdiv(lhs, rhs)
+                    } else {
+                        b.ins().udiv(lhs, rhs)
+                    }
+                })?;
+            }
+            Instruction::Rem { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, ty, lhs, rhs| {
+                    if ty.is_signed() {
+                        b.ins().srem(lhs, rhs)
+                    } else {
+                        b.ins().urem(lhs, rhs)
+                    }
+                })?;
+            }
+            Instruction::And { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().band(lhs, rhs))?;
+            }
+            Instruction::Or { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().bor(lhs, rhs))?;
+            }
+            Instruction::Xor { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().bxor(lhs, rhs))?;
+            }
+            Instruction::Shl { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().ishl(lhs, rhs))?;
+            }
+            Instruction::Shr { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, ty, lhs, rhs| {
+                    if ty.is_signed() {
+                        b.ins().sshr(lhs, rhs)
+                    } else {
+                        b.ins().ushr(lhs, rhs)
+                    }
+                })?;
+            }
+            Instruction::FNeg { dst, src } => {
+                let ty = self.register_type(src)?;
+                let value = self.use_register(src)?;
+
+                let value = self.builder.ins().fneg(value);
+                self.def(dst, ty, value);
+            }
+            Instruction::FAdd { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().fadd(lhs, rhs))?;
+            }
+            Instruction::FSub { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().fsub(lhs, rhs))?;
+            }
+            Instruction::FMul { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().fmul(lhs, rhs))?;
+            }
+            Instruction::FDiv { dst, lhs, rhs } => {
+                self.translate_binary(dst, lhs, rhs, |b, _, lhs, rhs| b.ins().fdiv(lhs, rhs))?;
+            }
+            Instruction::ICmp {
+                dst,
+                comparison,
+                lhs,
+                rhs,
+            } => {
+                let ty = self.register_type(lhs)?;
+                let lhs = self.use_register(lhs)?;
+                let rhs = self.use_register_as(rhs, ty)?;
+
+                // comparisons produce an i8 of 0 or 1, which is already a bool
+                let value = self.builder.ins().icmp(int_cc(comparison), lhs, rhs);
+                self.def(dst, MemoryType::Bool, value);
+            }
+            Instruction::FCmp {
+                dst,
+                comparison,
+                lhs,
+                rhs,
+            } => {
+                let lhs = self.use_register(lhs)?;
+                let rhs = self.use_register(rhs)?;
+
+                let value = self.builder.ins().fcmp(float_cc(comparison), lhs, rhs);
+                self.def(dst, MemoryType::Bool, value);
+            }
+            Instruction::IConvert { dst, src, ty } => {
+                let value = self.use_register_as(src, ty)?;
+                self.def(dst, ty, value);
+            }
+            Instruction::FConvert { dst, src, ty } => {
+                let from = self.register_type(src)?;
+                let value = self.use_register(src)?;
+                let cranelift_type = memory_type(ty, self.pointer_type);
+
+                let value = match (from, ty) {
+                    (MemoryType::F32, MemoryType::F64) => {
+                        self.builder.ins().fpromote(cranelift_type, value)
+                    }
+                    (MemoryType::F64, MemoryType::F32) => {
+                        self.builder.ins().fdemote(cranelift_type, value)
+                    }
+                    _ => value,
+                };
+
+                self.def(dst, ty, value);
+            }
+            Instruction::IntToFloat { dst, src, ty } => {
+                let cranelift_type = memory_type(ty, self.pointer_type);
+
+                // extend to 64 bits first, not every backend converts smaller integers
+                let value = if self.register_type(src)?.is_signed() {
+                    let value = self.use_register_as(src, MemoryType::I64)?;
+                    self.builder.ins().fcvt_from_sint(cranelift_type, value)
+                } else {
+                    let value = self.use_register_as(src, MemoryType::U64)?;
+                    self.builder.ins().fcvt_from_uint(cranelift_type, value)
+                };
+
+                self.def(dst, ty, value);
+            }
+            Instruction::FloatToInt { dst, src, ty } => {
+                let value = self.float_to_int(src, ty)?;
+                self.def(dst, ty, value);
+            }
+            Instruction::FuncAddr { dst, function, ty } => {
+                let func_id = self.functions[&function];
+                let func_ref = (self.module).declare_func_in_func(func_id, self.builder.func);
+
+                let cranelift_type = memory_type(ty, self.pointer_type);
+                let value = self.builder.ins().func_addr(cranelift_type, func_ref);
+                self.def(dst, ty, value);
+            }
+            Instruction::DataAddr { dst, data, ty } => {
+                let data_id = self.data[&data];
+                let global = (self.module).declare_data_in_func(data_id, self.builder.func);
+
+                let cranelift_type = memory_type(ty, self.pointer_type);
+                let value = self.builder.ins().symbol_value(cranelift_type, global);
+                self.def(dst, ty, value);
+            }
+            Instruction::StackLoad {
+                dst,
+                slot,
+                ty,
+                offset,
+            } => {
+                let cranelift_type = memory_type(ty, self.pointer_type);
+                let slot = self.stack_slots[slot.to_usize()];
+
+                let value = (self.builder.ins()).stack_load(cranelift_type, slot, offset as i32);
+                self.def(dst, ty, value);
+            }
+            Instruction::StackStore {
+                src,
+                slot,
+                ty,
+                offset,
+            } => {
+                let value = self.use_register_as(src, ty)?;
+                let slot = self.stack_slots[slot.to_usize()];
+
+                self.builder.ins().stack_store(value, slot, offset as i32);
+            }
+            Instruction::StackAddr { dst, slot, ty } => {
+                let cranelift_type = memory_type(ty, self.pointer_type);
+                let slot = self.stack_slots[slot.to_usize()];
+
+                let value = self.builder.ins().stack_addr(cranelift_type, slot, 0);
+                self.def(dst, ty, value);
+            }
+            Instruction::Load {
+                dst,
+                src,
+                ty,
+                offset,
+            } => {
+                let cranelift_type = memory_type(ty, self.pointer_type);
+                let address = self.use_register_as(src, MemoryType::Usize)?;
+
+                let value = self.builder.ins().load(
+                    cranelift_type,
+                    MemFlags::new(),
+                    address,
+                    offset as i32,
+                );
+                self.def(dst, ty, value);
+            }
+            Instruction::Store {
+                dst,
+                src,
+                ty,
+                offset,
+            } => {
+                let address = self.use_register_as(dst, MemoryType::Usize)?;
+                let value = self.use_register_as(src, ty)?;
+
+                (self.builder.ins()).store(MemFlags::new(), value, address, offset as i32);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fern::test_support::{compile, try_run};
+
+    use crate::Jit;
+
+    #[test]
+    fn bools() {
+        let program = compile(
+            "
+            struct Flags {
+                a: bool,
+                b: bool,
+                count: i32,
+            }
+
+            fn flags(a: bool, b: bool) -> Flags {
+                return Flags { a: !a, b: !b, count: a as i32 + b as i32 };
+            }
+
+            fn xor(a: bool, b: bool) -> bool {
+                let flags = flags(a, b);
+                return !flags.a ^ !flags.b != false && flags.count < 2;
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let xor = unsafe { jit.function::<extern "C" fn(bool, bool) -> bool>("xor") }.unwrap();
+
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(xor(a, b), a ^ b, "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn arrays() {
+        let program = compile(
+            "
+            fn squares() -> [i64; 5] {
+                let mut values = [0i64; 5];
+                let mut i = 0;
+
+                while i < 5 {
+                    values[i] = (i * i) as i64;
+                    i += 1;
+                }
+
+                return values;
+            }
+
+            fn sum(index: u8) -> i64 {
+                let values = [squares(), [1, 2, 3, 4, 5]];
+                let mut total = 0i64;
+                let mut i = 0;
+
+                while i < 5 {
+                    total += values[0][i] * values[1][i];
+                    i += 1;
+                }
+
+                return total + values[1][index];
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let sum = unsafe { jit.function::<extern "C" fn(u8) -> i64>("sum") }.unwrap();
+
+        // 0 * 1 + 1 * 2 + 4 * 3 + 9 * 4 + 16 * 5
+        assert_eq!(sum(0), 130 + 1);
+        assert_eq!(sum(4), 130 + 5);
+    }
+
+    #[test]
+    fn slices() {
+        let program = compile(
+            "
+            fn sum(values: &[i64], start: usize) -> i64 {
+                let values = values[start..];
+                let mut total = 0i64;
+                let mut i: usize = 0;
+
+                while i < values.len() {
+                    total += values[i];
+                    i += 1;
+                }
+
+                return total;
+            }
+
+            fn middle() -> i64 {
+                let values = [1i64, 2, 3, 4];
+                return sum(values[1..3], 0) * 10 + sum(&values, 3);
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+        let sum = unsafe { jit.function::<extern "C" fn(*const i64, usize, usize) -> i64>("sum") };
+        let sum = sum.unwrap();
+        let middle = unsafe { jit.function::<extern "C" fn() -> i64>("middle") }.unwrap();
+
+        let values = [10i64, 20, 30];
+
+        assert_eq!(sum(values.as_ptr(), values.len(), 0), 60);
+        assert_eq!(sum(values.as_ptr(), values.len(), 2), 30);
+        assert_eq!(middle(), 50 + 4);
+    }
+
+    #[test]
+    fn trap_errors() {
+        let program = compile(
+            "
+            fn get(values: &[u8], index: usize) -> u8 {
+                values[index]
+            }
+
+            fn main() -> u8 {
+                let values = [1u8, 2, 3];
+                get(&values, 1) + get(values[1..], 2)
+            }
+            ",
+        );
+        let jit = Jit::compile(&program).unwrap();
+
+        let errors: Vec<_> = jit.traps().map(|(_, error)| error).collect();
+        assert!(errors
+            .iter()
+            .all(|error| error.message() == "Index out of bounds"));
+
+        // the trap of the failing check has the error the interpreter reports
+        let error = try_run(&program, "main", &[]).unwrap_err();
+        assert!(errors.iter().any(|trap| trap.span() == error.span()));
+    }
+}
@@ -0,0 +1,111 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use fern::error::Error;
+
+/// The reports written by the trap handler, by the address of the trap instruction.
+static REPORTS: OnceLock<HashMap<usize, String>> = OnceLock::new();
+
+/// Installs a handler for traps in compiled code, which writes the report for the address of the
+/// trap instruction in `reports` to stderr and exits with status 1.
+///
+/// Traps at addresses without a report are left to the default action. Reports can only be
+/// registered once, later calls return an error.
+#[cfg(unix)]
+pub fn report_traps(reports: HashMap<usize, String>) -> Result<(), Error> {
+    register(reports)?;
+
+    // SAFETY: the handler only reads the reports, which are never modified once set
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handle_trap as *const () as libc::sighandler_t;
+        action.sa_flags = libc::SA_SIGINFO;
+        libc::sigemptyset(&mut action.sa_mask);
+
+        if libc::sigaction(libc::SIGILL, &action, std::ptr::null_mut()) != 0 {
+            return Err(Error::new("Failed to install the trap handler"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Traps are left to the default action on targets without a trap handler.
+#[cfg(not(unix))]
+pub fn report_traps(reports: HashMap<usize, String>) -> Result<(), Error> {
+    register(reports)?;
+    Ok(())
+}
+
+fn register(reports: HashMap<usize, String>) -> Result<(), Error> {
+    (REPORTS.set(reports)).map_err(|_| Error::new("Trap reports are already registered"))
+}
+
+#[cfg(unix)]
+extern "C" fn handle_trap(
+    signal: libc::c_int,
+    _: *mut libc::siginfo_t,
+    context: *mut libc::c_void,
+) {
+    // SAFETY: the context passed to a `SA_SIGINFO` handler is a `ucontext_t`
+    let address = unsafe { trap_address(context) };
+    let report = address.and_then(|address| REPORTS.get()?.get(&address));
+
+    match report {
+        Some(report) => {
+            let mut bytes = report.as_bytes();
+
+            while !bytes.is_empty() {
+                // SAFETY: `write` and `_exit` are async signal safe
+                let written = unsafe { libc::write(2, bytes.as_ptr().cast(), bytes.len()) };
+
+                if written <= 0 {
+                    break;
+                }
+
+                bytes = &bytes[written as usize..];
+            }
+
+            unsafe { libc::_exit(1) };
+        }
+        // returning retries the trap, which then takes the default action
+        None => unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+        },
+    }
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+unsafe fn trap_address(context: *mut libc::c_void) -> Option<usize> {
+    let context = &*(context as *const libc::ucontext_t);
+    Some(context.uc_mcontext.gregs[libc::REG_RIP as usize] as usize)
+}
+
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+unsafe fn trap_address(context: *mut libc::c_void) -> Option<usize> {
+    let context = &*(context as *const libc::ucontext_t);
+    Some(context.uc_mcontext.pc as usize)
+}
+
+#[cfg(all(
+    unix,
+    not(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))
+))]
+unsafe fn trap_address(_: *mut libc::c_void) -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_once() {
+        assert!(report_traps(HashMap::new()).is_ok());
+
+        let error = report_traps(HashMap::new()).unwrap_err();
+        assert_eq!(error.message(), "Trap reports are already registered");
+    }
+}
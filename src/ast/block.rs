@@ -1,4 +1,7 @@
-use crate::ast;
+use crate::{
+    ast,
+    span::{Span, Spanned},
+};
 
 #[derive(Clone, Debug)]
 pub struct Block {
@@ -11,4 +14,29 @@ impl Block {
     pub fn iter(&self) -> impl Iterator<Item = &ast::Statement> {
         self.statements.iter()
     }
+
+    /// Returns the trailing expression without a `;`, which is the value of the block.
+    pub fn tail(&self) -> Option<&ast::Expression> {
+        match self.statements.last() {
+            Some(ast::Statement::Expression(ast::ExpressionStatement {
+                expression,
+                semi_colon: None,
+            })) => Some(expression),
+            _ => None,
+        }
+    }
+
+    /// Returns the statements of the block, without [`Block::tail`].
+    pub fn body(&self) -> &[ast::Statement] {
+        match self.tail() {
+            Some(_) => &self.statements[..self.statements.len() - 1],
+            None => &self.statements,
+        }
+    }
+}
+
+impl Spanned for Block {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
 }
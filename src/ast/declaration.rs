@@ -1,4 +1,23 @@
-use crate::ast;
+use crate::{
+    ast,
+    source::SourceId,
+    span::{Span, Spanned},
+};
+
+/// The `///` comments before a declaration.
+#[derive(Clone, Debug)]
+pub struct DocComment {
+    /// Every line of the comment, without the `///` and the space following it.
+    pub lines: Vec<String>,
+    pub span: Span,
+}
+
+impl DocComment {
+    /// Gets the lines joined by newlines.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct ReturnType {
@@ -8,12 +27,15 @@ pub struct ReturnType {
 
 #[derive(Clone, Debug)]
 pub struct FunctionArgument {
+    pub mutability: Option<ast::Mut>,
     pub ident: ast::Ident,
     pub ty: ast::TypeDeclaration,
 }
 
 #[derive(Clone, Debug)]
 pub struct FunctionDeclaration {
+    pub doc: Option<DocComment>,
+    pub visibility: Option<ast::Pub>,
     pub _fn: ast::Fn,
     pub ident: ast::Ident,
     pub open: ast::OpenParen,
@@ -23,7 +45,236 @@ pub struct FunctionDeclaration {
     pub block: ast::Block,
 }
 
+#[derive(Clone, Debug)]
+pub struct StructField {
+    pub doc: Option<DocComment>,
+    pub ident: ast::Ident,
+    pub ty: ast::TypeDeclaration,
+}
+
+#[derive(Clone, Debug)]
+pub struct StructDeclaration {
+    pub doc: Option<DocComment>,
+    pub visibility: Option<ast::Pub>,
+    pub _struct: ast::Struct,
+    pub ident: ast::Ident,
+    pub open: ast::OpenBrace,
+    pub fields: ast::Punctuated<ast::StructField, ast::Comma>,
+    pub close: ast::CloseBrace,
+}
+
+#[derive(Clone, Debug)]
+pub struct InlineModule {
+    pub open: ast::OpenBrace,
+    pub declarations: Vec<ast::Declaration>,
+    pub close: ast::CloseBrace,
+}
+
+#[derive(Clone, Debug)]
+pub struct FileModule {
+    pub semi_colon: ast::SemiColon,
+    /// The source the module was loaded from, [`None`] until the file is loaded.
+    pub source: Option<SourceId>,
+    pub declarations: Vec<ast::Declaration>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ModuleBody {
+    /// `mod name { .. }`
+    Inline(InlineModule),
+    /// `mod name;`, with the declarations in `name.fn` or `name/mod.fn`.
+    File(FileModule),
+}
+
+#[derive(Clone, Debug)]
+pub struct ModuleDeclaration {
+    pub doc: Option<DocComment>,
+    pub visibility: Option<ast::Pub>,
+    pub _mod: ast::Mod,
+    pub ident: ast::Ident,
+    pub body: ModuleBody,
+}
+
+impl ModuleDeclaration {
+    /// Gets the declarations of the module, or [`None`] if the module is a file that isn't
+    /// loaded.
+    pub fn declarations(&self) -> Option<&[ast::Declaration]> {
+        match self.body {
+            ModuleBody::Inline(ref module) => Some(&module.declarations),
+            ModuleBody::File(ref module) => module.source.map(|_| module.declarations.as_slice()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UseAlias {
+    pub _as: ast::As,
+    pub ident: ast::Ident,
+}
+
+#[derive(Clone, Debug)]
+pub enum UseTree {
+    /// `name` or `name as alias`.
+    Name {
+        segment: ast::PathSegment,
+        alias: Option<UseAlias>,
+    },
+    /// `segment::tree`.
+    Path {
+        segment: ast::PathSegment,
+        colon_colon: ast::ColonColon,
+        tree: Box<UseTree>,
+    },
+    /// `*`, importing every visible item of a module.
+    Glob(ast::Asterisk),
+    /// `{ tree, .. }`.
+    Group {
+        open: ast::OpenBrace,
+        trees: ast::Punctuated<UseTree, ast::Comma>,
+        close: ast::CloseBrace,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct UseDeclaration {
+    pub visibility: Option<ast::Pub>,
+    pub _use: ast::Use,
+    pub absolute: Option<ast::ColonColon>,
+    pub tree: UseTree,
+    pub semi_colon: ast::SemiColon,
+}
+
 #[derive(Clone, Debug)]
 pub enum Declaration {
     Function(FunctionDeclaration),
+    Struct(StructDeclaration),
+    Module(ModuleDeclaration),
+    Use(UseDeclaration),
+    /// A declaration that failed to parse, the error is recorded by the parser.
+    Error(Span),
+}
+
+/// Joins the span of an optional `pub` with the span of the rest of a declaration.
+fn with_visibility(visibility: &Option<ast::Pub>, span: Span) -> Span {
+    visibility
+        .as_ref()
+        .map_or(span, |visibility| visibility.span() | span)
+}
+
+impl Spanned for DocComment {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Spanned for ReturnType {
+    fn span(&self) -> Span {
+        self.arrow.span() | self.ty.span()
+    }
+}
+
+impl Spanned for FunctionArgument {
+    fn span(&self) -> Span {
+        let span = self.ident.span() | self.ty.span();
+
+        match self.mutability {
+            Some(ref mutability) => mutability.span() | span,
+            None => span,
+        }
+    }
+}
+
+impl Spanned for FunctionDeclaration {
+    fn span(&self) -> Span {
+        with_visibility(&self.visibility, self._fn.span() | self.block.span())
+    }
+}
+
+impl FunctionDeclaration {
+    /// The span of the signature of the function, without the body.
+    pub fn signature_span(&self) -> Span {
+        let end = match self.return_type {
+            Some(ref return_type) => return_type.span(),
+            None => self.close.span(),
+        };
+
+        with_visibility(&self.visibility, self._fn.span() | end)
+    }
+}
+
+impl Spanned for StructField {
+    fn span(&self) -> Span {
+        self.ident.span() | self.ty.span()
+    }
+}
+
+impl Spanned for StructDeclaration {
+    fn span(&self) -> Span {
+        with_visibility(&self.visibility, self._struct.span() | self.close.span())
+    }
+}
+
+impl Spanned for InlineModule {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
+}
+
+impl Spanned for FileModule {
+    fn span(&self) -> Span {
+        self.semi_colon.span()
+    }
+}
+
+impl Spanned for ModuleBody {
+    fn span(&self) -> Span {
+        match self {
+            Self::Inline(module) => module.span(),
+            Self::File(module) => module.span(),
+        }
+    }
+}
+
+impl Spanned for ModuleDeclaration {
+    fn span(&self) -> Span {
+        with_visibility(&self.visibility, self._mod.span() | self.body.span())
+    }
+}
+
+impl Spanned for UseAlias {
+    fn span(&self) -> Span {
+        self._as.span() | self.ident.span()
+    }
+}
+
+impl Spanned for UseTree {
+    fn span(&self) -> Span {
+        match self {
+            Self::Name { segment, alias } => match alias {
+                Some(alias) => segment.span() | alias.span(),
+                None => segment.span(),
+            },
+            Self::Path { segment, tree, .. } => segment.span() | tree.span(),
+            Self::Glob(asterisk) => asterisk.span(),
+            Self::Group { open, close, .. } => open.span() | close.span(),
+        }
+    }
+}
+
+impl Spanned for UseDeclaration {
+    fn span(&self) -> Span {
+        with_visibility(&self.visibility, self._use.span() | self.semi_colon.span())
+    }
+}
+
+impl Spanned for Declaration {
+    fn span(&self) -> Span {
+        match self {
+            Self::Function(declaration) => declaration.span(),
+            Self::Struct(declaration) => declaration.span(),
+            Self::Module(declaration) => declaration.span(),
+            Self::Use(declaration) => declaration.span(),
+            Self::Error(span) => *span,
+        }
+    }
 }
@@ -1,9 +1,14 @@
-use crate::ast;
+use crate::{
+    ast,
+    span::{Span, Spanned},
+};
 
 #[derive(Clone, Debug)]
 pub enum LiteralExpression {
     String(ast::StringLiteral),
     Integer(ast::IntegerLiteral),
+    Float(ast::FloatLiteral),
+    Bool(ast::BoolLiteral),
 }
 
 #[derive(Clone, Debug)]
@@ -17,15 +22,72 @@ pub struct ParenExpression {
 pub struct CallExpression {
     pub function: Box<ast::Expression>,
     pub open: ast::OpenParen,
-    pub arguments: ast::Punctuated<ast::Expression, ast::Colon>,
+    pub arguments: ast::Punctuated<ast::Expression, ast::Comma>,
     pub close: ast::CloseParen,
 }
 
+#[derive(Clone, Debug)]
+pub struct FieldInitializer {
+    pub ident: ast::Ident,
+    pub colon: ast::Colon,
+    pub expression: ast::Expression,
+}
+
+#[derive(Clone, Debug)]
+pub struct StructExpression {
+    pub path: ast::Path,
+    pub open: ast::OpenBrace,
+    pub fields: ast::Punctuated<ast::FieldInitializer, ast::Comma>,
+    pub close: ast::CloseBrace,
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexExpression {
+    pub expression: Box<ast::Expression>,
+    pub open: ast::OpenBracket,
+    pub index: Box<ast::Expression>,
+    pub close: ast::CloseBracket,
+}
+
+/// A range `start..end`, both bounds are optional.
+#[derive(Clone, Debug)]
+pub struct RangeExpression {
+    pub start: Option<Box<ast::Expression>>,
+    pub dot_dot: ast::DotDot,
+    pub end: Option<Box<ast::Expression>>,
+}
+
+/// An array of the listed elements, `[a, b, c]`.
+#[derive(Clone, Debug)]
+pub struct ArrayExpression {
+    pub open: ast::OpenBracket,
+    pub elements: ast::Punctuated<ast::Expression, ast::Comma>,
+    pub close: ast::CloseBracket,
+}
+
+/// An array of `length` copies of `value`, `[x; N]`.
+#[derive(Clone, Debug)]
+pub struct ArrayRepeatExpression {
+    pub open: ast::OpenBracket,
+    pub value: Box<ast::Expression>,
+    pub semi_colon: ast::SemiColon,
+    pub length: ast::IntegerLiteral,
+    pub close: ast::CloseBracket,
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldExpression {
+    pub expression: Box<ast::Expression>,
+    pub dot: ast::Dot,
+    pub ident: ast::Ident,
+}
+
 #[derive(Clone, Debug)]
 pub enum UnaryOperator {
     Reference(ast::And),
     Dereference(ast::Asterisk),
     Negate(ast::Minus),
+    Not(ast::Bang),
 }
 
 #[derive(Clone, Debug)]
@@ -34,30 +96,48 @@ pub struct UnaryExpression {
     pub expression: Box<ast::Expression>,
 }
 
+/// `expression as ty`, converting between numeric types.
+#[derive(Clone, Debug)]
+pub struct CastExpression {
+    pub expression: Box<ast::Expression>,
+    pub _as: ast::As,
+    pub ty: ast::Type,
+}
+
 #[derive(Clone, Debug)]
 pub enum BinaryOperator {
     Add(ast::Plus),
     Sub(ast::Minus),
     Mul(ast::Asterisk),
     Div(ast::Slash),
+    Rem(ast::Percent),
     LogicalAnd(ast::AndAnd),
     LogicalOr(ast::OrOr),
     BinaryAnd(ast::And),
     BinaryOr(ast::Or),
+    BinaryXor(ast::Caret),
     BitShiftRight(ast::ShiftRight),
     BitShiftLeft(ast::ShiftLeft),
+    Equal(ast::EqualEqual),
+    NotEqual(ast::BangEqual),
+    LessThan(ast::Less),
+    LessThanOrEqual(ast::LessEqual),
+    GreaterThan(ast::Greater),
+    GreaterThanOrEqual(ast::GreaterEqual),
 }
 
 impl BinaryOperator {
-    pub fn precedence(&self) -> u32 {
-        match self {
-            Self::Mul(_) | Self::Div(_) => 12,
-            Self::Add(_) | Self::Sub(_) => 11,
-            Self::BitShiftLeft(_) | Self::BitShiftRight(_) => 10,
-            Self::LogicalAnd(_) => 4,
-            Self::LogicalOr(_) => 3,
-            _ => 0,
-        }
+    /// Whether the operator compares its operands, producing a `bool`.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Equal(_)
+                | Self::NotEqual(_)
+                | Self::LessThan(_)
+                | Self::LessThanOrEqual(_)
+                | Self::GreaterThan(_)
+                | Self::GreaterThanOrEqual(_)
+        )
     }
 }
 
@@ -68,19 +148,396 @@ pub struct BinaryExpression {
     pub rhs: Box<ast::Expression>,
 }
 
+#[derive(Clone, Debug)]
+pub enum AssignOperator {
+    Assign(ast::Equal),
+    Add(ast::PlusEqual),
+    Sub(ast::MinusEqual),
+    Mul(ast::AsteriskEqual),
+    Div(ast::SlashEqual),
+    Rem(ast::PercentEqual),
+    BinaryAnd(ast::AndEqual),
+    BinaryOr(ast::OrEqual),
+    BinaryXor(ast::CaretEqual),
+    BitShiftLeft(ast::ShiftLeftEqual),
+    BitShiftRight(ast::ShiftRightEqual),
+}
+
+impl AssignOperator {
+    /// Gets the operator applied before assigning, `+` for `+=`, [`None`] for `=`.
+    pub fn binary_operator(&self) -> Option<ast::BinaryOperator> {
+        let span = self.span();
+
+        Some(match self {
+            Self::Assign(_) => return None,
+            Self::Add(_) => ast::BinaryOperator::Add(ast::Plus::new(span)),
+            Self::Sub(_) => ast::BinaryOperator::Sub(ast::Minus::new(span)),
+            Self::Mul(_) => ast::BinaryOperator::Mul(ast::Asterisk::new(span)),
+            Self::Div(_) => ast::BinaryOperator::Div(ast::Slash::new(span)),
+            Self::Rem(_) => ast::BinaryOperator::Rem(ast::Percent::new(span)),
+            Self::BinaryAnd(_) => ast::BinaryOperator::BinaryAnd(ast::And::new(span)),
+            Self::BinaryOr(_) => ast::BinaryOperator::BinaryOr(ast::Or::new(span)),
+            Self::BinaryXor(_) => ast::BinaryOperator::BinaryXor(ast::Caret::new(span)),
+            Self::BitShiftLeft(_) => ast::BinaryOperator::BitShiftLeft(ast::ShiftLeft::new(span)),
+            Self::BitShiftRight(_) => {
+                ast::BinaryOperator::BitShiftRight(ast::ShiftRight::new(span))
+            }
+        })
+    }
+}
+
+/// `lhs = rhs`, or a compound assignment like `lhs += rhs`.
+#[derive(Clone, Debug)]
+pub struct AssignExpression {
+    pub lhs: Box<ast::Expression>,
+    pub operator: ast::AssignOperator,
+    pub rhs: Box<ast::Expression>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ReturnExpression {
     pub _return: ast::Return,
     pub expression: Box<ast::Expression>,
 }
 
+#[derive(Clone, Debug)]
+pub enum ElseBody {
+    If(Box<ast::IfExpression>),
+    Block(ast::Block),
+}
+
+#[derive(Clone, Debug)]
+pub struct ElseBranch {
+    pub _else: ast::Else,
+    pub body: ast::ElseBody,
+}
+
+#[derive(Clone, Debug)]
+pub struct IfExpression {
+    pub _if: ast::If,
+    pub condition: Box<ast::Expression>,
+    pub block: ast::Block,
+    pub else_branch: Option<ast::ElseBranch>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoopLabel {
+    pub label: ast::Label,
+    pub colon: ast::Colon,
+}
+
+#[derive(Clone, Debug)]
+pub struct WhileExpression {
+    pub label: Option<ast::LoopLabel>,
+    pub _while: ast::While,
+    pub condition: Box<ast::Expression>,
+    pub block: ast::Block,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoopExpression {
+    pub label: Option<ast::LoopLabel>,
+    pub _loop: ast::Loop,
+    pub block: ast::Block,
+}
+
+#[derive(Clone, Debug)]
+pub struct BreakExpression {
+    pub _break: ast::Break,
+    pub label: Option<ast::Label>,
+    pub expression: Option<Box<ast::Expression>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ContinueExpression {
+    pub _continue: ast::Continue,
+    pub label: Option<ast::Label>,
+}
+
 #[derive(Clone, Debug)]
 pub enum Expression {
     Paren(ast::ParenExpression),
+    Block(ast::Block),
     Literal(ast::LiteralExpression),
     Path(ast::Path),
     Call(ast::CallExpression),
+    Struct(ast::StructExpression),
+    Field(ast::FieldExpression),
+    Index(ast::IndexExpression),
+    Array(ast::ArrayExpression),
+    ArrayRepeat(ast::ArrayRepeatExpression),
+    Range(ast::RangeExpression),
     Unary(ast::UnaryExpression),
+    Cast(ast::CastExpression),
     Binary(ast::BinaryExpression),
+    Assign(ast::AssignExpression),
     Return(ast::ReturnExpression),
+    If(ast::IfExpression),
+    While(ast::WhileExpression),
+    Loop(ast::LoopExpression),
+    Break(ast::BreakExpression),
+    Continue(ast::ContinueExpression),
+}
+
+impl Expression {
+    /// Returns true if `self` ends with a block, and can be used as a statement without a `;`.
+    pub fn is_block_like(&self) -> bool {
+        matches!(
+            self,
+            Self::Block(_) | Self::If(_) | Self::While(_) | Self::Loop(_)
+        )
+    }
+}
+
+impl Spanned for LiteralExpression {
+    fn span(&self) -> Span {
+        match self {
+            Self::String(literal) => literal.span(),
+            Self::Integer(literal) => literal.span(),
+            Self::Float(literal) => literal.span(),
+            Self::Bool(literal) => literal.span(),
+        }
+    }
+}
+
+impl Spanned for ParenExpression {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
+}
+
+impl Spanned for CallExpression {
+    fn span(&self) -> Span {
+        self.function.span() | self.close.span()
+    }
+}
+
+impl Spanned for FieldInitializer {
+    fn span(&self) -> Span {
+        self.ident.span() | self.expression.span()
+    }
+}
+
+impl Spanned for StructExpression {
+    fn span(&self) -> Span {
+        self.path.span() | self.close.span()
+    }
+}
+
+impl Spanned for IndexExpression {
+    fn span(&self) -> Span {
+        self.expression.span() | self.close.span()
+    }
+}
+
+impl Spanned for RangeExpression {
+    fn span(&self) -> Span {
+        let start = (self.start.as_ref()).map_or(self.dot_dot.span(), |start| start.span());
+        let end = (self.end.as_ref()).map_or(self.dot_dot.span(), |end| end.span());
+
+        start | end
+    }
+}
+
+impl Spanned for ArrayExpression {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
+}
+
+impl Spanned for ArrayRepeatExpression {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
+}
+
+impl Spanned for FieldExpression {
+    fn span(&self) -> Span {
+        self.expression.span() | self.ident.span()
+    }
+}
+
+impl Spanned for UnaryOperator {
+    fn span(&self) -> Span {
+        match self {
+            Self::Reference(operator) => operator.span(),
+            Self::Dereference(operator) => operator.span(),
+            Self::Negate(operator) => operator.span(),
+            Self::Not(operator) => operator.span(),
+        }
+    }
+}
+
+impl Spanned for UnaryExpression {
+    fn span(&self) -> Span {
+        self.operator.span() | self.expression.span()
+    }
+}
+
+impl Spanned for CastExpression {
+    fn span(&self) -> Span {
+        self.expression.span() | self.ty.span()
+    }
+}
+
+impl Spanned for BinaryOperator {
+    fn span(&self) -> Span {
+        match self {
+            Self::Add(operator) => operator.span(),
+            Self::Sub(operator) => operator.span(),
+            Self::Mul(operator) => operator.span(),
+            Self::Div(operator) => operator.span(),
+            Self::Rem(operator) => operator.span(),
+            Self::LogicalAnd(operator) => operator.span(),
+            Self::LogicalOr(operator) => operator.span(),
+            Self::BinaryAnd(operator) => operator.span(),
+            Self::BinaryOr(operator) => operator.span(),
+            Self::BinaryXor(operator) => operator.span(),
+            Self::BitShiftRight(operator) => operator.span(),
+            Self::BitShiftLeft(operator) => operator.span(),
+            Self::Equal(operator) => operator.span(),
+            Self::NotEqual(operator) => operator.span(),
+            Self::LessThan(operator) => operator.span(),
+            Self::LessThanOrEqual(operator) => operator.span(),
+            Self::GreaterThan(operator) => operator.span(),
+            Self::GreaterThanOrEqual(operator) => operator.span(),
+        }
+    }
+}
+
+impl Spanned for BinaryExpression {
+    fn span(&self) -> Span {
+        self.lhs.span() | self.rhs.span()
+    }
+}
+
+impl Spanned for AssignOperator {
+    fn span(&self) -> Span {
+        match self {
+            Self::Assign(operator) => operator.span(),
+            Self::Add(operator) => operator.span(),
+            Self::Sub(operator) => operator.span(),
+            Self::Mul(operator) => operator.span(),
+            Self::Div(operator) => operator.span(),
+            Self::Rem(operator) => operator.span(),
+            Self::BinaryAnd(operator) => operator.span(),
+            Self::BinaryOr(operator) => operator.span(),
+            Self::BinaryXor(operator) => operator.span(),
+            Self::BitShiftLeft(operator) => operator.span(),
+            Self::BitShiftRight(operator) => operator.span(),
+        }
+    }
+}
+
+impl Spanned for AssignExpression {
+    fn span(&self) -> Span {
+        self.lhs.span() | self.rhs.span()
+    }
+}
+
+impl Spanned for ReturnExpression {
+    fn span(&self) -> Span {
+        self._return.span() | self.expression.span()
+    }
+}
+
+impl Spanned for ElseBody {
+    fn span(&self) -> Span {
+        match self {
+            Self::If(expression) => expression.span(),
+            Self::Block(block) => block.span(),
+        }
+    }
+}
+
+impl Spanned for ElseBranch {
+    fn span(&self) -> Span {
+        self._else.span() | self.body.span()
+    }
+}
+
+impl Spanned for IfExpression {
+    fn span(&self) -> Span {
+        match self.else_branch {
+            Some(ref else_branch) => self._if.span() | else_branch.span(),
+            None => self._if.span() | self.block.span(),
+        }
+    }
+}
+
+impl Spanned for LoopLabel {
+    fn span(&self) -> Span {
+        self.label.span() | self.colon.span()
+    }
+}
+
+impl Spanned for WhileExpression {
+    fn span(&self) -> Span {
+        match self.label {
+            Some(ref label) => label.span() | self.block.span(),
+            None => self._while.span() | self.block.span(),
+        }
+    }
+}
+
+impl Spanned for LoopExpression {
+    fn span(&self) -> Span {
+        match self.label {
+            Some(ref label) => label.span() | self.block.span(),
+            None => self._loop.span() | self.block.span(),
+        }
+    }
+}
+
+impl Spanned for BreakExpression {
+    fn span(&self) -> Span {
+        let mut span = self._break.span();
+
+        if let Some(ref label) = self.label {
+            span |= label.span();
+        }
+
+        if let Some(ref expression) = self.expression {
+            span |= expression.span();
+        }
+
+        span
+    }
+}
+
+impl Spanned for ContinueExpression {
+    fn span(&self) -> Span {
+        match self.label {
+            Some(ref label) => self._continue.span() | label.span(),
+            None => self._continue.span(),
+        }
+    }
+}
+
+impl Spanned for Expression {
+    fn span(&self) -> Span {
+        match self {
+            Self::Paren(expression) => expression.span(),
+            Self::Block(block) => block.span(),
+            Self::Literal(expression) => expression.span(),
+            Self::Path(expression) => expression.span(),
+            Self::Call(expression) => expression.span(),
+            Self::Struct(expression) => expression.span(),
+            Self::Field(expression) => expression.span(),
+            Self::Index(expression) => expression.span(),
+            Self::Array(expression) => expression.span(),
+            Self::ArrayRepeat(expression) => expression.span(),
+            Self::Range(expression) => expression.span(),
+            Self::Unary(expression) => expression.span(),
+            Self::Cast(expression) => expression.span(),
+            Self::Binary(expression) => expression.span(),
+            Self::Assign(expression) => expression.span(),
+            Self::Return(expression) => expression.span(),
+            Self::If(expression) => expression.span(),
+            Self::While(expression) => expression.span(),
+            Self::Loop(expression) => expression.span(),
+            Self::Break(expression) => expression.span(),
+            Self::Continue(expression) => expression.span(),
+        }
+    }
 }
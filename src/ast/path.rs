@@ -1,17 +1,29 @@
 use std::fmt::Display;
 
-use crate::ast;
+use crate::{
+    ast,
+    span::{Span, Spanned},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum PathSegment {
-    Super,
+    Super(ast::Super),
     Ident(ast::Ident),
 }
 
+impl Spanned for PathSegment {
+    fn span(&self) -> Span {
+        match self {
+            Self::Super(_super) => _super.span(),
+            Self::Ident(ident) => ident.span(),
+        }
+    }
+}
+
 impl Display for PathSegment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            PathSegment::Super => write!(f, "super"),
+            PathSegment::Super(_) => write!(f, "super"),
             PathSegment::Ident(ident) => write!(f, "{}", ident),
         }
     }
@@ -43,6 +55,20 @@ impl Display for Path {
     }
 }
 
+impl Spanned for Path {
+    fn span(&self) -> Span {
+        let last = self.segments.last().map(Spanned::span);
+
+        match self.absolute {
+            Some(ref absolute) => last.map_or(absolute.span(), |last| absolute.span() | last),
+            None => match self.segments.first() {
+                Some(first) => first.span() | last.unwrap(),
+                None => Span::null(),
+            },
+        }
+    }
+}
+
 impl Path {
     pub const fn empty() -> Self {
         Self {
@@ -23,6 +23,14 @@ impl<T, U> Punctuated<T, U> {
         self.items.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.items.first()
+    }
+
     pub fn last(&self) -> Option<&T> {
         self.items.last()
     }
@@ -36,6 +44,12 @@ impl<T, U> Punctuated<T, U> {
     }
 }
 
+impl<T, U> Default for Punctuated<T, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, U> Index<usize> for Punctuated<T, U> {
     type Output = T;
 
@@ -1,14 +1,18 @@
-use crate::ast;
+use crate::{
+    ast,
+    span::{Span, Spanned},
+};
 
 #[derive(Clone, Debug)]
 pub struct LetStatementValue {
     pub equal: ast::Equal,
-    pub expression: ast::Expression,
+    pub expression: Box<ast::Expression>,
 }
 
 #[derive(Clone, Debug)]
 pub struct LetStatement {
     pub _let: ast::Let,
+    pub mutability: Option<ast::Mut>,
     pub ident: ast::Ident,
     pub ty: Option<ast::TypeDeclaration>,
     pub value: Option<ast::LetStatementValue>,
@@ -18,7 +22,8 @@ pub struct LetStatement {
 #[derive(Clone, Debug)]
 pub struct ExpressionStatement {
     pub expression: ast::Expression,
-    pub semi_colon: ast::SemiColon,
+    /// Only optional if [`ast::Expression::is_block_like`] or if this is the tail of a block.
+    pub semi_colon: Option<ast::SemiColon>,
 }
 
 #[derive(Clone, Debug)]
@@ -26,4 +31,38 @@ pub enum Statement {
     Noop(ast::SemiColon),
     Expression(ast::ExpressionStatement),
     Let(ast::LetStatement),
+    /// A statement that failed to parse, the error is recorded by the parser.
+    Error(Span),
+}
+
+impl Spanned for LetStatementValue {
+    fn span(&self) -> Span {
+        self.equal.span() | self.expression.span()
+    }
+}
+
+impl Spanned for LetStatement {
+    fn span(&self) -> Span {
+        self._let.span() | self.semi_colon.span()
+    }
+}
+
+impl Spanned for ExpressionStatement {
+    fn span(&self) -> Span {
+        match self.semi_colon {
+            Some(ref semi_colon) => self.expression.span() | semi_colon.span(),
+            None => self.expression.span(),
+        }
+    }
+}
+
+impl Spanned for Statement {
+    fn span(&self) -> Span {
+        match self {
+            Self::Noop(semi_colon) => semi_colon.span(),
+            Self::Expression(statement) => statement.span(),
+            Self::Let(statement) => statement.span(),
+            Self::Error(span) => *span,
+        }
+    }
 }
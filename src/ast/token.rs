@@ -8,8 +8,10 @@ use crate::{
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Token {
     Ident(String),
+    Label(String),
     String(String),
     Integer(Integer),
+    Float(Float),
     Keyword(Keyword),
     Symbol(Symbol),
     Eof,
@@ -19,8 +21,10 @@ impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Ident(ident) => write!(f, "'{}'", ident),
-            Self::String(string) => write!(f, "\"{}\"", string),
+            Self::Label(label) => write!(f, "''{}'", label),
+            Self::String(string) => write!(f, "{:?}", string),
             Self::Integer(integer) => write!(f, "'{}'", integer),
+            Self::Float(float) => write!(f, "'{}'", float),
             Self::Keyword(keyword) => write!(f, "'{}'", keyword),
             Self::Symbol(symbol) => write!(f, "'{}'", symbol),
             Self::Eof => write!(f, "end of file"),
@@ -45,32 +49,111 @@ impl IntegerKind {
     }
 }
 
+/// The type suffix of a number literal, e.g. `u8` in `5u8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NumberSuffix {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    Usize,
+    F32,
+    F64,
+}
+
+impl NumberSuffix {
+    pub fn from_string(string: &str) -> Option<Self> {
+        Some(match string {
+            "i8" => Self::I8,
+            "u8" => Self::U8,
+            "i16" => Self::I16,
+            "u16" => Self::U16,
+            "i32" => Self::I32,
+            "u32" => Self::U32,
+            "i64" => Self::I64,
+            "u64" => Self::U64,
+            "usize" => Self::Usize,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            _ => return None,
+        })
+    }
+
+    pub const fn is_float(&self) -> bool {
+        matches!(self, Self::F32 | Self::F64)
+    }
+}
+
+impl Display for NumberSuffix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suffix = match self {
+            Self::I8 => "i8",
+            Self::U8 => "u8",
+            Self::I16 => "i16",
+            Self::U16 => "u16",
+            Self::I32 => "i32",
+            Self::U32 => "u32",
+            Self::I64 => "i64",
+            Self::U64 => "u64",
+            Self::Usize => "usize",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+        };
+
+        write!(f, "{}", suffix)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Integer {
-    value: i64,
+    value: u64,
     kind: IntegerKind,
+    /// An integer suffix, float suffixes make the literal a [`Float`].
+    suffix: Option<NumberSuffix>,
 }
 
 impl Integer {
-    pub const fn new(value: i64, kind: IntegerKind) -> Self {
-        Self { value, kind }
+    pub const fn new(value: u64, kind: IntegerKind) -> Self {
+        Self {
+            value,
+            kind,
+            suffix: None,
+        }
     }
 
-    pub const fn value(&self) -> i64 {
+    pub const fn with_suffix(mut self, suffix: Option<NumberSuffix>) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    pub const fn value(&self) -> u64 {
         self.value
     }
 
     pub const fn kind(&self) -> IntegerKind {
         self.kind
     }
+
+    pub const fn suffix(&self) -> Option<NumberSuffix> {
+        self.suffix
+    }
 }
 
 impl Display for Integer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.kind {
-            IntegerKind::Binary => write!(f, "0b{:b}", self.value),
-            IntegerKind::Decimal => write!(f, "{}", self.value),
-            IntegerKind::Hex => write!(f, "0x{:x}", self.value),
+            IntegerKind::Binary => write!(f, "0b{:b}", self.value)?,
+            IntegerKind::Decimal => write!(f, "{}", self.value)?,
+            IntegerKind::Hex => write!(f, "0x{:x}", self.value)?,
+        }
+
+        match self.suffix {
+            Some(suffix) => write!(f, "{}", suffix),
+            None => Ok(()),
         }
     }
 }
@@ -113,7 +196,7 @@ impl crate::parse::Parse for Ident {
 
         match parser.next_token()? {
             Token::Ident(string) => Ok(Self { string, span }),
-            tok => Err(Error::expected(Expected::String, tok, span)),
+            tok => Err(Error::expected(Expected::Ident, tok, span)),
         }
     }
 }
@@ -138,6 +221,112 @@ impl Hash for Ident {
     }
 }
 
+/// A loop label, `'label`.
+#[derive(Clone)]
+pub struct Label {
+    string: String,
+    span: Span,
+}
+
+impl Label {
+    pub fn new(string: impl Into<String>, span: Span) -> Self {
+        Self {
+            string: string.into(),
+            span,
+        }
+    }
+
+    /// The name of the label, without the leading `'`.
+    pub fn string(&self) -> &String {
+        &self.string
+    }
+}
+
+impl std::fmt::Debug for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Label({})", self.string)
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}", self.string)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl crate::parse::Parse for Label {
+    fn parse(parser: &mut crate::parse::Parser) -> Result<Self, Error> {
+        let span = parser.next_span()?;
+
+        match parser.next_token()? {
+            Token::Label(string) => Ok(Self { string, span }),
+            tok => Err(Error::expected(Expected::Label, tok, span)),
+        }
+    }
+}
+
+impl Spanned for Label {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl PartialEq for Label {
+    fn eq(&self, other: &Self) -> bool {
+        self.string.eq(&other.string)
+    }
+}
+
+impl Eq for Label {}
+
+impl Hash for Label {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.string.hash(state);
+    }
+}
+
+/// A float stored as its bit pattern, so tokens can be compared and hashed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Float {
+    bits: u64,
+    /// A float suffix.
+    suffix: Option<NumberSuffix>,
+}
+
+impl Float {
+    pub fn new(value: f64) -> Self {
+        Self {
+            bits: value.to_bits(),
+            suffix: None,
+        }
+    }
+
+    pub const fn with_suffix(mut self, suffix: Option<NumberSuffix>) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    pub fn value(&self) -> f64 {
+        f64::from_bits(self.bits)
+    }
+
+    pub const fn suffix(&self) -> Option<NumberSuffix> {
+        self.suffix
+    }
+}
+
+impl Display for Float {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value())?;
+
+        match self.suffix {
+            Some(suffix) => write!(f, "{}", suffix),
+            None => Ok(()),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct IntegerLiteral {
     integer: Integer,
@@ -163,7 +352,7 @@ impl crate::parse::Parse for IntegerLiteral {
 
         match parser.next_token()? {
             Token::Integer(integer) => Ok(Self { integer, span }),
-            tok => Err(Error::expected(Expected::String, tok, span)),
+            tok => Err(Error::expected(Expected::Integer, tok, span)),
         }
     }
 }
@@ -174,6 +363,42 @@ impl Spanned for IntegerLiteral {
     }
 }
 
+#[derive(Clone)]
+pub struct FloatLiteral {
+    float: Float,
+    span: Span,
+}
+
+impl FloatLiteral {
+    pub fn float(&self) -> Float {
+        self.float
+    }
+}
+
+impl std::fmt::Debug for FloatLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.float)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl crate::parse::Parse for FloatLiteral {
+    fn parse(parser: &mut crate::parse::Parser) -> Result<Self, Error> {
+        let span = parser.next_span()?;
+
+        match parser.next_token()? {
+            Token::Float(float) => Ok(Self { float, span }),
+            tok => Err(Error::expected(Expected::Float, tok, span)),
+        }
+    }
+}
+
+impl Spanned for FloatLiteral {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
 #[derive(Clone)]
 pub struct StringLiteral {
     string: String,
@@ -210,12 +435,62 @@ impl Spanned for StringLiteral {
     }
 }
 
+#[derive(Clone)]
+pub struct BoolLiteral {
+    value: bool,
+    span: Span,
+}
+
+impl BoolLiteral {
+    pub fn value(&self) -> bool {
+        self.value
+    }
+}
+
+impl std::fmt::Debug for BoolLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl crate::parse::Parse for BoolLiteral {
+    fn parse(parser: &mut crate::parse::Parser) -> Result<Self, Error> {
+        let span = parser.next_span()?;
+
+        match parser.next_token()? {
+            Token::Keyword(Keyword::True) => Ok(Self { value: true, span }),
+            Token::Keyword(Keyword::False) => Ok(Self { value: false, span }),
+            tok => Err(Error::expected_any(
+                &[
+                    Expected::Keyword(Keyword::True),
+                    Expected::Keyword(Keyword::False),
+                ],
+                tok,
+                span,
+            )),
+        }
+    }
+}
+
+impl Spanned for BoolLiteral {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
 macro_rules! symbols {
-    ($($first:literal $(, $second:literal)? => $ident:ident),* $(,)?) => {
+    ($($first:literal $(, $second:literal $(, $third:literal)?)? => $ident:ident),* $(,)?) => {
         $(
             #[derive(Clone, Copy)]
             pub struct $ident(Span);
 
+            impl $ident {
+                pub const fn new(span: Span) -> Self {
+                    Self(span)
+                }
+            }
+
             impl Default for $ident {
                 fn default() -> Self {
                     Self(Span::null())
@@ -237,22 +512,17 @@ macro_rules! symbols {
             #[cfg(feature = "parse")]
             impl $crate::parse::Parse for $ident {
                 fn parse(parser: &mut $crate::parse::Parser) -> Result<Self, Error> {
-                    let span = parser.span();
+                    let span = parser.next_span()?;
 
                     match parser.next_token()? {
-                        Token::Symbol(Symbol::$ident) => {
-                            let span = span | parser.span();
-
-                            Ok(Self(span))
-                        },
+                        Token::Symbol(Symbol::$ident) => Ok(Self(span)),
                         tok => {
-                            let span = span | parser.span();
 
                             #[allow(unused_mut)]
                             let mut symbol = String::from($first);
-                            $(symbol.push($second);)?
+                            $(symbol.push($second); $(symbol.push($third);)?)?
 
-                            Err(Error::spanned(format!("Expected symbol '{}' found '{}'", symbol, tok), span))
+                            Err(Error::spanned(format!("Expected symbol '{}' found {}", symbol, tok), span))
                         },
                     }
                 }
@@ -285,7 +555,7 @@ macro_rules! symbols {
                         Self::$ident => {
                             #[allow(unused_mut)]
                             let mut symbol = String::from($first);
-                            $(symbol.push($second);)?
+                            $(symbol.push($second); $(symbol.push($third);)?)?
 
                             write!(f, "{}", symbol)
                         }
@@ -294,12 +564,17 @@ macro_rules! symbols {
             }
         }
 
-        pub(crate) fn symbol_from_chars(first: char, second: Option<char>) -> Option<(Symbol, bool)> {
+        /// Gets the symbol starting with `first`, and the number of following chars it uses.
+        pub(crate) fn symbol_from_chars(
+            first: char,
+            second: Option<char>,
+            third: Option<char>,
+        ) -> Option<(Symbol, usize)> {
             match first {
                 $(
-                    $first $(if second == Some($second))? => Some((
+                    $first $(if second == Some($second) $(&& third == Some($third))?)? => Some((
                         Symbol::$ident,
-                        false $(|| { let _ = $second; true })?
+                        0 $(+ { let _ = $second; 1 } $(+ { let _ = $third; 1 })?)?
                     )),
                 )*
                 _ => None,
@@ -335,18 +610,13 @@ macro_rules! keywords {
             #[cfg(feature = "parse")]
             impl $crate::parse::Parse for $ident {
                 fn parse(parser: &mut $crate::parse::Parser) -> Result<Self, Error> {
-                    let span = parser.span();
+                    let span = parser.next_span()?;
 
                     match parser.next_token()? {
-                        Token::Keyword(Keyword::$ident) => {
-                            let span = span | parser.span();
-
-                            Ok(Self(span))
-                        },
+                        Token::Keyword(Keyword::$ident) => Ok(Self(span)),
                         tok => {
-                            let span = span | parser.span();
 
-                            Err(Error::spanned(format!("Expected keyword '{}' found '{}'", $keyword, tok), span))
+                            Err(Error::spanned(format!("Expected keyword '{}' found {}", $keyword, tok), span))
                         },
                     }
                 }
@@ -394,13 +664,28 @@ macro_rules! keywords {
 }
 
 symbols! {
+    '>', '>', '=' => ShiftRightEqual,
+    '<', '<', '=' => ShiftLeftEqual,
     '>', '>' => ShiftRight,
     '<', '<' => ShiftLeft,
+    '>', '=' => GreaterEqual,
+    '<', '=' => LessEqual,
+    '=', '=' => EqualEqual,
+    '!', '=' => BangEqual,
+    '+', '=' => PlusEqual,
+    '-', '=' => MinusEqual,
+    '*', '=' => AsteriskEqual,
+    '/', '=' => SlashEqual,
+    '%', '=' => PercentEqual,
+    '^', '=' => CaretEqual,
+    '&', '=' => AndEqual,
+    '|', '=' => OrEqual,
     '-', '>' => Arrow,
     '=', '>' => FatArrow,
     '&', '&' => AndAnd,
     '|', '|' => OrOr,
     ':', ':' => ColonColon,
+    '.', '.' => DotDot,
     '(' => OpenParen,
     ')' => CloseParen,
     '[' => OpenBracket,
@@ -411,6 +696,11 @@ symbols! {
     '-' => Minus,
     '*' => Asterisk,
     '/' => Slash,
+    '%' => Percent,
+    '^' => Caret,
+    '!' => Bang,
+    '<' => Less,
+    '>' => Greater,
     '&' => And,
     '|' => Or,
     '=' => Equal,
@@ -423,9 +713,23 @@ symbols! {
 keywords! {
     "fn" => Fn,
     "let" => Let,
+    "mut" => Mut,
+    "struct" => Struct,
+    "mod" => Mod,
+    "super" => Super,
+    "pub" => Pub,
+    "use" => Use,
+    "as" => As,
     "return" => Return,
+    "if" => If,
+    "else" => Else,
+    "while" => While,
+    "loop" => Loop,
+    "break" => Break,
+    "continue" => Continue,
     "void" => Void,
     "bool" => Bool,
+    "str" => Str,
     "true" => True,
     "false" => False,
     "i8" => I8,
@@ -436,4 +740,7 @@ keywords! {
     "u32" => U32,
     "i64" => I64,
     "u64" => U64,
+    "usize" => Usize,
+    "f32" => F32,
+    "f64" => F64,
 }
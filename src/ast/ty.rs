@@ -1,4 +1,7 @@
-use crate::ast;
+use crate::{
+    ast,
+    span::{Span, Spanned},
+};
 
 #[derive(Clone, Debug)]
 pub enum IntegerType {
@@ -10,6 +13,13 @@ pub enum IntegerType {
     U32(ast::U32),
     I64(ast::I64),
     U64(ast::U64),
+    Usize(ast::Usize),
+}
+
+#[derive(Clone, Debug)]
+pub enum FloatType {
+    F32(ast::F32),
+    F64(ast::F64),
 }
 
 #[derive(Clone, Debug)]
@@ -18,13 +28,35 @@ pub struct ReferenceType {
     pub ty: Box<ast::Type>,
 }
 
+/// A fixed-size array, `[T; N]`.
+#[derive(Clone, Debug)]
+pub struct ArrayType {
+    pub open: ast::OpenBracket,
+    pub ty: Box<ast::Type>,
+    pub semi_colon: ast::SemiColon,
+    pub length: ast::IntegerLiteral,
+    pub close: ast::CloseBracket,
+}
+
+/// The elements of a slice, `[T]`, only valid behind a reference.
+#[derive(Clone, Debug)]
+pub struct SliceType {
+    pub open: ast::OpenBracket,
+    pub ty: Box<ast::Type>,
+    pub close: ast::CloseBracket,
+}
+
 #[derive(Clone, Debug)]
 pub enum Type {
     Void(ast::Void),
     Boolean(ast::Bool),
+    Str(ast::Str),
     Integer(ast::IntegerType),
+    Float(ast::FloatType),
     Path(ast::Path),
     Reference(ast::ReferenceType),
+    Array(Box<ast::ArrayType>),
+    Slice(ast::SliceType),
 }
 
 #[derive(Clone, Debug)]
@@ -32,3 +64,68 @@ pub struct TypeDeclaration {
     pub colon: ast::Colon,
     pub ty: ast::Type,
 }
+
+impl Spanned for IntegerType {
+    fn span(&self) -> Span {
+        match self {
+            Self::I8(ty) => ty.span(),
+            Self::U8(ty) => ty.span(),
+            Self::I16(ty) => ty.span(),
+            Self::U16(ty) => ty.span(),
+            Self::I32(ty) => ty.span(),
+            Self::U32(ty) => ty.span(),
+            Self::I64(ty) => ty.span(),
+            Self::U64(ty) => ty.span(),
+            Self::Usize(ty) => ty.span(),
+        }
+    }
+}
+
+impl Spanned for FloatType {
+    fn span(&self) -> Span {
+        match self {
+            Self::F32(ty) => ty.span(),
+            Self::F64(ty) => ty.span(),
+        }
+    }
+}
+
+impl Spanned for ReferenceType {
+    fn span(&self) -> Span {
+        self.and.span() | self.ty.span()
+    }
+}
+
+impl Spanned for ArrayType {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
+}
+
+impl Spanned for SliceType {
+    fn span(&self) -> Span {
+        self.open.span() | self.close.span()
+    }
+}
+
+impl Spanned for Type {
+    fn span(&self) -> Span {
+        match self {
+            Self::Void(ty) => ty.span(),
+            Self::Boolean(ty) => ty.span(),
+            Self::Str(ty) => ty.span(),
+            Self::Integer(ty) => ty.span(),
+            Self::Float(ty) => ty.span(),
+            Self::Path(ty) => ty.span(),
+            Self::Reference(ty) => ty.span(),
+            Self::Array(ty) => ty.span(),
+            Self::Slice(ty) => ty.span(),
+        }
+    }
+}
+
+impl Spanned for TypeDeclaration {
+    fn span(&self) -> Span {
+        self.colon.span() | self.ty.span()
+    }
+}
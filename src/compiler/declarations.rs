@@ -2,27 +2,148 @@ use std::collections::HashMap;
 
 use crate::{
     ast,
+    diagnostic::Diagnostics,
     error::Error,
-    id::{FunctionId, FunctionIds, ModuleId, ModuleIds, TypeId},
+    id::{FunctionId, FunctionIds, ModuleId, ModuleIds, StructId, StructIds, TypeId},
     ir::MemoryType,
+    span::{Span, Spanned},
 };
 
-use super::{Type, Types};
+use super::{Struct, StructField, Type, Types};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    /// Only visible in the module it's declared in, and its sub modules.
+    Private,
+    Public,
+}
+
+impl Visibility {
+    pub fn from_ast(visibility: &Option<ast::Pub>) -> Self {
+        match visibility {
+            Some(_) => Self::Public,
+            None => Self::Private,
+        }
+    }
+}
+
+/// A name bound in a module, either by a declaration or an import.
+#[derive(Clone, Copy, Debug)]
+pub struct Binding<T> {
+    pub id: T,
+    pub visibility: Visibility,
+    /// The span of the name in the declaration or import.
+    pub span: Span,
+    /// Bound by a glob import, explicit bindings of the same name replace it.
+    pub glob: bool,
+}
+
+/// A `use` import, resolved by [`Declarations::resolve_imports`].
+#[derive(Clone, Debug)]
+pub struct Import {
+    pub visibility: Visibility,
+    /// The path of the imported item, or of the module for glob imports.
+    pub path: ast::Path,
+    /// The name the item is bound to, [`None`] for glob imports.
+    pub ident: Option<ast::Ident>,
+    pub span: Span,
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct Module {
+    /// The name of the module, [`None`] for the base module.
+    pub ident: Option<ast::Ident>,
     pub super_module: Option<ModuleId>,
-    pub sub_modules: HashMap<ast::Ident, ModuleId>,
-    pub functions: HashMap<ast::Ident, FunctionId>,
+    pub sub_modules: HashMap<ast::Ident, Binding<ModuleId>>,
+    pub functions: HashMap<ast::Ident, Binding<FunctionId>>,
+    pub structs: HashMap<ast::Ident, Binding<StructId>>,
+    pub imports: Vec<Import>,
+}
+
+fn bind<T>(
+    bindings: &mut HashMap<ast::Ident, Binding<T>>,
+    ident: &ast::Ident,
+    binding: Binding<T>,
+    kind: &str,
+) -> Result<(), Error> {
+    if let Some(existing) = bindings.get(ident).filter(|existing| !existing.glob) {
+        return Err(Error::spanned(
+            format!("{} {} declared more than once", kind, ident),
+            binding.span,
+        )
+        .with_hint("first declared here", existing.span));
+    }
+
+    bindings.insert(ident.clone(), binding);
+    Ok(())
+}
+
+/// Flattens `tree` into one [`Import`] per imported name or glob.
+fn flatten_use_tree(
+    visibility: Visibility,
+    mut path: ast::Path,
+    tree: &ast::UseTree,
+    imports: &mut Vec<Import>,
+) -> Result<(), Error> {
+    match tree {
+        ast::UseTree::Name { segment, alias } => {
+            let ident = match segment {
+                ast::PathSegment::Ident(ident) => ident,
+                ast::PathSegment::Super(_super) => {
+                    return Err(Error::spanned("Cannot import super", _super.span()));
+                }
+            };
+
+            let ident = alias.as_ref().map_or(ident, |alias| &alias.ident).clone();
+            path.push_segment(segment.clone());
+
+            imports.push(Import {
+                visibility,
+                path,
+                span: ident.span(),
+                ident: Some(ident),
+            });
+        }
+        ast::UseTree::Path { segment, tree, .. } => {
+            path.push_segment(segment.clone());
+            flatten_use_tree(visibility, path, tree, imports)?;
+        }
+        ast::UseTree::Glob(asterisk) => {
+            // glob paths end in `*`, so the module segments of the path name the module
+            path.push_ident(ast::Ident::new("*", asterisk.span()));
+
+            imports.push(Import {
+                visibility,
+                path,
+                ident: None,
+                span: asterisk.span(),
+            });
+        }
+        ast::UseTree::Group { trees, .. } => {
+            for tree in trees.iter() {
+                flatten_use_tree(visibility, path.clone(), tree, imports)?;
+            }
+        }
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
 pub struct Declarations {
     pub module_ids: ModuleIds,
     pub function_ids: FunctionIds,
+    pub struct_ids: StructIds,
     pub base_module: ModuleId,
     pub modules: HashMap<ModuleId, Module>,
-    pub functions: HashMap<FunctionId, ast::FunctionDeclaration>,
+    pub functions: HashMap<FunctionId, (ModuleId, ast::FunctionDeclaration)>,
+    pub structs: HashMap<StructId, (ModuleId, ast::StructDeclaration)>,
+}
+
+impl Default for Declarations {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Declarations {
@@ -36,26 +157,37 @@ impl Declarations {
         Self {
             module_ids,
             function_ids: Default::default(),
+            struct_ids: Default::default(),
             base_module,
             modules,
             functions: Default::default(),
+            structs: Default::default(),
         }
     }
 
-    pub fn from_program(program: ast::Program) -> Result<Self, Error> {
+    /// Collects the declarations of `program`, reporting every error to `diagnostics`.
+    pub fn from_program(program: ast::Program, diagnostics: &mut Diagnostics) -> Self {
         let mut this = Declarations::new();
 
         for declaration in program.declarations {
-            this.insert_declaration(this.base_module, declaration)?;
+            this.insert_declaration(this.base_module, declaration, diagnostics);
         }
 
-        Ok(this)
+        this.resolve_imports(diagnostics);
+
+        this
     }
 
-    pub fn resolve_type(&self, types: &mut Types, ty: &ast::Type) -> Result<Type, Error> {
+    pub fn resolve_type(
+        &self,
+        types: &mut Types,
+        module: ModuleId,
+        ty: &ast::Type,
+    ) -> Result<Type, Error> {
         match ty {
             ast::Type::Void(_) => Ok(Type::Void),
             ast::Type::Boolean(_) => Ok(Type::Memory(MemoryType::Bool)),
+            ast::Type::Str(_) => Ok(Type::Str),
             ast::Type::Integer(integer) => match integer {
                 ast::IntegerType::I8(_) => Ok(Type::Memory(MemoryType::I8)),
                 ast::IntegerType::U8(_) => Ok(Type::Memory(MemoryType::U8)),
@@ -65,70 +197,741 @@ impl Declarations {
                 ast::IntegerType::U32(_) => Ok(Type::Memory(MemoryType::U32)),
                 ast::IntegerType::I64(_) => Ok(Type::Memory(MemoryType::I64)),
                 ast::IntegerType::U64(_) => Ok(Type::Memory(MemoryType::U64)),
+                ast::IntegerType::Usize(_) => Ok(Type::Memory(MemoryType::Usize)),
+            },
+            ast::Type::Float(float) => match float {
+                ast::FloatType::F32(_) => Ok(Type::Memory(MemoryType::F32)),
+                ast::FloatType::F64(_) => Ok(Type::Memory(MemoryType::F64)),
             },
-            ast::Type::Path(_) => todo!(),
-            ast::Type::Reference(inner) => {
-                let ty = self.resolve_type(types, &inner.ty)?;
-                let id = types.get_type_id(&ty);
-                Ok(Type::Reference(id))
+            ast::Type::Path(path) => Ok(Type::Struct(self.resolve_struct(module, path)?)),
+            ast::Type::Reference(inner) => match *inner.ty {
+                ast::Type::Slice(ref slice) => {
+                    let ty = self.resolve_type(types, module, &slice.ty)?;
+                    Ok(Type::Slice(types.get_type_id(&ty)))
+                }
+                _ => {
+                    let ty = self.resolve_type(types, module, &inner.ty)?;
+                    let id = types.get_type_id(&ty);
+                    Ok(Type::Reference(id))
+                }
+            },
+            ast::Type::Array(array) => {
+                let ty = self.resolve_type(types, module, &array.ty)?;
+
+                Ok(Type::Array {
+                    element: types.get_type_id(&ty),
+                    length: array.length.integer().value(),
+                })
             }
+            ast::Type::Slice(slice) => Err(Error::spanned(
+                "Slices must be behind a reference, like &[T]",
+                slice.span(),
+            )),
         }
     }
 
-    pub fn resolve_type_id(&self, types: &mut Types, ty: &ast::Type) -> Result<TypeId, Error> {
-        let ty = self.resolve_type(types, ty)?;
+    pub fn resolve_type_id(
+        &self,
+        types: &mut Types,
+        module: ModuleId,
+        ty: &ast::Type,
+    ) -> Result<TypeId, Error> {
+        let ty = self.resolve_type(types, module, ty)?;
         Ok(types.get_type_id(&ty))
     }
 
+    pub fn resolve_function(
+        &self,
+        module: ModuleId,
+        path: &ast::Path,
+    ) -> Result<FunctionId, Error> {
+        let module_id = self.canonicalize_module(module, path)?;
+        let ident = path.get_ident();
+
+        match ident.and_then(|ident| self.modules[&module_id].functions.get(ident)) {
+            Some(binding) => {
+                self.check_visible(module, module_id, binding, ident.unwrap(), "Function")?;
+                Ok(binding.id)
+            }
+            None => Err(Error::spanned(format!("{} not defined", path), path.span())),
+        }
+    }
+
+    pub fn resolve_struct(&self, module: ModuleId, path: &ast::Path) -> Result<StructId, Error> {
+        let module_id = self.canonicalize_module(module, path)?;
+        let ident = path.get_ident();
+
+        match ident.and_then(|ident| self.modules[&module_id].structs.get(ident)) {
+            Some(binding) => {
+                self.check_visible(module, module_id, binding, ident.unwrap(), "Struct")?;
+                Ok(binding.id)
+            }
+            None => Err(Error::spanned(
+                format!("Struct {} not defined", path),
+                path.span(),
+            )),
+        }
+    }
+
+    /// Resolves the fields of every struct, and inserts them into `types`.
+    ///
+    /// Fields that fail to resolve are reported to `diagnostics` and left out.
+    pub fn resolve_structs(&self, types: &mut Types, diagnostics: &mut Diagnostics) {
+        let mut ids: Vec<_> = self.structs.keys().copied().collect();
+        ids.sort();
+
+        for &id in ids.iter() {
+            let (module, ref ast) = self.structs[&id];
+            let mut fields: Vec<StructField> = Vec::with_capacity(ast.fields.len());
+
+            for field in ast.fields.iter() {
+                if let Some(other) = fields.iter().find(|other| other.ident == field.ident) {
+                    let message = format!(
+                        "Field {} declared more than once in {}",
+                        field.ident, ast.ident
+                    );
+
+                    diagnostics.push(
+                        Error::spanned(message, field.ident.span())
+                            .with_hint("first declared here", other.ident.span()),
+                    );
+
+                    continue;
+                }
+
+                let ty = self.resolve_type_id(types, module, &field.ty.ty);
+
+                if let Some(ty) = diagnostics.report(ty) {
+                    let ident = field.ident.clone();
+                    fields.push(StructField { ident, ty });
+                }
+            }
+
+            let ident = ast.ident.clone();
+            types.structs.insert(id, Struct { ident, fields });
+        }
+
+        for &id in ids.iter() {
+            diagnostics.report(types.check_recursion(id));
+        }
+    }
+
+    /// Inserts `declaration` into the module `module_id`, reporting errors to `diagnostics`.
     pub fn insert_declaration(
         &mut self,
         module_id: ModuleId,
         declaration: ast::Declaration,
+        diagnostics: &mut Diagnostics,
+    ) {
+        if let Err(error) = self.try_insert_declaration(module_id, declaration, diagnostics) {
+            diagnostics.push(error);
+        }
+    }
+
+    fn try_insert_declaration(
+        &mut self,
+        module_id: ModuleId,
+        declaration: ast::Declaration,
+        diagnostics: &mut Diagnostics,
     ) -> Result<(), Error> {
         let module = self.modules.get_mut(&module_id).unwrap();
 
         match declaration {
             ast::Declaration::Function(function) => {
-                let function_id = self.function_ids.generate();
+                let binding = Binding {
+                    id: self.function_ids.generate(),
+                    visibility: Visibility::from_ast(&function.visibility),
+                    span: function.ident.span(),
+                    glob: false,
+                };
 
-                module.functions.insert(function.ident.clone(), function_id);
-                self.functions.insert(function_id, function);
+                bind(&mut module.functions, &function.ident, binding, "Function")?;
+                self.functions.insert(binding.id, (module_id, function));
+            }
+            ast::Declaration::Module(declaration) => {
+                let binding = Binding {
+                    id: self.module_ids.generate(),
+                    visibility: Visibility::from_ast(&declaration.visibility),
+                    span: declaration.ident.span(),
+                    glob: false,
+                };
+
+                bind(
+                    &mut module.sub_modules,
+                    &declaration.ident,
+                    binding,
+                    "Module",
+                )?;
+
+                let declarations = declaration.declarations().ok_or_else(|| {
+                    Error::spanned(
+                        format!("Module {} was not loaded", declaration.ident),
+                        declaration.ident.span(),
+                    )
+                })?;
+
+                self.modules.insert(
+                    binding.id,
+                    Module {
+                        ident: Some(declaration.ident.clone()),
+                        super_module: Some(module_id),
+                        ..Default::default()
+                    },
+                );
+
+                for declaration in declarations.iter().cloned() {
+                    self.insert_declaration(binding.id, declaration, diagnostics);
+                }
+            }
+            ast::Declaration::Struct(declaration) => {
+                let binding = Binding {
+                    id: self.struct_ids.generate(),
+                    visibility: Visibility::from_ast(&declaration.visibility),
+                    span: declaration.ident.span(),
+                    glob: false,
+                };
+
+                bind(&mut module.structs, &declaration.ident, binding, "Struct")?;
+                self.structs.insert(binding.id, (module_id, declaration));
+            }
+            ast::Declaration::Use(declaration) => {
+                let visibility = Visibility::from_ast(&declaration.visibility);
+
+                let path = match declaration.absolute {
+                    Some(_) => ast::Path::absolute(),
+                    None => ast::Path::empty(),
+                };
+
+                flatten_use_tree(visibility, path, &declaration.tree, &mut module.imports)?;
+            }
+            ast::Declaration::Error(span) => {
+                return Err(Error::spanned("Declaration failed to parse", span));
             }
         }
 
         Ok(())
     }
 
+    /// Resolves every `use` import, binding the imported names in the importing modules.
+    ///
+    /// Imports can depend on names bound by other imports, so they're resolved repeatedly until
+    /// no more progress is made.
+    pub fn resolve_imports(&mut self, diagnostics: &mut Diagnostics) {
+        let mut pending: Vec<(ModuleId, Import)> = Vec::new();
+
+        for (&module_id, module) in self.modules.iter() {
+            let imports = module
+                .imports
+                .iter()
+                .map(|import| (module_id, import.clone()));
+            pending.extend(imports);
+        }
+
+        // named imports take precedence over globs, so resolve them first
+        pending.sort_by_key(|(module_id, import)| (import.ident.is_none(), *module_id));
+
+        loop {
+            let mut progress = false;
+            let mut unresolved = Vec::new();
+
+            for (module_id, import) in pending {
+                let result = match import.ident {
+                    Some(ref ident) => {
+                        self.resolve_import(module_id, &import.path, ident, import.visibility)
+                    }
+                    None => self.resolve_glob(module_id, &import),
+                };
+
+                match result {
+                    Ok(resolved) => {
+                        progress |= resolved;
+
+                        // globs are kept, since later imports can add names to the module
+                        if !resolved || import.ident.is_none() {
+                            unresolved.push((module_id, import));
+                        }
+                    }
+                    // imports that fail are dropped, so they're only reported once
+                    Err(error) => diagnostics.push(error),
+                }
+            }
+
+            pending = unresolved;
+
+            if !progress {
+                break;
+            }
+        }
+
+        for (module_id, import) in pending {
+            let module = match self.find_module(module_id, &import.path) {
+                Ok(module) => module,
+                Err(error) => {
+                    diagnostics.push(error);
+                    continue;
+                }
+            };
+
+            match module {
+                // the first glob to bind a name keeps it, other globs binding it are ambiguous
+                Some(target_id) if import.ident.is_none() => {
+                    diagnostics.extend(self.glob_ambiguities(module_id, target_id, &import));
+                }
+                _ => diagnostics.push(Error::spanned(
+                    format!("Unresolved import {}", import.path),
+                    import.span,
+                )),
+            }
+        }
+    }
+
+    /// Tries to resolve the import of `path` as `ident` in `module_id`, returns false if the
+    /// item isn't bound yet.
+    fn resolve_import(
+        &mut self,
+        module_id: ModuleId,
+        path: &ast::Path,
+        ident: &ast::Ident,
+        visibility: Visibility,
+    ) -> Result<bool, Error> {
+        let target_id = match self.find_module(module_id, path)? {
+            Some(target_id) => target_id,
+            None => return Ok(false),
+        };
+
+        let name = path.get_ident().unwrap();
+        let target = &self.modules[&target_id];
+
+        let function = target.functions.get(name).copied();
+        let structure = target.structs.get(name).copied();
+        let sub_module = target.sub_modules.get(name).copied();
+
+        if function.is_none() && structure.is_none() && sub_module.is_none() {
+            return Ok(false);
+        }
+
+        if let Some(ref binding) = function {
+            self.check_visible(module_id, target_id, binding, name, "Function")?;
+        }
+
+        if let Some(ref binding) = structure {
+            self.check_visible(module_id, target_id, binding, name, "Struct")?;
+        }
+
+        if let Some(ref binding) = sub_module {
+            self.check_visible(module_id, target_id, binding, name, "Module")?;
+        }
+
+        let module = self.modules.get_mut(&module_id).unwrap();
+        let span = ident.span();
+
+        if let Some(binding) = function {
+            let binding = Binding {
+                visibility,
+                span,
+                glob: false,
+                ..binding
+            };
+            bind(&mut module.functions, ident, binding, "Function")?;
+        }
+
+        if let Some(binding) = structure {
+            let binding = Binding {
+                visibility,
+                span,
+                glob: false,
+                ..binding
+            };
+            bind(&mut module.structs, ident, binding, "Struct")?;
+        }
+
+        if let Some(binding) = sub_module {
+            let binding = Binding {
+                visibility,
+                span,
+                glob: false,
+                ..binding
+            };
+            bind(&mut module.sub_modules, ident, binding, "Module")?;
+        }
+
+        Ok(true)
+    }
+
+    /// Binds every name visible from `module_id` in the module of a glob import, that isn't
+    /// already bound, returns true if any names were bound.
+    fn resolve_glob(&mut self, module_id: ModuleId, import: &Import) -> Result<bool, Error> {
+        let target_id = match self.find_module(module_id, &import.path)? {
+            Some(target_id) if target_id != module_id => target_id,
+            _ => return Ok(false),
+        };
+
+        let visible = self.is_visible_from(target_id, module_id);
+
+        let target = &self.modules[&target_id];
+        let module = &self.modules[&module_id];
+
+        let functions = glob_bindings(&target.functions, &module.functions, visible, import);
+        let structs = glob_bindings(&target.structs, &module.structs, visible, import);
+        let sub_modules = glob_bindings(&target.sub_modules, &module.sub_modules, visible, import);
+
+        let progress = !functions.is_empty() || !structs.is_empty() || !sub_modules.is_empty();
+
+        let module = self.modules.get_mut(&module_id).unwrap();
+        module.functions.extend(functions);
+        module.structs.extend(structs);
+        module.sub_modules.extend(sub_modules);
+
+        Ok(progress)
+    }
+
+    /// Gets an error for every name the glob `import` of `target_id` would bind in `module_id`,
+    /// that is already bound by another glob to a different item.
+    fn glob_ambiguities(
+        &self,
+        module_id: ModuleId,
+        target_id: ModuleId,
+        import: &Import,
+    ) -> Vec<Error> {
+        if target_id == module_id {
+            return Vec::new();
+        }
+
+        let visible = self.is_visible_from(target_id, module_id);
+
+        let target = &self.modules[&target_id];
+        let module = &self.modules[&module_id];
+
+        let mut errors = glob_conflicts(&target.functions, &module.functions, visible, import);
+        errors.extend(glob_conflicts(
+            &target.structs,
+            &module.structs,
+            visible,
+            import,
+        ));
+        errors.extend(glob_conflicts(
+            &target.sub_modules,
+            &module.sub_modules,
+            visible,
+            import,
+        ));
+
+        errors
+            .into_iter()
+            .map(|(ident, existing)| {
+                Error::spanned(
+                    format!("{} is imported by more than one glob", ident),
+                    import.span,
+                )
+                .with_hint(format!("{} imported here", ident), existing)
+                .with_hint("and here", import.span)
+            })
+            .collect()
+    }
+
+    /// Returns true if items private to `module` are visible from `from`, which is the case when
+    /// `from` is `module` or one of its sub modules.
+    pub fn is_visible_from(&self, module: ModuleId, mut from: ModuleId) -> bool {
+        loop {
+            if from == module {
+                return true;
+            }
+
+            match self.modules[&from].super_module {
+                Some(super_module) => from = super_module,
+                None => return false,
+            }
+        }
+    }
+
+    /// Checks that `binding` named `ident` in `module` can be accessed from `from`.
+    fn check_visible<T>(
+        &self,
+        from: ModuleId,
+        module: ModuleId,
+        binding: &Binding<T>,
+        ident: &ast::Ident,
+        kind: &str,
+    ) -> Result<(), Error> {
+        if binding.visibility == Visibility::Public || self.is_visible_from(module, from) {
+            Ok(())
+        } else {
+            Err(
+                Error::spanned(format!("{} {} is private", kind, ident), ident.span())
+                    .with_hint("declared here", binding.span),
+            )
+        }
+    }
+
+    /// Gets the path of the module `id` from the base module.
+    pub fn module_path(&self, mut id: ModuleId) -> ast::Path {
+        let mut idents = Vec::new();
+
+        while let Some(ref ident) = self.modules[&id].ident {
+            idents.push(ident.clone());
+            id = self.modules[&id].super_module.unwrap();
+        }
+
+        let mut path = ast::Path::empty();
+
+        for ident in idents.into_iter().rev() {
+            path.push_ident(ident);
+        }
+
+        path
+    }
+
+    /// Finds the module named by the module segments of `path` from `module_id`, or [`None`] if
+    /// a segment isn't bound.
+    pub fn find_module(
+        &self,
+        module_id: ModuleId,
+        path: &ast::Path,
+    ) -> Result<Option<ModuleId>, Error> {
+        Ok(self.walk_module(module_id, path)?.ok())
+    }
+
+    /// Walks the module segments of `path`, returning the first segment that isn't bound as an
+    /// error.
+    fn walk_module<'p>(
+        &self,
+        module_id: ModuleId,
+        path: &'p ast::Path,
+    ) -> Result<Result<ModuleId, &'p ast::Ident>, Error> {
+        let mut current = match path.is_absolute() {
+            true => self.base_module,
+            false => module_id,
+        };
+
+        for segment in path.iter_modules() {
+            let module = &self.modules[&current];
+
+            match segment {
+                ast::PathSegment::Super(_super) => match module.super_module {
+                    Some(super_module) => current = super_module,
+                    None => {
+                        return Err(Error::spanned(
+                            "The base module has no super module",
+                            _super.span(),
+                        ));
+                    }
+                },
+                ast::PathSegment::Ident(ident) => match module.sub_modules.get(ident) {
+                    Some(binding) => {
+                        self.check_visible(module_id, current, binding, ident, "Module")?;
+                        current = binding.id;
+                    }
+                    None => return Ok(Err(ident)),
+                },
+            }
+        }
+
+        Ok(Ok(current))
+    }
+
     pub fn canonicalize_module(
         &self,
-        mut module_id: ModuleId,
+        module_id: ModuleId,
         path: &ast::Path,
     ) -> Result<ModuleId, Error> {
-        if path.is_absolute() {
-            module_id = self.base_module;
+        match self.walk_module(module_id, path)? {
+            Ok(module_id) => Ok(module_id),
+            Err(ident) => Err(Error::spanned(
+                format!("Module {} not defined", ident),
+                ident.span(),
+            )),
         }
+    }
+}
 
-        for segment in path.iter_modules() {
-            let module = &self.modules[&module_id];
+/// Collects the names in `target` a glob import would bind in `module`, that another glob already
+/// bound to a different item, along with the span of the other glob.
+fn glob_conflicts<T: PartialEq>(
+    target: &HashMap<ast::Ident, Binding<T>>,
+    module: &HashMap<ast::Ident, Binding<T>>,
+    visible: bool,
+    import: &Import,
+) -> Vec<(ast::Ident, Span)> {
+    (target.iter())
+        .filter(|(_, binding)| visible || binding.visibility == Visibility::Public)
+        .filter_map(|(ident, binding)| {
+            let existing = module.get(ident)?;
+            let conflict =
+                existing.glob && existing.span != import.span && existing.id != binding.id;
 
-            match segment {
-                ast::PathSegment::Super => {
-                    if let Some(super_module) = module.super_module {
-                        module_id = super_module;
-                    } else {
-                        return Err(Error::new("invalid path"));
+            conflict.then(|| (ident.clone(), existing.span))
+        })
+        .collect()
+}
+
+/// Collects the bindings of `target` a glob import can bind in `module`.
+fn glob_bindings<T: Copy>(
+    target: &HashMap<ast::Ident, Binding<T>>,
+    module: &HashMap<ast::Ident, Binding<T>>,
+    visible: bool,
+    import: &Import,
+) -> Vec<(ast::Ident, Binding<T>)> {
+    (target.iter())
+        .filter(|(_, binding)| visible || binding.visibility == Visibility::Public)
+        .filter(|(ident, _)| !module.contains_key(*ident))
+        .map(|(ident, binding)| {
+            let binding = Binding {
+                visibility: import.visibility,
+                span: import.span,
+                glob: true,
+                ..*binding
+            };
+
+            (ident.clone(), binding)
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "parse"))]
+mod tests {
+    use crate::test_support::{compile, compile_error, run, try_compile};
+
+    #[test]
+    fn imports() {
+        let program = compile(
+            "
+            mod math {
+                pub use self_ops::*;
+
+                mod self_ops {
+                    pub fn double(x: i32) -> i32 {
+                        return x + x;
+                    }
+
+                    pub fn triple(x: i32) -> i32 {
+                        return x + x + x;
                     }
                 }
-                ast::PathSegment::Ident(ident) => {
-                    if let Some(&sub_module) = module.sub_modules.get(ident) {
-                        module_id = sub_module;
-                    } else {
-                        return Err(Error::new("invalid path"));
+
+                pub mod consts {
+                    pub fn one() -> i32 {
+                        return 1;
                     }
                 }
             }
+
+            use math::{double, triple as thrice, consts::*};
+
+            fn main(x: i32) -> i32 {
+                return double(x) + thrice(x) + one();
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[2]), 11);
+    }
+
+    #[test]
+    fn private_items() {
+        let sources = [
+            "mod a { fn f() {} } fn g() { a::f(); }",
+            "mod a { struct S { x: i32 } } fn g(s: a::S) {}",
+            "mod a { mod b { pub fn f() {} } } fn g() { a::b::f(); }",
+            "mod a { fn f() {} } use a::f;",
+            "mod a { pub fn f() {} } use a::g;",
+            "use a::*;",
+            "fn f() {} fn f() {}",
+        ];
+
+        for source in sources {
+            assert!(try_compile(source).is_err(), "{}", source);
         }
 
-        Ok(module_id)
+        // private items are visible from sub modules
+        compile("fn f() {} mod a { fn g() { super::f(); } }");
+    }
+
+    #[test]
+    fn private_item_error() {
+        let source = "mod a { fn f() {} } fn g() { a::f(); }";
+        let error = compile_error(source);
+
+        assert_eq!(error.message(), "Function f is private");
+        assert!(error.span().is_some());
+        assert_eq!(error.hints().len(), 1);
+    }
+
+    #[test]
+    fn glob_imports() {
+        let modules = "
+            mod a { pub fn f() -> i32 { return 1; } pub fn g() -> i32 { return 2; } }
+            mod b { pub fn f() -> i32 { return 3; } }
+            mod c { pub use super::a::g; }
+            ";
+
+        // an explicit import overrides a glob, whichever order they are written in
+        for imports in ["use a::*; use b::f;", "use b::f; use a::*;"] {
+            let source = format!(
+                "{} {} fn main() -> i32 {{ return f() + g(); }}",
+                modules, imports
+            );
+            assert_eq!(run(&compile(&source), "main", &[]), 5, "{}", imports);
+        }
+
+        // two globs binding the same item are fine
+        let source = format!(
+            "{} use a::*; use c::*; fn main() -> i32 {{ return g(); }}",
+            modules
+        );
+        assert_eq!(run(&compile(&source), "main", &[]), 2);
+
+        let source = format!("{} use a::*; use b::*;", modules);
+        let error = compile_error(&source);
+
+        assert_eq!(error.message(), "f is imported by more than one glob");
+
+        // the hints point at the `*` of each glob
+        let hints: Vec<_> = (error.hints())
+            .iter()
+            .map(|hint| hint.span().index())
+            .collect();
+        let globs = [
+            source.find("a::*").unwrap() + 3,
+            source.find("b::*").unwrap() + 3,
+        ];
+        assert_eq!(hints, globs);
+    }
+
+    #[test]
+    fn modules() {
+        let program = compile(
+            "
+            mod math {
+                pub struct Pair {
+                    a: i32,
+                    b: i32,
+                }
+
+                pub fn sum(pair: Pair) -> i32 {
+                    return pair.a + pair.b + super::one();
+                }
+
+                pub mod nested {
+                    pub fn pair(a: i32) -> super::Pair {
+                        return ::math::Pair { a: a, b: a };
+                    }
+                }
+            }
+
+            fn one() -> i32 {
+                return 1;
+            }
+
+            fn main(x: i32) -> i32 {
+                return math::sum(math::nested::pair(x));
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[2]), 5);
+        assert!(program
+            .functions
+            .get_by_label("math::nested::pair")
+            .is_some());
     }
 }
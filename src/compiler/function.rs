@@ -1,18 +1,60 @@
 use crate::{
     ast,
     compiler::Type,
+    diagnostic::Diagnostics,
     error::Error,
     id::{BlockId, FunctionId, ModuleId},
     ir::{
-        Blocks, Function, Functions, Immediate, InstructionBuilder, MemoryType, Register,
-        RegisterAllocator, Stack, StackAllocation, StackSlot,
+        Blocks, Comparison, FloatComparison, Function, Functions, Immediate, InstructionBuilder,
+        MemoryType, ReadOnlyData, Register, RegisterAllocator, Stack, StackAllocation, StackSlot,
+        TrapCode,
     },
+    span::{Span, Spanned},
 };
 
-use super::{Declarations, FunctionDeclarations, FunctionSignatures, Types, Value, ValueKind};
+use super::{
+    CompileOptions, Declarations, FunctionDeclarations, FunctionSignatures, Types, Value,
+    ValueKind, PTR_SIZE,
+};
+
+/// Gets the type of a number literal with `suffix`.
+fn suffix_type(suffix: ast::NumberSuffix) -> MemoryType {
+    match suffix {
+        ast::NumberSuffix::I8 => MemoryType::I8,
+        ast::NumberSuffix::U8 => MemoryType::U8,
+        ast::NumberSuffix::I16 => MemoryType::I16,
+        ast::NumberSuffix::U16 => MemoryType::U16,
+        ast::NumberSuffix::I32 => MemoryType::I32,
+        ast::NumberSuffix::U32 => MemoryType::U32,
+        ast::NumberSuffix::I64 => MemoryType::I64,
+        ast::NumberSuffix::U64 => MemoryType::U64,
+        ast::NumberSuffix::Usize => MemoryType::Usize,
+        ast::NumberSuffix::F32 => MemoryType::F32,
+        ast::NumberSuffix::F64 => MemoryType::F64,
+    }
+}
+
+/// Whether `expression` is a number literal without a suffix, which takes its type from context.
+fn is_unsuffixed_literal(expression: &ast::Expression) -> bool {
+    match expression {
+        ast::Expression::Paren(paren) => is_unsuffixed_literal(&paren.expression),
+        ast::Expression::Unary(unary) => match unary.operator {
+            ast::UnaryOperator::Negate(_) => is_unsuffixed_literal(&unary.expression),
+            _ => false,
+        },
+        ast::Expression::Literal(ast::LiteralExpression::Integer(literal)) => {
+            literal.integer().suffix().is_none()
+        }
+        ast::Expression::Literal(ast::LiteralExpression::Float(literal)) => {
+            literal.float().suffix().is_none()
+        }
+        _ => false,
+    }
+}
 
 pub enum ControlFlow {
     None,
+    /// Control never reaches the end of the expression, e.g. after `return` or `break`.
     Return,
 }
 
@@ -30,21 +72,41 @@ fn err<T>(error: Error) -> Result<T, ErrorFlow> {
 
 pub struct FunctionVariable {
     pub ident: ast::Ident,
+    pub mutable: bool,
     pub ty: Type,
     pub stack_slot: StackSlot,
 }
 
+pub struct LoopContext {
+    pub label: Option<ast::Label>,
+    pub continue_block: BlockId,
+    pub break_block: BlockId,
+    /// Whether `break` may carry a value, only `loop` allows this.
+    pub value_breaks: bool,
+    /// The type of the loop, set by the first `break`.
+    pub ty: Option<Type>,
+    pub stack_slot: Option<StackSlot>,
+}
+
 pub struct FunctionContext<'a> {
     pub types: &'a mut Types,
     pub signatures: &'a mut FunctionSignatures,
     pub blocks: &'a mut Blocks,
+    pub data: &'a mut ReadOnlyData,
+    pub diagnostics: &'a mut Diagnostics,
     pub module: ModuleId,
     pub stack: Stack,
     pub registers: RegisterAllocator,
     pub current_block: BlockId,
     pub used_blocks: Vec<BlockId>,
+    /// The variables in scope, blocks remove the variables they declare when they end.
     pub variables: Vec<FunctionVariable>,
+    pub loops: Vec<LoopContext>,
     pub return_type: Type,
+    /// The span of the declared return type, or of the signature if the function returns void.
+    pub return_span: Span,
+    /// See [`Function::return_pointer`].
+    pub return_pointer: Option<Register>,
 }
 
 impl<'a> FunctionContext<'a> {
@@ -56,17 +118,63 @@ impl<'a> FunctionContext<'a> {
     }
 
     pub fn new_block(&mut self) -> BlockId {
-        self.blocks.create()
+        let block = self.blocks.create();
+        self.used_blocks.push(block);
+        block
     }
 
     pub fn set_block(&mut self, block: BlockId) {
         self.current_block = block;
     }
 
+    /// Finds the innermost variable named `ident` that is in scope.
+    pub fn find_variable(&self, ident: &ast::Ident) -> Option<&FunctionVariable> {
+        self.variables.iter().rev().find(|var| var.ident == *ident)
+    }
+
+    /// Finds the loop targeted by a `break` or `continue`, the innermost if `label` is [`None`].
+    ///
+    /// `span` is the span of the `break` or `continue`.
+    pub fn find_loop(&self, label: Option<&ast::Label>, span: Span) -> Result<usize, Error> {
+        let index = match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rposition(|lp| lp.label.as_ref() == Some(label)),
+            None => self.loops.len().checked_sub(1),
+        };
+
+        match (index, label) {
+            (Some(index), _) => Ok(index),
+            (None, Some(label)) => Err(Error::spanned(
+                format!("Use of undeclared label {}", label),
+                label.span(),
+            )),
+            (None, None) => Err(Error::spanned(
+                "Cannot break or continue outside of a loop",
+                span,
+            )),
+        }
+    }
+
+    /// Creates an error for `found` not matching the `expected` type.
+    pub fn mismatched_types(&self, expected: Type, found: Type, span: Span) -> Error {
+        Error::spanned(
+            format!(
+                "Mismatched types, expected {} found {}",
+                self.types.name_of(expected),
+                self.types.name_of(found),
+            ),
+            span,
+        )
+    }
+
     pub fn free(&mut self, value: Value) {
         match value.kind {
-            ValueKind::Register(register) => {
-                self.registers.free(register);
+            ValueKind::Register(register) => self.registers.free(register),
+            ValueKind::Pair(pointer, length) => {
+                self.registers.free(pointer);
+                self.registers.free(length);
             }
             _ => {}
         }
@@ -76,16 +184,119 @@ impl<'a> FunctionContext<'a> {
 pub struct FunctionCompiler<'a> {
     pub declarations: &'a Declarations,
     pub functions: &'a FunctionDeclarations,
+    pub options: &'a CompileOptions,
 }
 
 impl<'a> FunctionCompiler<'a> {
-    pub fn new(declarations: &'a Declarations, functions: &'a FunctionDeclarations) -> Self {
+    pub fn new(
+        declarations: &'a Declarations,
+        functions: &'a FunctionDeclarations,
+        options: &'a CompileOptions,
+    ) -> Self {
         Self {
             declarations,
             functions,
+            options,
+        }
+    }
+
+    /// Loads a value of `ty` from `place`.
+    fn load(ctx: &mut FunctionContext<'_>, place: ValueKind, ty: MemoryType) -> Register {
+        match place {
+            ValueKind::Stack(slot, offset) => ctx.ins().stack_load(slot, ty, offset),
+            ValueKind::Address(address, offset) => ctx.ins().load(address, ty, offset),
+            _ => unreachable!("values can only be loaded from memory"),
+        }
+    }
+
+    /// Stores `src` holding a value of `ty` in `place`.
+    fn store(ctx: &mut FunctionContext<'_>, place: ValueKind, src: Register, ty: MemoryType) {
+        match place {
+            ValueKind::Stack(slot, offset) => ctx.ins().stack_store(src, slot, ty, offset),
+            ValueKind::Address(address, offset) => ctx.ins().store(address, src, ty, offset),
+            _ => unreachable!("values can only be stored in memory"),
+        }
+    }
+
+    /// Gets the address of `place`, or [`None`] if `place` isn't stored in memory.
+    fn address_of(ctx: &mut FunctionContext<'_>, place: ValueKind) -> Option<Register> {
+        let (address, offset) = match place {
+            ValueKind::Stack(slot, offset) => {
+                (ctx.ins().stack_addr(MemoryType::Usize, slot), offset)
+            }
+            ValueKind::Address(address, offset) => (address, offset),
+            _ => return None,
+        };
+
+        if offset == 0 {
+            Some(address)
+        } else {
+            let offset = ctx.ins().iconst(offset as u64, MemoryType::Usize);
+            Some(ctx.ins().add(address, offset))
+        }
+    }
+
+    /// Copies a value of `ty` from `src` to `dst`, both stored in memory.
+    fn copy(ctx: &mut FunctionContext<'_>, dst: ValueKind, src: ValueKind, ty: Type) {
+        match ty {
+            Type::Void => {}
+            Type::Struct(id) => {
+                let layout = ctx.types.layout_of(id, PTR_SIZE);
+                let fields = ctx.types.structs[&id].fields.clone();
+
+                for (field, offset) in fields.iter().zip(layout.offsets) {
+                    let ty = ctx.types.get_type(field.ty);
+                    let dst = dst.offset(offset as u32).unwrap();
+                    let src = src.offset(offset as u32).unwrap();
+
+                    Self::copy(ctx, dst, src, ty);
+                }
+            }
+            Type::Array { element, length } => {
+                let ty = ctx.types.get_type(element);
+                let size = ctx.types.size_of(ty, PTR_SIZE);
+
+                for index in 0..length {
+                    let offset = (index * size) as u32;
+                    let dst = dst.offset(offset).unwrap();
+                    let src = src.offset(offset).unwrap();
+
+                    Self::copy(ctx, dst, src, ty);
+                }
+            }
+            Type::Str | Type::Slice(_) => {
+                for offset in [0, PTR_SIZE as u32] {
+                    let dst = dst.offset(offset).unwrap();
+                    let src = src.offset(offset).unwrap();
+
+                    Self::copy(ctx, dst, src, Type::Memory(MemoryType::Usize));
+                }
+            }
+            _ => {
+                let ty = ty.memory_type().unwrap();
+                let register = Self::load(ctx, src, ty);
+
+                Self::store(ctx, dst, register, ty);
+                ctx.registers.free(register);
+            }
+        }
+    }
+
+    /// Gets the pointer and length of a [fat](Type::is_fat) value.
+    fn fat_parts(ctx: &mut FunctionContext<'_>, value: Value) -> (Register, Register) {
+        match value.kind {
+            ValueKind::Pair(pointer, length) => (pointer, length),
+            place => {
+                let pointer = Self::load(ctx, place, MemoryType::Usize);
+                let length = place.offset(PTR_SIZE as u32).unwrap();
+                let length = Self::load(ctx, length, MemoryType::Usize);
+
+                (pointer, length)
+            }
         }
     }
 
+    /// Gets a register holding `value`, aggregates are held by address.
     pub fn compile_value(
         &self,
         ctx: &mut FunctionContext<'_>,
@@ -93,79 +304,203 @@ impl<'a> FunctionCompiler<'a> {
     ) -> Result<Register, Error> {
         match value.kind {
             ValueKind::Register(register) => Ok(register),
-            ValueKind::Stack(slot) => match value.ty {
+            ValueKind::Function(id) => Ok(ctx.ins().func_addr(id, MemoryType::Usize)),
+            // pairs are spilled to the stack, so they have an address
+            ValueKind::Pair(..) => {
+                let type_id = ctx.types.get_type_id(&value.ty);
+                let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+
+                self.stack_store_value(ctx, stack_slot, value)?;
+
+                Ok(ctx.ins().stack_addr(MemoryType::Usize, stack_slot))
+            }
+            place => match value.ty {
                 Type::Void => Ok(ctx.ins().iconst(Immediate::ZERO, MemoryType::Usize)),
-                Type::Memory(ty) => Ok(ctx.ins().stack_load(slot, ty, 0)),
-                Type::Struct(_) => unimplemented!(),
-                Type::Reference(_) | Type::Function(_) => {
-                    Ok(ctx.ins().stack_load(slot, MemoryType::Usize, 0))
-                }
+                ty if ty.is_aggregate() => Ok(Self::address_of(ctx, place).unwrap()),
+                ty => Ok(Self::load(ctx, place, ty.memory_type().unwrap())),
             },
-            ValueKind::Function(id) => Ok(ctx.ins().func_addr(id, MemoryType::Usize)),
         }
     }
 
-    pub fn stack_store_value(
+    /// Gets the registers `value` is passed and returned in, see [`Type::abi_types`].
+    fn abi_registers(
         &self,
         ctx: &mut FunctionContext<'_>,
-        stack_slot: StackSlot,
         value: Value,
-    ) -> Result<(), Error> {
-        let register = self.compile_value(ctx, value)?;
-
+    ) -> Result<Vec<Register>, Error> {
         match value.ty {
-            Type::Void => {}
-            Type::Memory(ty) => ctx.ins().stack_store(register, stack_slot, ty, 0),
-            Type::Struct(_) => unimplemented!(),
-            Type::Reference(_) | Type::Function(_) => {
-                ctx.ins()
-                    .stack_store(register, stack_slot, MemoryType::Usize, 0)
+            Type::Void => Ok(Vec::new()),
+            ty if ty.is_fat() => {
+                let (pointer, length) = Self::fat_parts(ctx, value);
+                Ok(vec![pointer, length])
             }
+            _ => Ok(vec![self.compile_value(ctx, value)?]),
         }
-
-        Ok(())
     }
 
-    pub fn store_value(
+    /// Stores `value` in `place`, which must be stored in memory.
+    pub fn assign_value(
         &self,
         ctx: &mut FunctionContext<'_>,
-        dst: Register,
+        place: ValueKind,
         value: Value,
     ) -> Result<(), Error> {
-        let register = self.compile_value(ctx, value)?;
-
         match value.ty {
             Type::Void => {}
-            Type::Memory(ty) => ctx.ins().store(dst, register, ty, 0),
-            Type::Struct(_) => unimplemented!(),
-            Type::Reference(_) | Type::Function(_) => {
-                ctx.ins().store(dst, register, MemoryType::Usize, 0)
+            _ if matches!(value.kind, ValueKind::Pair(..)) => {
+                let (pointer, length) = Self::fat_parts(ctx, value);
+                let usize = MemoryType::Usize;
+
+                Self::store(ctx, place, pointer, usize);
+                Self::store(ctx, place.offset(PTR_SIZE as u32).unwrap(), length, usize);
+            }
+            ty if ty.is_aggregate() => Self::copy(ctx, place, value.kind, value.ty),
+            ty => {
+                let register = self.compile_value(ctx, value)?;
+                Self::store(ctx, place, register, ty.memory_type().unwrap());
             }
         }
 
         Ok(())
     }
 
+    pub fn stack_store_value(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        stack_slot: StackSlot,
+        value: Value,
+    ) -> Result<(), Error> {
+        self.assign_value(ctx, ValueKind::Stack(stack_slot, 0), value)
+    }
+
+    pub fn store_value(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        dst: Register,
+        value: Value,
+    ) -> Result<(), Error> {
+        self.assign_value(ctx, ValueKind::Address(dst, 0), value)
+    }
+
     pub fn compile_paren(
         &self,
         ctx: &mut FunctionContext<'_>,
         ast: &ast::ParenExpression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        self.compile_expression(ctx, &ast.expression, expected)
+    }
+
+    /// Compiles an integer literal, `negation` is the unary expression negating it, if any.
+    pub fn compile_integer(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        literal: &ast::IntegerLiteral,
+        expected: Option<Type>,
+        negation: Option<&ast::UnaryExpression>,
     ) -> Result<Value, ErrorFlow> {
-        self.compile_expression(ctx, &ast.expression)
+        let integer = literal.integer();
+
+        // suffixed literals have the type of the suffix, otherwise the expected integer type is
+        // used, falling back to i32
+        let ty = match (integer.suffix(), expected) {
+            (Some(suffix), _) => suffix_type(suffix),
+            (None, Some(Type::Memory(ty))) if ty.is_integer() => ty,
+            (None, _) => MemoryType::I32,
+        };
+
+        let name = ctx.types.name_of(Type::Memory(ty));
+        let max = ty.max_value(PTR_SIZE);
+
+        let value = match negation {
+            Some(negation) if !ty.is_signed() => {
+                let message = format!("Cannot apply operator - to {}", name);
+                return err(Error::spanned(message, negation.operator.span()));
+            }
+            Some(negation) if integer.value() > max + 1 => {
+                let message = format!(
+                    "Integer literal out of range for {}, the minimum is -{}",
+                    name,
+                    max + 1,
+                );
+
+                return err(Error::spanned(message, negation.span()));
+            }
+            Some(_) => integer.value().wrapping_neg(),
+            None if integer.value() > max => {
+                let message = format!(
+                    "Integer literal out of range for {}, the maximum is {}",
+                    name, max,
+                );
+
+                return err(Error::spanned(message, literal.span()));
+            }
+            None => integer.value(),
+        };
+
+        let register = ctx.ins().iconst(value, ty);
+        Ok(Value::new(Type::Memory(ty), register))
     }
 
     pub fn compile_literal(
         &self,
         ctx: &mut FunctionContext<'_>,
         ast: &ast::LiteralExpression,
+        expected: Option<Type>,
     ) -> Result<Value, ErrorFlow> {
         match ast {
-            ast::LiteralExpression::String(_) => unimplemented!(),
-            ast::LiteralExpression::Integer(integer) => {
-                let value = integer.integer().value();
-                let register = ctx.ins().iconst(value as i32, MemoryType::I32);
+            ast::LiteralExpression::String(literal) => {
+                let data = ctx.data.insert(literal.string().as_bytes());
+
+                let address = ctx.ins().data_addr(data, MemoryType::Usize);
+                let length = literal.string().len() as u64;
+                let length = ctx.ins().iconst(length, MemoryType::Usize);
+
+                Ok(Value::new(Type::Str, ValueKind::Pair(address, length)))
+            }
+            ast::LiteralExpression::Integer(literal) => {
+                self.compile_integer(ctx, literal, expected, None)
+            }
+            ast::LiteralExpression::Float(literal) => {
+                let float = literal.float();
+
+                let ty = match (float.suffix(), expected) {
+                    (Some(suffix), _) => suffix_type(suffix),
+                    (None, Some(Type::Memory(ty))) if ty.is_float() => ty,
+                    (None, _) => MemoryType::F64,
+                };
+
+                let register = match ty {
+                    MemoryType::F32 => {
+                        let value = float.value() as f32;
+
+                        if value.is_infinite() {
+                            return err(Error::spanned(
+                                "Float literal out of range for f32",
+                                literal.span(),
+                            ));
+                        }
+
+                        ctx.ins().fconst(value, ty)
+                    }
+                    _ => {
+                        if float.value().is_infinite() {
+                            return err(Error::spanned(
+                                "Float literal out of range for f64",
+                                literal.span(),
+                            ));
+                        }
+
+                        ctx.ins().fconst(float.value(), ty)
+                    }
+                };
+
+                Ok(Value::new(Type::Memory(ty), register))
+            }
+            ast::LiteralExpression::Bool(literal) => {
+                let register = ctx.ins().iconst(literal.value() as u64, MemoryType::Bool);
 
-                Ok(Value::new(Type::Memory(MemoryType::I32), register))
+                Ok(Value::new(Type::Memory(MemoryType::Bool), register))
             }
         }
     }
@@ -176,25 +511,15 @@ impl<'a> FunctionCompiler<'a> {
         ast: &ast::Path,
     ) -> Result<Value, ErrorFlow> {
         if let Some(ident) = ast.as_ident() {
-            let variable = ctx.variables.iter().rev().find(|var| var.ident == *ident);
-
-            if let Some(variable) = variable {
+            if let Some(variable) = ctx.find_variable(ident) {
                 return Ok(Value::new(variable.ty, variable.stack_slot));
             }
         }
 
-        let module_id = self.declarations.canonicalize_module(ctx.module, ast)?;
-        let module = &self.declarations.modules[&module_id];
-
-        if let Some(ident) = ast.get_ident() {
-            if let Some(&function_id) = module.functions.get(ident) {
-                let function = &self.functions.functions[&function_id];
-
-                return Ok(Value::new(Type::Function(function.signature), function_id));
-            }
-        }
+        let function_id = self.declarations.resolve_function(ctx.module, ast)?;
+        let function = &self.functions.functions[&function_id];
 
-        err(Error::new(format!("{:?} not defined", ast)))
+        Ok(Value::new(Type::Function(function.signature), function_id))
     }
 
     pub fn compile_call(
@@ -202,252 +527,1513 @@ impl<'a> FunctionCompiler<'a> {
         ctx: &mut FunctionContext,
         ast: &ast::CallExpression,
     ) -> Result<Value, ErrorFlow> {
-        let function = self.compile_expression(ctx, &ast.function)?;
+        let function = match &*ast.function {
+            ast::Expression::Field(field) => {
+                let value = self.compile_expression(ctx, &field.expression, None)?;
+
+                // arrays, slices and str have methods instead of fields
+                if let Some(ty) = Self::method_receiver(ctx, value.ty) {
+                    return self.compile_method(ctx, ast, field, value, ty);
+                }
+
+                self.field_of(ctx, field, value)?
+            }
+            function => self.compile_expression(ctx, function, None)?,
+        };
 
         let signature_id = if let Type::Function(id) = function.ty {
             id
         } else {
-            return err(Error::new("Cannot call"));
+            let message = format!(
+                "Cannot call value of type {}",
+                ctx.types.name_of(function.ty)
+            );
+            return err(Error::spanned(message, ast.function.span()));
         };
 
-        let mut arguments = Vec::with_capacity(ast.arguments.len());
-        let mut types = Vec::with_capacity(ast.arguments.len());
+        let function_id = match function.kind {
+            ValueKind::Function(function_id) => function_id,
+            _ => {
+                let message = "Only functions can be called, not function pointers";
+                return err(Error::spanned(message, ast.function.span()));
+            }
+        };
 
-        for argument in ast.arguments.iter() {
-            let value = self.compile_expression(ctx, &argument)?;
-            let register = self.compile_value(ctx, value)?;
+        let declaration = &self.declarations.functions[&function_id].1;
+        let signature = ctx.signatures.get_signature(signature_id).clone();
+        let return_type = ctx.types.get_type(signature.return_type);
+
+        if ast.arguments.len() != signature.arguments.len() {
+            let message = format!(
+                "Function takes {} arguments but {} were given",
+                signature.arguments.len(),
+                ast.arguments.len(),
+            );
 
-            types.push(ctx.types.get_type_id(&value.ty));
-            arguments.push(register);
+            return err(Error::spanned(message, ast.span())
+                .with_hint("function declared here", declaration.signature_span()));
         }
 
-        let signature = ctx.signatures.get_signature(signature_id);
-        let return_type = ctx.types.get_type(signature.return_type);
+        let mut arguments = Vec::with_capacity(ast.arguments.len());
+
+        for (argument, &type_id) in ast.arguments.iter().zip(signature.arguments.iter()) {
+            let ty = ctx.types.get_type(type_id);
+            let value = self.compile_expression(ctx, argument, Some(ty))?;
+
+            if value.ty != ty {
+                let index = arguments.len();
+
+                return err(ctx
+                    .mismatched_types(ty, value.ty, argument.span())
+                    .with_hint("argument declared here", declaration.args[index].span()));
+            }
 
-        if types != signature.arguments {
-            return err(Error::new("Arguments don't match function signature"));
+            arguments.extend(self.abi_registers(ctx, value)?);
         }
 
-        match function.kind {
-            ValueKind::Function(function_id) => {
-                let register = ctx.ins().call(function_id, arguments);
+        // structs and arrays are returned by writing them to an address passed before the arguments
+        let return_slot = if return_type.is_passed_by_address() {
+            let type_id = ctx.types.get_type_id(&return_type);
+            let slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+            arguments.insert(0, ctx.ins().stack_addr(MemoryType::Usize, slot));
 
-                Ok(Value::new(return_type, register))
+            Some(slot)
+        } else {
+            None
+        };
+
+        let results = match return_slot {
+            Some(_) => 0,
+            None => return_type.abi_types().len(),
+        };
+
+        let registers = ctx.ins().call(function_id, arguments, results);
+
+        match (return_slot, &registers[..]) {
+            (Some(slot), _) => Ok(Value::new(return_type, slot)),
+            (None, &[pointer, length]) => {
+                Ok(Value::new(return_type, ValueKind::Pair(pointer, length)))
             }
-            _ => unimplemented!(),
+            (None, &[register]) => Ok(Value::new(return_type, register)),
+            // void values still get a register, which is never read
+            (None, _) => Ok(Value::new(return_type, ctx.registers.allocate())),
         }
     }
 
-    pub fn compile_unary(
+    /// Gets the type methods are resolved on for a receiver of type `ty`, or [`None`] if `ty` has
+    /// no methods. References are dereferenced.
+    fn method_receiver(ctx: &FunctionContext<'_>, ty: Type) -> Option<Type> {
+        let ty = match ty {
+            Type::Reference(inner) => ctx.types.get_type(inner),
+            ty => ty,
+        };
+
+        match ty {
+            Type::Array { .. } | Type::Slice(_) | Type::Str => Some(ty),
+            _ => None,
+        }
+    }
+
+    /// Compiles a call of a method on an array, slice or `str` of type `ty`.
+    fn compile_method(
         &self,
         ctx: &mut FunctionContext<'_>,
-        ast: &ast::UnaryExpression,
+        ast: &ast::CallExpression,
+        field: &ast::FieldExpression,
+        value: Value,
+        ty: Type,
     ) -> Result<Value, ErrorFlow> {
-        let value = self.compile_expression(ctx, &ast.expression)?;
+        if field.ident.string() != "len" {
+            let message = format!(
+                "No method {} on type {}",
+                field.ident,
+                ctx.types.name_of(value.ty)
+            );
+
+            return err(Error::spanned(message, field.ident.span()));
+        }
 
-        match ast.operator {
-            ast::UnaryOperator::Reference(_) => match value.kind {
-                ValueKind::Stack(slot) => {
-                    let ty = ctx.types.get_type_id(&value.ty);
-                    let register = ctx.ins().stack_addr(MemoryType::Usize, slot);
+        if !ast.arguments.is_empty() {
+            let message = format!(
+                "Method len takes 0 arguments but {} were given",
+                ast.arguments.len()
+            );
 
-                    ctx.free(value);
-                    Ok(Value::new(Type::Reference(ty), register))
-                }
-                _ => err(Error::new("Cannot reference")),
-            },
-            ast::UnaryOperator::Dereference(_) => {
-                if let Type::Reference(inner) = value.ty {
-                    match value.kind {
-                        ValueKind::Register(register) => {
-                            let ty = ctx.types.get_type(inner);
-
-                            let kind = match ty {
-                                Type::Void => {
-                                    let register = ctx.registers.allocate();
-                                    ctx.free(value);
-                                    register
-                                }
-                                Type::Memory(ty) => {
-                                    let register = ctx.ins().load(register, ty, 0);
-                                    ctx.free(value);
-                                    register
-                                }
-                                Type::Struct(_) => register,
-                                Type::Reference(_) | Type::Function(_) => {
-                                    let register = ctx.ins().load(register, MemoryType::Usize, 0);
-                                    ctx.free(value);
-                                    register
-                                }
-                            };
-
-                            Ok(Value::new(ty, kind))
-                        }
-                        ValueKind::Stack(stack_slot) => {
-                            let ty = ctx.types.get_type(inner);
-
-                            let kind = match ty {
-                                Type::Void => {
-                                    let register = ctx.registers.allocate();
-                                    ValueKind::from(register)
-                                }
-                                Type::Memory(ty) => {
-                                    let register = ctx.ins().stack_load(stack_slot, ty, 0);
-                                    ValueKind::from(register)
-                                }
-                                Type::Struct(_) => ValueKind::from(stack_slot),
-                                Type::Reference(_) | Type::Function(_) => {
-                                    let register =
-                                        ctx.ins().stack_load(stack_slot, MemoryType::Usize, 0);
-                                    ValueKind::from(register)
-                                }
-                            };
-
-                            ctx.free(value);
-                            Ok(Value::new(ty, kind))
-                        }
-                        _ => unimplemented!(),
+            return err(Error::spanned(message, ast.span()));
+        }
+
+        let length = self.length_of(ctx, value, ty)?;
+        Ok(Value::new(Type::Memory(MemoryType::Usize), length))
+    }
+
+    /// Gets the length of `value` for the `len()` method, `ty` is the dereferenced type of `value`.
+    fn length_of(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        value: Value,
+        ty: Type,
+    ) -> Result<Register, Error> {
+        let length = match ty {
+            Type::Array { length, .. } => ctx.ins().iconst(length, MemoryType::Usize),
+            _ => {
+                // references to slices are loaded through the reference
+                let value = match value.ty {
+                    Type::Reference(_) => {
+                        let address = self.compile_value(ctx, value)?;
+                        Value::new(ty, ValueKind::Address(address, 0))
                     }
-                } else {
-                    err(Error::new("Cannot dereference"))
-                }
+                    _ => value,
+                };
+
+                Self::fat_parts(ctx, value).1
             }
-            ast::UnaryOperator::Negate(_) => todo!(),
-        }
+        };
+
+        Ok(length)
     }
 
-    pub fn compile_binary(
+    pub fn compile_struct(
         &self,
         ctx: &mut FunctionContext<'_>,
-        ast: &ast::BinaryExpression,
+        ast: &ast::StructExpression,
     ) -> Result<Value, ErrorFlow> {
-        let lhs = self.compile_expression(ctx, &ast.lhs)?;
-        let rhs = self.compile_expression(ctx, &ast.rhs)?;
+        let id = self.declarations.resolve_struct(ctx.module, &ast.path)?;
+        let ty = Type::Struct(id);
 
-        let lhs_val = self.compile_value(ctx, lhs)?;
-        let rhs_val = self.compile_value(ctx, rhs)?;
+        let type_id = ctx.types.get_type_id(&ty);
+        let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
 
-        match ast.operator {
-            ast::BinaryOperator::Add(_) if rhs.ty == lhs.ty && lhs.ty.is_integer() => {
-                let dst = ctx.ins().add(lhs_val, rhs_val);
+        let layout = ctx.types.layout_of(id, PTR_SIZE);
+        let declaration = ctx.types.structs[&id].clone();
+        let mut initialized: Vec<Option<Span>> = vec![None; declaration.fields.len()];
+
+        for initializer in ast.fields.iter() {
+            let (index, field) = match declaration.field(&initializer.ident) {
+                Some(field) => field,
+                None => {
+                    let message = format!(
+                        "Struct {} has no field {}",
+                        declaration.ident, initializer.ident
+                    );
+
+                    return err(Error::spanned(message, initializer.ident.span())
+                        .with_hint("struct declared here", declaration.ident.span()));
+                }
+            };
 
-                ctx.free(lhs);
-                ctx.free(rhs);
+            if let Some(span) = initialized[index] {
+                let message = format!("Field {} initialized more than once", field.ident);
 
-                Ok(Value::new(lhs.ty, dst))
+                return err(Error::spanned(message, initializer.ident.span())
+                    .with_hint("first initialized here", span));
             }
-            _ => unimplemented!(),
-        }
-    }
 
-    pub fn compile_return(
-        &self,
-        ctx: &mut FunctionContext<'_>,
-        ast: &ast::ReturnExpression,
-    ) -> Result<Value, ErrorFlow> {
-        let value = self.compile_expression(ctx, &ast.expression)?;
-        let register = self.compile_value(ctx, value)?;
+            initialized[index] = Some(initializer.ident.span());
 
-        if value.ty != ctx.return_type {
-            return err(Error::new("Invalid return type"));
+            let ty = ctx.types.get_type(field.ty);
+            let value = self.compile_expression(ctx, &initializer.expression, Some(ty))?;
+
+            if value.ty != ty {
+                return err(ctx
+                    .mismatched_types(ty, value.ty, initializer.expression.span())
+                    .with_hint("field declared here", field.ident.span()));
+            }
+
+            let place = ValueKind::Stack(stack_slot, layout.offsets[index] as u32);
+            self.assign_value(ctx, place, value)?;
+            ctx.free(value);
         }
 
-        ctx.ins().ret(register);
+        let mut fields = declaration.fields.iter().zip(initialized);
 
-        Err(Ok(ControlFlow::Return))
+        if let Some((field, _)) = fields.find(|(_, initialized)| initialized.is_none()) {
+            let message = format!(
+                "Missing field {} in initializer of {}",
+                field.ident, declaration.ident
+            );
+
+            return err(Error::spanned(message, ast.path.span())
+                .with_hint("field declared here", field.ident.span()));
+        }
+
+        Ok(Value::new(ty, stack_slot))
     }
 
-    pub fn compile_expression(
+    pub fn compile_field(
         &self,
         ctx: &mut FunctionContext<'_>,
-        expression: &ast::Expression,
+        ast: &ast::FieldExpression,
     ) -> Result<Value, ErrorFlow> {
-        match expression {
-            ast::Expression::Paren(paren) => self.compile_paren(ctx, paren),
-            ast::Expression::Literal(literal) => self.compile_literal(ctx, literal),
-            ast::Expression::Path(path) => self.compile_path(ctx, path),
-            ast::Expression::Call(call) => self.compile_call(ctx, call),
-            ast::Expression::Unary(unary) => self.compile_unary(ctx, unary),
-            ast::Expression::Binary(binary) => self.compile_binary(ctx, binary),
-            ast::Expression::Return(ast) => self.compile_return(ctx, ast),
-        }
+        let value = self.compile_expression(ctx, &ast.expression, None)?;
+        self.field_of(ctx, ast, value)
     }
 
-    pub fn compile_let(
+    /// Gets the field `ast` of `value`, which is the compiled expression of `ast`.
+    fn field_of(
         &self,
         ctx: &mut FunctionContext<'_>,
-        ast: &ast::LetStatement,
-    ) -> Result<ControlFlow, Error> {
-        let (value, ty) = if let Some(ref value) = ast.value {
-            let value = match self.compile_expression(ctx, &value.expression) {
-                Ok(value) => value,
-                Err(flow) => return flow,
-            };
-
-            if let Some(ref ty) = ast.ty {
-                let ty = self.declarations.resolve_type(ctx.types, &ty.ty)?;
+        ast: &ast::FieldExpression,
+        value: Value,
+    ) -> Result<Value, ErrorFlow> {
+        let no_field = |ctx: &FunctionContext| {
+            let message = format!(
+                "No field {} on type {}",
+                ast.ident,
+                ctx.types.name_of(value.ty)
+            );
+
+            err(Error::spanned(message, ast.ident.span()))
+        };
 
-                if value.ty != ty {
-                    return Err(Error::new("Must match type defined"));
+        // fields of references to structs are accessed through the reference
+        let (id, place) = match value.ty {
+            Type::Struct(id) => (id, value.kind),
+            Type::Reference(inner) => match ctx.types.get_type(inner) {
+                Type::Struct(id) => {
+                    let address = self.compile_value(ctx, value)?;
+                    (id, ValueKind::Address(address, 0))
                 }
-            }
+                _ => return no_field(ctx),
+            },
+            _ => return no_field(ctx),
+        };
 
-            (Some(value), value.ty)
-        } else {
-            let ast = ast.ty.as_ref().ok_or(Error::new("Type must be defined"))?;
+        let declaration = &ctx.types.structs[&id];
 
-            let ty = self.declarations.resolve_type(ctx.types, &ast.ty)?;
+        let (index, field) = match declaration.field(&ast.ident) {
+            Some(field) => field,
+            None => {
+                let message = format!("Struct {} has no field {}", declaration.ident, ast.ident);
 
-            (None, ty)
+                return err(Error::spanned(message, ast.ident.span())
+                    .with_hint("struct declared here", declaration.ident.span()));
+            }
         };
 
-        let type_id = ctx.types.get_type_id(&ty);
-        let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
-
-        ctx.variables.push(FunctionVariable {
-            ident: ast.ident.clone(),
-            ty,
-            stack_slot,
-        });
-
-        if let Some(value) = value {
-            self.stack_store_value(ctx, stack_slot, value)?;
-            ctx.free(value);
-        }
+        let ty = ctx.types.get_type(field.ty);
+        let offset = ctx.types.layout_of(id, PTR_SIZE).offsets[index];
 
-        Ok(ControlFlow::None)
+        Ok(Value::new(ty, place.offset(offset as u32).unwrap()))
     }
 
-    pub fn compile_statement(
+    pub fn compile_index(
         &self,
         ctx: &mut FunctionContext<'_>,
-        statement: &ast::Statement,
-    ) -> Result<ControlFlow, Error> {
-        match statement {
-            ast::Statement::Noop(_) => Ok(ControlFlow::None),
-            ast::Statement::Expression(expression) => {
-                match self.compile_expression(ctx, &expression.expression) {
-                    Ok(value) => {
-                        ctx.free(value);
-
-                        Ok(ControlFlow::None)
-                    }
-                    Err(error_flow) => error_flow,
-                }
-            }
-            ast::Statement::Let(ast) => self.compile_let(ctx, ast),
-        }
+        ast: &ast::IndexExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let value = self.compile_expression(ctx, &ast.expression, None)?;
+        self.index_of(ctx, ast, value)
+    }
+
+    /// Gets the element at the index of `ast` in `value`, which is the compiled expression of
+    /// `ast`. Indexing with a range gets a slice of the elements instead.
+    fn index_of(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::IndexExpression,
+        value: Value,
+    ) -> Result<Value, ErrorFlow> {
+        let not_indexable = |ctx: &FunctionContext| {
+            let message = format!("Cannot index into type {}", ctx.types.name_of(value.ty));
+            err(Error::spanned(message, ast.expression.span()))
+        };
+
+        // references to arrays are indexed through the reference, slices through their pointer
+        let (element, length, base) = match value.ty {
+            Type::Array { element, length } => match Self::address_of(ctx, value.kind) {
+                Some(base) => (element, ctx.ins().iconst(length, MemoryType::Usize), base),
+                None => unreachable!("arrays are stored in memory"),
+            },
+            Type::Slice(element) => {
+                let (base, length) = Self::fat_parts(ctx, value);
+                (element, length, base)
+            }
+            Type::Reference(inner) => match ctx.types.get_type(inner) {
+                Type::Array { element, length } => {
+                    let base = self.compile_value(ctx, value)?;
+                    (element, ctx.ins().iconst(length, MemoryType::Usize), base)
+                }
+                _ => return not_indexable(ctx),
+            },
+            _ => return not_indexable(ctx),
+        };
+
+        let ty = ctx.types.get_type(element);
+        let size = ctx.types.size_of(ty, PTR_SIZE);
+        let size = ctx.ins().iconst(size, MemoryType::Usize);
+
+        if let ast::Expression::Range(range) = &*ast.index {
+            let start = match range.start {
+                Some(ref start) => self.compile_index_register(ctx, start)?,
+                None => ctx.ins().iconst(Immediate::ZERO, MemoryType::Usize),
+            };
+
+            let end = match range.end {
+                Some(ref end) => self.compile_index_register(ctx, end)?,
+                None => length,
+            };
+
+            if self.options.bounds_checks {
+                let ordered = ctx.ins().le(start, end, false);
+                let in_length = ctx.ins().le(end, length, false);
+                let in_bounds = ctx.ins().and(ordered, in_length);
+
+                self.check(ctx, in_bounds, TrapCode::IndexOutOfBounds, ast.span());
+            }
+
+            let offset = ctx.ins().mul(start, size);
+            let pointer = ctx.ins().add(base, offset);
+            let length = ctx.ins().sub(end, start);
+
+            let ty = Type::Slice(element);
+            return Ok(Value::new(ty, ValueKind::Pair(pointer, length)));
+        }
+
+        let index = self.compile_index_register(ctx, &ast.index)?;
+
+        if self.options.bounds_checks {
+            let in_bounds = ctx.ins().lt(index, length, false);
+            self.check(ctx, in_bounds, TrapCode::IndexOutOfBounds, ast.span());
+        }
+
+        let offset = ctx.ins().mul(index, size);
+        let address = ctx.ins().add(base, offset);
+
+        Ok(Value::new(ty, ValueKind::Address(address, 0)))
+    }
+
+    /// Compiles an index or range bound `ast` to a usize register.
+    fn compile_index_register(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::Expression,
+    ) -> Result<Register, ErrorFlow> {
+        let usize = Type::Memory(MemoryType::Usize);
+        let index = self.compile_expression(ctx, ast, Some(usize))?;
+
+        if !index.ty.is_integer() {
+            let message = format!(
+                "Array index must be an integer, found {}",
+                ctx.types.name_of(index.ty)
+            );
+
+            return err(Error::spanned(message, ast.span()));
+        }
+
+        let register = self.compile_value(ctx, index)?;
+
+        // signed indices are sign extended, so negative indices are out of bounds
+        if index.ty != usize {
+            Ok(ctx.ins().iconvert(register, MemoryType::Usize))
+        } else {
+            Ok(register)
+        }
+    }
+
+    /// Traps with `code` at `span` unless `condition` is true.
+    fn check(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        condition: Register,
+        code: TrapCode,
+        span: Span,
+    ) {
+        let ok_block = ctx.new_block();
+        let trap_block = ctx.new_block();
+
+        ctx.ins().branch(condition, ok_block, trap_block);
+
+        ctx.set_block(trap_block);
+        ctx.ins().trap(code, span);
+
+        ctx.set_block(ok_block);
+    }
+
+    /// Gets the element type of an array literal from the type expected of the array.
+    fn expected_element(ctx: &FunctionContext<'_>, expected: Option<Type>) -> Option<Type> {
+        match expected {
+            Some(Type::Array { element, .. }) => Some(ctx.types.get_type(element)),
+            _ => None,
+        }
+    }
+
+    pub fn compile_array(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::ArrayExpression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        let mut elements = ast.elements.iter();
+
+        // the first element decides the element type, unless it's known from the context
+        let first = match elements.next() {
+            Some(first) => {
+                let expected = Self::expected_element(ctx, expected);
+                Some(self.compile_expression(ctx, first, expected)?)
+            }
+            None => None,
+        };
+
+        let element = match (first, Self::expected_element(ctx, expected)) {
+            (Some(first), _) => first.ty,
+            (None, Some(element)) => element,
+            (None, None) => {
+                return err(Error::spanned(
+                    "Type of empty array must be known",
+                    ast.span(),
+                ));
+            }
+        };
+
+        let size = ctx.types.size_of(element, PTR_SIZE);
+        let ty = Type::Array {
+            element: ctx.types.get_type_id(&element),
+            length: ast.elements.len() as u64,
+        };
+
+        let type_id = ctx.types.get_type_id(&ty);
+        let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+
+        if let Some(first) = first {
+            self.stack_store_value(ctx, stack_slot, first)?;
+            ctx.free(first);
+        }
+
+        for (index, expression) in elements.enumerate() {
+            let value = self.compile_expression(ctx, expression, Some(element))?;
+
+            if value.ty != element {
+                return err(ctx
+                    .mismatched_types(element, value.ty, expression.span())
+                    .with_hint("expected because of this", ast.elements[0].span()));
+            }
+
+            let offset = (index as u64 + 1) * size;
+            let place = ValueKind::Stack(stack_slot, offset as u32);
+
+            self.assign_value(ctx, place, value)?;
+            ctx.free(value);
+        }
+
+        Ok(Value::new(ty, stack_slot))
+    }
+
+    /// Compiles `[x; N]`, `x` is evaluated once and copied to every element in a loop.
+    pub fn compile_array_repeat(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::ArrayRepeatExpression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        let expected = Self::expected_element(ctx, expected);
+        let value = self.compile_expression(ctx, &ast.value, expected)?;
+
+        let length = ast.length.integer().value();
+        let ty = Type::Array {
+            element: ctx.types.get_type_id(&value.ty),
+            length,
+        };
+
+        let type_id = ctx.types.get_type_id(&ty);
+        let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+
+        // values in registers are spilled, so every iteration copies from memory
+        let value = match value.kind {
+            ValueKind::Register(_) | ValueKind::Pair(..) | ValueKind::Function(_) => {
+                let type_id = ctx.types.get_type_id(&value.ty);
+                let value_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+
+                self.stack_store_value(ctx, value_slot, value)?;
+                ctx.free(value);
+
+                Value::new(value.ty, value_slot)
+            }
+            _ => value,
+        };
+
+        let usize = ctx.types.get_type_id(&Type::Memory(MemoryType::Usize));
+        let counter = ctx.stack.allocate(StackAllocation { ty: usize });
+
+        let zero = ctx.ins().iconst(Immediate::ZERO, MemoryType::Usize);
+        ctx.ins().stack_store(zero, counter, MemoryType::Usize, 0);
+        ctx.registers.free(zero);
+
+        let header_block = ctx.new_block();
+        let body_block = ctx.new_block();
+        let exit_block = ctx.new_block();
+
+        ctx.ins().jump(header_block);
+        ctx.set_block(header_block);
+
+        let index = ctx.ins().stack_load(counter, MemoryType::Usize, 0);
+        let length = ctx.ins().iconst(length, MemoryType::Usize);
+        let condition = ctx.ins().lt(index, length, false);
+        ctx.ins().branch(condition, body_block, exit_block);
+
+        ctx.set_block(body_block);
+
+        let size = ctx.types.size_of(value.ty, PTR_SIZE);
+        let base = ctx.ins().stack_addr(MemoryType::Usize, stack_slot);
+        let size = ctx.ins().iconst(size, MemoryType::Usize);
+        let offset = ctx.ins().mul(index, size);
+        let address = ctx.ins().add(base, offset);
+        self.store_value(ctx, address, value)?;
+
+        let one = ctx.ins().iconst(1u64, MemoryType::Usize);
+        let next = ctx.ins().add(index, one);
+        ctx.ins().stack_store(next, counter, MemoryType::Usize, 0);
+        ctx.ins().jump(header_block);
+
+        ctx.set_block(exit_block);
+
+        Ok(Value::new(ty, stack_slot))
+    }
+
+    /// Compiles the place assigned to by `ast`, and gets the immutable variable it's part of,
+    /// if any. Places behind references can always be assigned to.
+    fn compile_place(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::Expression,
+    ) -> Result<(Value, Option<ast::Ident>), ErrorFlow> {
+        match ast {
+            ast::Expression::Paren(paren) => self.compile_place(ctx, &paren.expression),
+            ast::Expression::Path(path) => {
+                let value = self.compile_path(ctx, path)?;
+
+                let variable = path.as_ident().and_then(|ident| ctx.find_variable(ident));
+
+                match variable {
+                    Some(variable) if !variable.mutable => {
+                        Ok((value, Some(variable.ident.clone())))
+                    }
+                    _ => Ok((value, None)),
+                }
+            }
+            ast::Expression::Field(field) => {
+                let (value, immutable) = self.compile_place(ctx, &field.expression)?;
+
+                let immutable = match value.ty {
+                    Type::Reference(_) => None,
+                    _ => immutable,
+                };
+
+                Ok((self.field_of(ctx, field, value)?, immutable))
+            }
+            ast::Expression::Index(index) => {
+                let (value, immutable) = self.compile_place(ctx, &index.expression)?;
+
+                let immutable = match value.ty {
+                    Type::Reference(_) | Type::Slice(_) => None,
+                    _ => immutable,
+                };
+
+                Ok((self.index_of(ctx, index, value)?, immutable))
+            }
+            _ => Ok((self.compile_expression(ctx, ast, None)?, None)),
+        }
+    }
+
+    pub fn compile_assign(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::AssignExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let (place, immutable) = self.compile_place(ctx, &ast.lhs)?;
+
+        if !place.kind.is_place() {
+            return err(Error::spanned(
+                "Invalid left hand side of assignment",
+                ast.lhs.span(),
+            ));
+        }
+
+        if let Some(ident) = immutable {
+            let message = format!("Cannot assign to immutable variable {}", ident);
+
+            return err(Error::spanned(message, ast.lhs.span())
+                .with_hint("declared here, consider adding mut", ident.span()));
+        }
+
+        let value = self.compile_expression(ctx, &ast.rhs, Some(place.ty))?;
+
+        // compound assignments apply the operator to the current value first
+        let value = match ast.operator.binary_operator() {
+            Some(operator) => {
+                let current = self.compile_value(ctx, place)?;
+                let current = Value::new(place.ty, current);
+
+                let value = self.binary_operation(ctx, &operator, current, value, ast.span())?;
+                ctx.free(current);
+
+                value
+            }
+            None => value,
+        };
+
+        if value.ty != place.ty {
+            return err(ctx.mismatched_types(place.ty, value.ty, ast.rhs.span()));
+        }
+
+        self.assign_value(ctx, place.kind, value)?;
+        ctx.free(value);
+
+        Ok(Value::new(Type::Void, ctx.registers.allocate()))
+    }
+
+    pub fn compile_unary(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::UnaryExpression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        // negative literals are compiled directly, so the minimum of signed types can be written
+        if let (
+            ast::UnaryOperator::Negate(_),
+            ast::Expression::Literal(ast::LiteralExpression::Integer(literal)),
+        ) = (&ast.operator, &*ast.expression)
+        {
+            return self.compile_integer(ctx, literal, expected, Some(ast));
+        }
+
+        let expected = match ast.operator {
+            ast::UnaryOperator::Negate(_) | ast::UnaryOperator::Not(_) => expected,
+            _ => None,
+        };
+
+        let value = self.compile_expression(ctx, &ast.expression, expected)?;
+
+        let unsupported = |ctx: &FunctionContext, operator: &dyn Spanned, symbol: &str| {
+            let message = format!(
+                "Cannot apply operator {} to {}",
+                symbol,
+                ctx.types.name_of(value.ty)
+            );
+
+            err(Error::spanned(message, operator.span()))
+        };
+
+        match ast.operator {
+            ast::UnaryOperator::Reference(_) => match Self::address_of(ctx, value.kind) {
+                Some(register) => {
+                    let ty = ctx.types.get_type_id(&value.ty);
+
+                    Ok(Value::new(Type::Reference(ty), register))
+                }
+                None => err(Error::spanned(
+                    "Cannot reference a temporary value",
+                    ast.expression.span(),
+                )),
+            },
+            ast::UnaryOperator::Dereference(_) => {
+                if let Type::Reference(inner) = value.ty {
+                    let address = self.compile_value(ctx, value)?;
+                    let ty = ctx.types.get_type(inner);
+
+                    Ok(Value::new(ty, ValueKind::Address(address, 0)))
+                } else {
+                    let message = format!("Cannot dereference {}", ctx.types.name_of(value.ty));
+                    err(Error::spanned(message, ast.expression.span()))
+                }
+            }
+            ast::UnaryOperator::Negate(ref operator) => {
+                let ty = match value.ty {
+                    Type::Memory(ty) if ty.is_signed() || ty.is_float() => ty,
+                    _ => return unsupported(ctx, operator, "-"),
+                };
+
+                let src = self.compile_value(ctx, value)?;
+
+                let dst = if ty.is_float() {
+                    ctx.ins().fneg(src)
+                } else {
+                    let zero = ctx.ins().iconst(Immediate::ZERO, ty);
+                    let dst = ctx.ins().sub(zero, src);
+                    ctx.registers.free(zero);
+
+                    dst
+                };
+
+                ctx.registers.free(src);
+                Ok(Value::new(value.ty, dst))
+            }
+            ast::UnaryOperator::Not(ref operator) => {
+                // `!` flips every bit of integers, and the single bit of bools
+                let mask = match value.ty {
+                    Type::Memory(MemoryType::Bool) => 1,
+                    Type::Memory(ty) if ty.is_integer() => u64::MAX,
+                    _ => return unsupported(ctx, operator, "!"),
+                };
+
+                let ty = value.ty.memory_type().unwrap();
+                let src = self.compile_value(ctx, value)?;
+
+                let mask = ctx.ins().iconst(mask, ty);
+                let dst = ctx.ins().xor(src, mask);
+
+                ctx.registers.free(mask);
+                ctx.registers.free(src);
+                Ok(Value::new(value.ty, dst))
+            }
+        }
+    }
+
+    pub fn compile_cast(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::CastExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let value = self.compile_expression(ctx, &ast.expression, None)?;
+        let ty = (self.declarations).resolve_type(ctx.types, ctx.module, &ast.ty)?;
+
+        // bools can be cast to integers, but not the other way around
+        let (from, to) = match (value.ty, ty) {
+            (Type::Memory(from), Type::Memory(to))
+                if (from.is_integer() || from.is_float()) && (to.is_integer() || to.is_float()) =>
+            {
+                (from, to)
+            }
+            (Type::Memory(MemoryType::Bool), Type::Memory(to)) if to.is_integer() => {
+                (MemoryType::Bool, to)
+            }
+            _ => {
+                let message = format!(
+                    "Cannot cast {} to {}",
+                    ctx.types.name_of(value.ty),
+                    ctx.types.name_of(ty),
+                );
+
+                return err(Error::spanned(message, ast.span()));
+            }
+        };
+
+        if from == to {
+            return Ok(value);
+        }
+
+        let src = self.compile_value(ctx, value)?;
+
+        let dst = match (from.is_float(), to.is_float()) {
+            (false, false) => ctx.ins().iconvert(src, to),
+            (false, true) => ctx.ins().int_to_float(src, to),
+            (true, false) => ctx.ins().float_to_int(src, to),
+            (true, true) => ctx.ins().fconvert(src, to),
+        };
+
+        ctx.free(value);
+
+        Ok(Value::new(ty, dst))
+    }
+
+    /// Compiles `&&` and `||`, the right hand side is only evaluated if the left hand side
+    /// doesn't decide the result.
+    fn compile_logical(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::BinaryExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let bool = Type::Memory(MemoryType::Bool);
+
+        let lhs = self.compile_expression(ctx, &ast.lhs, Some(bool))?;
+
+        if lhs.ty != bool {
+            return err(ctx.mismatched_types(bool, lhs.ty, ast.lhs.span()));
+        }
+
+        let type_id = ctx.types.get_type_id(&bool);
+        let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+
+        let register = self.compile_value(ctx, lhs)?;
+        ctx.ins()
+            .stack_store(register, stack_slot, MemoryType::Bool, 0);
+
+        let rhs_block = ctx.new_block();
+        let merge_block = ctx.new_block();
+
+        match ast.operator {
+            ast::BinaryOperator::LogicalAnd(_) => {
+                ctx.ins().branch(register, rhs_block, merge_block);
+            }
+            _ => {
+                ctx.ins().branch(register, merge_block, rhs_block);
+            }
+        }
+
+        ctx.registers.free(register);
+
+        ctx.set_block(rhs_block);
+        let rhs = self.compile_expression(ctx, &ast.rhs, Some(bool));
+
+        if let Some((_, rhs)) = Self::branch_arm(ctx, rhs)? {
+            if rhs.ty != bool {
+                return err(ctx.mismatched_types(bool, rhs.ty, ast.rhs.span()));
+            }
+
+            self.stack_store_value(ctx, stack_slot, rhs)?;
+            ctx.free(rhs);
+            ctx.ins().jump(merge_block);
+        }
+
+        ctx.set_block(merge_block);
+
+        Ok(Value::new(bool, stack_slot))
+    }
+
+    pub fn compile_binary(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::BinaryExpression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        use ast::BinaryOperator as Op;
+
+        if let Op::LogicalAnd(_) | Op::LogicalOr(_) = ast.operator {
+            return self.compile_logical(ctx, ast);
+        }
+
+        // comparisons produce bools, which says nothing about the operands
+        let expected = match ast.operator.is_comparison() {
+            true => None,
+            false => expected,
+        };
+
+        // an unsuffixed literal on the left takes the type of the right, compiling it last is
+        // fine since literals have no side effects. shift amounts don't affect the shifted type
+        let shift = matches!(ast.operator, Op::BitShiftLeft(_) | Op::BitShiftRight(_));
+
+        let (lhs, rhs) = if !shift && expected.is_none() && is_unsuffixed_literal(&ast.lhs) {
+            let rhs = self.compile_expression(ctx, &ast.rhs, None)?;
+            let lhs = self.compile_expression(ctx, &ast.lhs, Some(rhs.ty))?;
+            (lhs, rhs)
+        } else {
+            let lhs = self.compile_expression(ctx, &ast.lhs, expected)?;
+            let rhs = self.compile_expression(ctx, &ast.rhs, Some(lhs.ty))?;
+            (lhs, rhs)
+        };
+
+        self.binary_operation(ctx, &ast.operator, lhs, rhs, ast.span())
+    }
+
+    /// Applies `operator` to the compiled operands `lhs` and `rhs`, `&&` and `||` aren't
+    /// supported since they need to control evaluation of `rhs`. Integer division traps at `span`.
+    fn binary_operation(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        operator: &ast::BinaryOperator,
+        lhs: Value,
+        rhs: Value,
+        span: Span,
+    ) -> Result<Value, ErrorFlow> {
+        use ast::BinaryOperator as Op;
+
+        let unsupported = |ctx: &FunctionContext| {
+            let message = format!(
+                "Operator not supported for {} and {}",
+                ctx.types.name_of(lhs.ty),
+                ctx.types.name_of(rhs.ty),
+            );
+
+            err(Error::spanned(message, operator.span()))
+        };
+
+        // shift amounts may be any integer type, other operands must have the same type
+        let ty = match (lhs.ty, rhs.ty, &operator) {
+            (Type::Memory(ty), Type::Memory(rhs), Op::BitShiftLeft(_) | Op::BitShiftRight(_))
+                if ty.is_integer() && rhs.is_integer() =>
+            {
+                ty
+            }
+            (_, _, Op::BitShiftLeft(_) | Op::BitShiftRight(_)) => return unsupported(ctx),
+            (Type::Memory(ty), Type::Memory(rhs), _) if ty == rhs => ty,
+            _ => return unsupported(ctx),
+        };
+
+        let integer = ty.is_integer();
+        let float = ty.is_float();
+        let signed = ty.is_signed();
+        let bool = ty == MemoryType::Bool;
+
+        let lhs_val = self.compile_value(ctx, lhs)?;
+        let rhs_val = self.compile_value(ctx, rhs)?;
+
+        if integer && matches!(operator, Op::Div(_) | Op::Rem(_)) {
+            let zero = ctx.ins().iconst(Immediate::ZERO, ty);
+            let non_zero = ctx.ins().ne(rhs_val, zero);
+            self.check(ctx, non_zero, TrapCode::DivisionByZero, span);
+
+            // the remainder of the minimum value by -1 is 0, but the quotient overflows
+            if signed && matches!(operator, Op::Div(_)) {
+                let min = ctx.ins().iconst(1u64 << (ty.size(PTR_SIZE) * 8 - 1), ty);
+                let minus_one = ctx.ins().iconst(u64::MAX, ty);
+
+                let not_min = ctx.ins().ne(lhs_val, min);
+                let not_minus_one = ctx.ins().ne(rhs_val, minus_one);
+                let no_overflow = ctx.ins().or(not_min, not_minus_one);
+
+                self.check(ctx, no_overflow, TrapCode::DivisionOverflow, span);
+            }
+        }
+
+        let mut ins = ctx.ins();
+
+        let dst = match *operator {
+            Op::Add(_) if integer => ins.add(lhs_val, rhs_val),
+            Op::Add(_) if float => ins.fadd(lhs_val, rhs_val),
+            Op::Sub(_) if integer => ins.sub(lhs_val, rhs_val),
+            Op::Sub(_) if float => ins.fsub(lhs_val, rhs_val),
+            Op::Mul(_) if integer => ins.mul(lhs_val, rhs_val),
+            Op::Mul(_) if float => ins.fmul(lhs_val, rhs_val),
+            Op::Div(_) if integer => ins.div(lhs_val, rhs_val),
+            Op::Div(_) if float => ins.fdiv(lhs_val, rhs_val),
+            Op::Rem(_) if integer => ins.rem(lhs_val, rhs_val),
+            Op::BinaryAnd(_) if integer || bool => ins.and(lhs_val, rhs_val),
+            Op::BinaryOr(_) if integer || bool => ins.or(lhs_val, rhs_val),
+            Op::BinaryXor(_) if integer || bool => ins.xor(lhs_val, rhs_val),
+            Op::BitShiftLeft(_) => ins.shl(lhs_val, rhs_val),
+            Op::BitShiftRight(_) => ins.shr(lhs_val, rhs_val),
+            Op::Equal(_) if float => ins.fcmp(FloatComparison::Equal, lhs_val, rhs_val),
+            Op::Equal(_) => ins.eq(lhs_val, rhs_val),
+            Op::NotEqual(_) if float => ins.fcmp(FloatComparison::NotEqual, lhs_val, rhs_val),
+            Op::NotEqual(_) => ins.ne(lhs_val, rhs_val),
+            Op::LessThan(_) if integer => ins.icmp(Comparison::less_than(signed), lhs_val, rhs_val),
+            Op::LessThan(_) if float => ins.fcmp(FloatComparison::LessThan, lhs_val, rhs_val),
+            Op::LessThanOrEqual(_) if integer => {
+                ins.icmp(Comparison::less_than_or_equal(signed), lhs_val, rhs_val)
+            }
+            Op::LessThanOrEqual(_) if float => {
+                ins.fcmp(FloatComparison::LessThanOrEqual, lhs_val, rhs_val)
+            }
+            Op::GreaterThan(_) if integer => {
+                ins.icmp(Comparison::greater_than(signed), lhs_val, rhs_val)
+            }
+            Op::GreaterThan(_) if float => ins.fcmp(FloatComparison::GreaterThan, lhs_val, rhs_val),
+            Op::GreaterThanOrEqual(_) if integer => {
+                ins.icmp(Comparison::greater_than_or_equal(signed), lhs_val, rhs_val)
+            }
+            Op::GreaterThanOrEqual(_) if float => {
+                ins.fcmp(FloatComparison::GreaterThanOrEqual, lhs_val, rhs_val)
+            }
+            _ => return unsupported(ctx),
+        };
+
+        ctx.free(lhs);
+        ctx.free(rhs);
+
+        match operator.is_comparison() {
+            true => Ok(Value::new(Type::Memory(MemoryType::Bool), dst)),
+            false => Ok(Value::new(lhs.ty, dst)),
+        }
+    }
+
+    pub fn compile_return(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::ReturnExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let value = self.compile_expression(ctx, &ast.expression, Some(ctx.return_type))?;
+
+        if value.ty != ctx.return_type {
+            let span = ast.expression.span();
+
+            return err(ctx
+                .mismatched_types(ctx.return_type, value.ty, span)
+                .with_hint("return type declared here", ctx.return_span));
+        }
+
+        self.return_value(ctx, value)
+    }
+
+    /// Returns `value` from the function, `value` must be of the return type.
+    fn return_value(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        value: Value,
+    ) -> Result<Value, ErrorFlow> {
+        match ctx.return_pointer {
+            Some(return_pointer) => {
+                self.store_value(ctx, return_pointer, value)?;
+                ctx.ins().ret([]);
+            }
+            None => {
+                let registers = self.abi_registers(ctx, value)?;
+                ctx.ins().ret(registers);
+            }
+        }
+
+        Err(Ok(ControlFlow::Return))
+    }
+
+    /// Compiles `ast` in a new scope, the value of the block is its tail expression or void.
+    pub fn compile_block(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::Block,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        let scope = ctx.variables.len();
+        let value = self.compile_scope(ctx, ast, expected);
+        ctx.variables.truncate(scope);
+
+        value
+    }
+
+    fn compile_scope(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::Block,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        for statement in ast.body() {
+            match self.compile_statement(ctx, statement) {
+                Ok(ControlFlow::None) => {}
+                flow => return Err(flow),
+            }
+        }
+
+        match ast.tail() {
+            Some(tail) => self.compile_expression(ctx, tail, expected),
+            None => Ok(Value::new(Type::Void, ctx.registers.allocate())),
+        }
+    }
+
+    /// Gets the block an arm of a branch ended in and the value it produced, or [`None`] if the
+    /// arm diverged.
+    fn branch_arm(
+        ctx: &FunctionContext<'_>,
+        result: Result<Value, ErrorFlow>,
+    ) -> Result<Option<(BlockId, Value)>, ErrorFlow> {
+        match result {
+            Ok(value) => Ok(Some((ctx.current_block, value))),
+            Err(Ok(_)) => Ok(None),
+            Err(Err(error)) => err(error),
+        }
+    }
+
+    pub fn compile_if(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::IfExpression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        let bool = Type::Memory(MemoryType::Bool);
+        let condition = self.compile_expression(ctx, &ast.condition, Some(bool))?;
+
+        if condition.ty != bool {
+            return err(ctx.mismatched_types(bool, condition.ty, ast.condition.span()));
+        }
+
+        let register = self.compile_value(ctx, condition)?;
+
+        let then_block = ctx.new_block();
+        let else_block = ctx.new_block();
+
+        ctx.ins().branch(register, then_block, else_block);
+        ctx.registers.free(register);
+
+        ctx.set_block(then_block);
+        let then_value = self.compile_block(ctx, &ast.block, expected);
+        let then_arm = Self::branch_arm(ctx, then_value)?;
+
+        // the else arm must have the type of the then arm, so its literals take that type
+        let else_expected = then_arm.map_or(expected, |(_, value)| Some(value.ty));
+
+        ctx.set_block(else_block);
+        let else_value = match ast.else_branch {
+            Some(ref else_branch) => match else_branch.body {
+                ast::ElseBody::If(ref ast) => self.compile_if(ctx, ast, else_expected),
+                ast::ElseBody::Block(ref ast) => self.compile_block(ctx, ast, else_expected),
+            },
+            None => Ok(Value::new(Type::Void, ctx.registers.allocate())),
+        };
+        let else_arm = Self::branch_arm(ctx, else_value)?;
+
+        let ty = match (then_arm, else_arm) {
+            (None, None) => return Err(Ok(ControlFlow::Return)),
+            (Some((_, then_value)), Some((_, else_value))) if then_value.ty != else_value.ty => {
+                let else_span = ast.else_branch.as_ref().unwrap().body.span();
+
+                return err(ctx
+                    .mismatched_types(then_value.ty, else_value.ty, else_span)
+                    .with_hint("expected because of this", ast.block.span()));
+            }
+            (Some((_, value)), _) | (_, Some((_, value))) => value.ty,
+        };
+
+        if ast.else_branch.is_none() && ty != Type::Void {
+            return err(Error::spanned(
+                "If without else must be of type void",
+                ast.block.span(),
+            ));
+        }
+
+        let stack_slot = if ty != Type::Void {
+            let type_id = ctx.types.get_type_id(&ty);
+            Some(ctx.stack.allocate(StackAllocation { ty: type_id }))
+        } else {
+            None
+        };
+
+        let merge_block = ctx.new_block();
+
+        for (block, value) in [then_arm, else_arm].into_iter().flatten() {
+            ctx.set_block(block);
+
+            if let Some(stack_slot) = stack_slot {
+                self.stack_store_value(ctx, stack_slot, value)?;
+            }
+
+            ctx.free(value);
+            ctx.ins().jump(merge_block);
+        }
+
+        ctx.set_block(merge_block);
+
+        match stack_slot {
+            Some(stack_slot) => Ok(Value::new(ty, stack_slot)),
+            None => Ok(Value::new(Type::Void, ctx.registers.allocate())),
+        }
+    }
+
+    pub fn compile_while(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::WhileExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let header_block = ctx.new_block();
+        let body_block = ctx.new_block();
+        let exit_block = ctx.new_block();
+
+        ctx.ins().jump(header_block);
+        ctx.set_block(header_block);
+
+        let bool = Type::Memory(MemoryType::Bool);
+        let condition = self.compile_expression(ctx, &ast.condition, Some(bool))?;
+
+        if condition.ty != bool {
+            return err(ctx.mismatched_types(bool, condition.ty, ast.condition.span()));
+        }
+
+        let register = self.compile_value(ctx, condition)?;
+        ctx.ins().branch(register, body_block, exit_block);
+        ctx.registers.free(register);
+
+        ctx.loops.push(LoopContext {
+            label: ast.label.as_ref().map(|label| label.label.clone()),
+            continue_block: header_block,
+            break_block: exit_block,
+            value_breaks: false,
+            ty: Some(Type::Void),
+            stack_slot: None,
+        });
+
+        ctx.set_block(body_block);
+        let body = self.compile_block(ctx, &ast.block, None);
+        ctx.loops.pop();
+
+        if let Some((_, value)) = Self::branch_arm(ctx, body)? {
+            if value.ty != Type::Void {
+                return err(Error::spanned(
+                    "While body must be of type void",
+                    ast.block.span(),
+                ));
+            }
+
+            ctx.free(value);
+            ctx.ins().jump(header_block);
+        }
+
+        ctx.set_block(exit_block);
+
+        Ok(Value::new(Type::Void, ctx.registers.allocate()))
+    }
+
+    pub fn compile_loop(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::LoopExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let body_block = ctx.new_block();
+        let exit_block = ctx.new_block();
+
+        ctx.ins().jump(body_block);
+
+        ctx.loops.push(LoopContext {
+            label: ast.label.as_ref().map(|label| label.label.clone()),
+            continue_block: body_block,
+            break_block: exit_block,
+            value_breaks: true,
+            ty: None,
+            stack_slot: None,
+        });
+
+        ctx.set_block(body_block);
+        let body = self.compile_block(ctx, &ast.block, None);
+        let context = ctx.loops.pop().unwrap();
+
+        if let Some((_, value)) = Self::branch_arm(ctx, body)? {
+            if value.ty != Type::Void {
+                return err(Error::spanned(
+                    "Loop body must be of type void",
+                    ast.block.span(),
+                ));
+            }
+
+            ctx.free(value);
+            ctx.ins().jump(body_block);
+        }
+
+        ctx.set_block(exit_block);
+
+        match (context.ty, context.stack_slot) {
+            (None, _) => {
+                ctx.ins().unreachable();
+
+                Err(Ok(ControlFlow::Return))
+            }
+            (Some(ty), Some(stack_slot)) => Ok(Value::new(ty, stack_slot)),
+            (Some(_), None) => Ok(Value::new(Type::Void, ctx.registers.allocate())),
+        }
+    }
+
+    pub fn compile_break(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::BreakExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let index = ctx.find_loop(ast.label.as_ref(), ast.span())?;
+
+        let value = match ast.expression {
+            Some(ref expression) => {
+                if !ctx.loops[index].value_breaks {
+                    return err(Error::spanned(
+                        "Only loop can break with a value",
+                        expression.span(),
+                    ));
+                }
+
+                let expected = ctx.loops[index].ty;
+                Some(self.compile_expression(ctx, expression, expected)?)
+            }
+            None => None,
+        };
+
+        let ty = value.map_or(Type::Void, |value| value.ty);
+
+        match ctx.loops[index].ty {
+            Some(loop_ty) if loop_ty != ty => {
+                let span = ast
+                    .expression
+                    .as_ref()
+                    .map_or(ast.span(), |expr| expr.span());
+                return err(ctx.mismatched_types(loop_ty, ty, span));
+            }
+            Some(_) => {}
+            None => {
+                ctx.loops[index].ty = Some(ty);
+
+                if ty != Type::Void {
+                    let type_id = ctx.types.get_type_id(&ty);
+                    let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+                    ctx.loops[index].stack_slot = Some(stack_slot);
+                }
+            }
+        }
+
+        if let Some(value) = value {
+            if let Some(stack_slot) = ctx.loops[index].stack_slot {
+                self.stack_store_value(ctx, stack_slot, value)?;
+            }
+
+            ctx.free(value);
+        }
+
+        let break_block = ctx.loops[index].break_block;
+        ctx.ins().jump(break_block);
+
+        Err(Ok(ControlFlow::Return))
+    }
+
+    pub fn compile_continue(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::ContinueExpression,
+    ) -> Result<Value, ErrorFlow> {
+        let index = ctx.find_loop(ast.label.as_ref(), ast.span())?;
+
+        let continue_block = ctx.loops[index].continue_block;
+        ctx.ins().jump(continue_block);
+
+        Err(Ok(ControlFlow::Return))
+    }
+
+    /// Compiles `expression`, `expected` is the type the surrounding code expects, and is used
+    /// to infer the types of literals.
+    pub fn compile_expression(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        expression: &ast::Expression,
+        expected: Option<Type>,
+    ) -> Result<Value, ErrorFlow> {
+        let value = match expression {
+            ast::Expression::Paren(paren) => self.compile_paren(ctx, paren, expected),
+            ast::Expression::Block(ast) => self.compile_block(ctx, ast, expected),
+            ast::Expression::Literal(literal) => self.compile_literal(ctx, literal, expected),
+            ast::Expression::Path(path) => self.compile_path(ctx, path),
+            ast::Expression::Call(call) => self.compile_call(ctx, call),
+            ast::Expression::Struct(ast) => self.compile_struct(ctx, ast),
+            ast::Expression::Field(ast) => self.compile_field(ctx, ast),
+            ast::Expression::Index(ast) => self.compile_index(ctx, ast),
+            ast::Expression::Array(ast) => self.compile_array(ctx, ast, expected),
+            ast::Expression::ArrayRepeat(ast) => self.compile_array_repeat(ctx, ast, expected),
+            ast::Expression::Unary(unary) => self.compile_unary(ctx, unary, expected),
+            ast::Expression::Cast(ast) => self.compile_cast(ctx, ast),
+            ast::Expression::Binary(binary) => self.compile_binary(ctx, binary, expected),
+            ast::Expression::Assign(ast) => self.compile_assign(ctx, ast),
+            ast::Expression::Return(ast) => self.compile_return(ctx, ast),
+            ast::Expression::If(ast) => self.compile_if(ctx, ast, expected),
+            ast::Expression::While(ast) => self.compile_while(ctx, ast),
+            ast::Expression::Loop(ast) => self.compile_loop(ctx, ast),
+            ast::Expression::Break(ast) => self.compile_break(ctx, ast),
+            ast::Expression::Continue(ast) => self.compile_continue(ctx, ast),
+            ast::Expression::Range(ast) => err(Error::spanned(
+                "Ranges can only be used to slice arrays and slices",
+                ast.span(),
+            )),
+        }?;
+
+        match expected {
+            Some(expected) => Ok(Self::coerce(ctx, value, expected)),
+            None => Ok(value),
+        }
+    }
+
+    /// Converts `value` to `expected` if there's an implicit conversion between them, which is
+    /// only the case for references to arrays used as slices.
+    fn coerce(ctx: &mut FunctionContext<'_>, value: Value, expected: Type) -> Value {
+        let (Type::Reference(inner), Type::Slice(expected_element)) = (value.ty, expected) else {
+            return value;
+        };
+
+        match ctx.types.get_type(inner) {
+            Type::Array { element, length } if element == expected_element => {
+                let pointer = match value.kind {
+                    ValueKind::Register(register) => register,
+                    place => Self::load(ctx, place, MemoryType::Usize),
+                };
+                let length = ctx.ins().iconst(length, MemoryType::Usize);
+
+                Value::new(expected, ValueKind::Pair(pointer, length))
+            }
+            _ => value,
+        }
+    }
+
+    pub fn compile_let(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        ast: &ast::LetStatement,
+    ) -> Result<ControlFlow, Error> {
+        let (value, ty) = if let Some(ref value) = ast.value {
+            let expected = match ast.ty {
+                Some(ref ty_ast) => {
+                    Some((self.declarations).resolve_type(ctx.types, ctx.module, &ty_ast.ty)?)
+                }
+                None => None,
+            };
+
+            let value = match self.compile_expression(ctx, &value.expression, expected) {
+                Ok(value) => value,
+                Err(flow) => return flow,
+            };
+
+            if let (Some(ty), Some(ty_ast)) = (expected, &ast.ty) {
+                if value.ty != ty {
+                    let span = ast.value.as_ref().unwrap().expression.span();
+
+                    return Err(ctx
+                        .mismatched_types(ty, value.ty, span)
+                        .with_hint("expected due to this", ty_ast.ty.span()));
+                }
+            }
+
+            (Some(value), value.ty)
+        } else {
+            let ast = (ast.ty.as_ref()).ok_or_else(|| {
+                Error::spanned(
+                    format!("Type of {} must be declared", ast.ident),
+                    ast.ident.span(),
+                )
+            })?;
+
+            let ty = self
+                .declarations
+                .resolve_type(ctx.types, ctx.module, &ast.ty)?;
+
+            (None, ty)
+        };
+
+        if self.options.warn_shadowing {
+            if let Some(variable) = ctx.find_variable(&ast.ident) {
+                let warning = Error::warning(
+                    format!("Variable {} shadows a previous binding", ast.ident),
+                    ast.ident.span(),
+                )
+                .with_hint("previously bound here", variable.ident.span());
+
+                ctx.diagnostics.push(warning);
+            }
+        }
+
+        let type_id = ctx.types.get_type_id(&ty);
+        let stack_slot = ctx.stack.allocate(StackAllocation { ty: type_id });
+
+        ctx.variables.push(FunctionVariable {
+            ident: ast.ident.clone(),
+            mutable: ast.mutability.is_some(),
+            ty,
+            stack_slot,
+        });
+
+        if let Some(value) = value {
+            self.stack_store_value(ctx, stack_slot, value)?;
+            ctx.free(value);
+        }
+
+        Ok(ControlFlow::None)
+    }
+
+    pub fn compile_statement(
+        &self,
+        ctx: &mut FunctionContext<'_>,
+        statement: &ast::Statement,
+    ) -> Result<ControlFlow, Error> {
+        match statement {
+            ast::Statement::Noop(_) => Ok(ControlFlow::None),
+            ast::Statement::Expression(expression) => {
+                match self.compile_expression(ctx, &expression.expression, None) {
+                    Ok(value) => {
+                        ctx.free(value);
+
+                        Ok(ControlFlow::None)
+                    }
+                    Err(error_flow) => error_flow,
+                }
+            }
+            ast::Statement::Let(ast) => self.compile_let(ctx, ast),
+            ast::Statement::Error(span) => Err(Error::spanned("Statement failed to parse", *span)),
+        }
     }
 
     pub fn compile_function(
         &self,
         blocks: &mut Blocks,
+        data: &mut ReadOnlyData,
         types: &mut Types,
         signatures: &mut FunctionSignatures,
+        diagnostics: &mut Diagnostics,
         id: FunctionId,
-        module: ModuleId,
     ) -> Result<Function, Error> {
-        let ast = &self.declarations.functions[&id];
+        let (module, ref ast) = self.declarations.functions[&id];
         let declaration = &self.functions.functions[&id];
         let return_type = types.get_type(declaration.return_type);
 
@@ -457,42 +2043,96 @@ impl<'a> FunctionCompiler<'a> {
             types,
             signatures,
             blocks,
+            data,
+            diagnostics,
             module,
             registers: RegisterAllocator::new(),
             stack: Stack::new(),
             current_block: entry_point,
             used_blocks: vec![entry_point],
             variables: Vec::new(),
+            loops: Vec::new(),
             return_type,
+            return_span: match ast.return_type {
+                Some(ref return_type) => return_type.ty.span(),
+                None => ast.signature_span(),
+            },
+            return_pointer: None,
         };
 
-        let mut returned = false;
+        if return_type.is_passed_by_address() {
+            ctx.return_pointer = Some(ctx.registers.allocate());
+        }
 
-        for statement in ast.block.iter() {
-            let control_flow = self.compile_statement(&mut ctx, statement)?;
+        // every argument register is allocated before copying, so copies can't reuse them
+        let registers: Vec<Vec<_>> = (declaration.arguments.iter())
+            .map(|argument| ctx.types.get_type(argument.ty).abi_types())
+            .map(|types| types.iter().map(|_| ctx.registers.allocate()).collect())
+            .collect();
+
+        for ((argument, argument_ast), registers) in (declaration.arguments.iter())
+            .zip(ast.args.iter())
+            .zip(registers.iter())
+        {
+            let ty = ctx.types.get_type(argument.ty);
+            let stack_slot = ctx.stack.allocate(StackAllocation { ty: argument.ty });
+
+            // structs and arrays are passed by address, and copied by the callee
+            let value = match registers[..] {
+                [register] if ty.is_passed_by_address() => {
+                    Value::new(ty, ValueKind::Address(register, 0))
+                }
+                [pointer, length] => Value::new(ty, ValueKind::Pair(pointer, length)),
+                [register] => Value::new(ty, register),
+                _ => Value::new(ty, ctx.registers.allocate()),
+            };
 
-            match control_flow {
-                ControlFlow::Return => {
-                    returned = true;
+            self.stack_store_value(&mut ctx, stack_slot, value)?;
 
-                    break;
-                }
-                ControlFlow::None => {}
-            }
+            ctx.variables.push(FunctionVariable {
+                ident: argument.ident.clone(),
+                mutable: argument_ast.mutability.is_some(),
+                ty,
+                stack_slot,
+            });
         }
 
-        if !returned && return_type != Type::Void {
-            Err(Error::new("Function must return"))
+        let body = match self.compile_block(&mut ctx, &ast.block, Some(return_type)) {
+            Ok(value) if value.ty == return_type => self.return_value(&mut ctx, value),
+            // a tail that isn't void has the wrong type, otherwise the return is missing
+            Ok(value) => match ast.block.tail() {
+                Some(tail) if value.ty != Type::Void => err(ctx
+                    .mismatched_types(return_type, value.ty, tail.span())
+                    .with_hint("return type declared here", ctx.return_span)),
+                _ => {
+                    let message = format!("Function {} must return", ast.ident);
+
+                    err(Error::spanned(message, ast.block.close.span())
+                        .with_hint("return type declared here", ctx.return_span))
+                }
+            },
+            flow => flow,
+        };
+
+        if let Err(Err(error)) = body {
+            Err(error)
         } else {
-            if !returned {
-                let register = ctx.registers.allocate();
-                ctx.ins().ret(register);
-            }
+            // functions outside the base module are labeled with their full path, e.g. `a::b::f`
+            let label = if module == self.declarations.base_module {
+                ast.ident.to_string()
+            } else {
+                let mut path = self.declarations.module_path(module);
+                path.push_ident(ast.ident.clone());
+                path.to_string()
+            };
 
             let function = Function {
-                label: None,
+                label: Some(label),
                 signature: declaration.signature,
-                blocks: Vec::new(),
+                return_pointer: ctx.return_pointer,
+                arguments: registers.into_iter().flatten().collect(),
+                registers: ctx.registers.count(),
+                blocks: ctx.used_blocks,
                 stack: ctx.stack,
             };
 
@@ -500,23 +2140,981 @@ impl<'a> FunctionCompiler<'a> {
         }
     }
 
+    /// Compiles every function, functions that fail are reported to `diagnostics` and left out.
     pub fn compile_program(
         &self,
         blocks: &mut Blocks,
+        data: &mut ReadOnlyData,
         types: &mut Types,
         signatures: &mut FunctionSignatures,
-    ) -> Result<Functions, Error> {
+        diagnostics: &mut Diagnostics,
+    ) -> Functions {
         let mut functions = Functions::default();
 
-        for (&module_id, module) in self.declarations.modules.iter() {
-            for function_id in module.functions.values().copied() {
-                let function =
-                    self.compile_function(blocks, types, signatures, function_id, module_id)?;
+        let mut ids: Vec<_> = self.declarations.functions.keys().copied().collect();
+        ids.sort();
 
+        for function_id in ids {
+            let function =
+                self.compile_function(blocks, data, types, signatures, diagnostics, function_id);
+
+            if let Some(function) = diagnostics.report(function) {
                 functions.insert(function_id, function);
             }
         }
 
-        Ok(functions)
+        functions
+    }
+}
+
+#[cfg(all(test, feature = "parse"))]
+mod tests {
+    use crate::{
+        compiler::CompileOptions,
+        span::Span,
+        test_support::{compile, compile_error, compile_with, run, try_run},
+    };
+
+    #[test]
+    fn literal_inference() {
+        let program = compile(
+            "
+            struct Pair {
+                a: u8,
+                b: i64,
+            }
+
+            fn widen(x: u64) -> u64 {
+                return x + 1;
+            }
+
+            fn main() -> u64 {
+                let a: u64 = 18446744073709551615;
+                let b = 1 + 2u8;
+                let pair = Pair { a: 255, b: 5000000000 };
+                let c: f32 = 0.5;
+                return widen(4) + a;
+            }
+
+            fn half() -> f32 {
+                return 0.25 + 0.25;
+            }
+
+            fn big() -> i64 {
+                return 10i64 + 0x7fffffff;
+            }
+
+            fn pick(c: bool) -> i64 {
+                let x = if c { 5i64 } else if !c { 6000000000 } else { 7 };
+                x
+            }
+
+            fn shift(x: u8) -> i32 {
+                let y = 256 << x;
+                y
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[]), 4);
+        assert_eq!(run(&program, "pick", &[0]), 6000000000);
+        assert_eq!(run(&program, "shift", &[2]), 1024);
+        assert_eq!(f32::from_bits(run(&program, "half", &[]) as u32), 0.5);
+        assert_eq!(run(&program, "big", &[]), 0x7fffffff + 10);
+
+        let cases = [
+            ("fn f() { let a: u8 = 256; }", "256"),
+            ("fn f() { let a = 2147483648; }", "2147483648"),
+            ("fn f(x: i8) { let a = x + 128; }", "128"),
+            ("fn f() { let a = 1u16 + 65536; }", "65536"),
+            ("fn f() -> f32 { return 1e39; }", "1e39"),
+        ];
+
+        for (source, span) in cases {
+            let error = compile_error(source);
+            let text = error.span().unwrap();
+
+            assert_eq!(&source[text.index()..text.end()], span, "{}", source);
+        }
+
+        let error = compile_error("fn f() { let a: i32 = 1u8; }");
+        assert_eq!(error.message(), "Mismatched types, expected i32 found u8");
+    }
+
+    #[test]
+    fn shadowing_warnings() {
+        let source = "fn f(a: i32) { let a = 1; { let b = a; } let b = 2; { let a = b; } }";
+
+        let options = CompileOptions {
+            warn_shadowing: true,
+            ..Default::default()
+        };
+
+        let (program, diagnostics) = compile_with(source, &options);
+        assert!(program.is_some());
+
+        let warnings: Vec<_> = (diagnostics.warnings())
+            .map(|warning| {
+                (
+                    warning.span().unwrap().index(),
+                    warning.hints()[0].span().index(),
+                )
+            })
+            .collect();
+
+        // the first `b` is out of scope when the second is declared
+        let first_a = source.find("let a").unwrap() + 4;
+        let second_a = source.rfind("let a").unwrap() + 4;
+        assert_eq!(warnings, [(first_a, 5), (second_a, first_a)]);
+        assert_eq!(
+            diagnostics.warnings().next().unwrap().message(),
+            "Variable a shadows a previous binding"
+        );
+    }
+
+    #[test]
+    fn bools() {
+        let program = compile(
+            "
+            struct Flags {
+                a: bool,
+                b: bool,
+                c: u8,
+            }
+
+            fn not(x: bool) -> bool {
+                !x
+            }
+
+            fn main(x: bool) -> u8 {
+                let mut flags = Flags { a: x, b: true, c: 7 };
+                flags.b = not(flags.a) == false;
+
+                let mut count = 0u8;
+
+                if flags.a && true {
+                    count += 1;
+                }
+
+                if flags.b || false {
+                    count += 2;
+                }
+
+                count * 10 + flags.c + flags.a as u8
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[0]), 7);
+        assert_eq!(run(&program, "main", &[1]), 38);
+
+        let error = compile_error("fn f() -> i32 { true as i32 + 1 as bool }");
+        assert_eq!(error.message(), "Cannot cast i32 to bool");
+
+        let error = compile_error("fn f() { let x: i32 = false; }");
+        assert_eq!(error.message(), "Mismatched types, expected i32 found bool");
+    }
+
+    #[test]
+    fn arrays() {
+        let program = compile(
+            "
+            struct Grid {
+                cells: [[u8; 3]; 2],
+                size: i32,
+            }
+
+            fn fill(values: &[i32; 4], value: i32) {
+                let mut i = 0;
+
+                while i < 4 {
+                    values[i] = value + i;
+                    i += 1;
+                }
+            }
+
+            fn reverse(values: [i32; 4]) -> [i32; 4] {
+                [values[3], values[2], values[1], values[0]]
+            }
+
+            fn main(x: i32) -> i32 {
+                let mut values = [0; 4];
+                fill(&values, x);
+
+                let values = reverse(values);
+                let mut grid = Grid { cells: [[1u8; 3]; 2], size: 6 };
+                grid.cells[1][2] += 4;
+
+                let empty: [bool; 0] = [];
+
+                values[0] * 100 + values[3] + grid.cells[1][2] as i32 + grid.cells[0][2] as i32
+            }
+
+            fn get(index: i64) -> u8 {
+                let values = [1u8, 2, 3];
+                values[index]
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[10]), 1300 + 10 + 5 + 1);
+        assert_eq!(run(&program, "get", &[2]), 3);
+
+        let source = "values[index]";
+
+        for index in [3, -1i64 as u64] {
+            let error = try_run(&program, "get", &[index]).unwrap_err();
+
+            assert_eq!(error.message(), "Index out of bounds");
+            assert_eq!(error.span().unwrap().length(), source.len());
+        }
+
+        let cases = [
+            ("fn f(x: i32) -> i32 { x[0] }", "Cannot index into type i32"),
+            (
+                "fn f() -> i32 { [1, 2][true] }",
+                "Array index must be an integer, found bool",
+            ),
+            (
+                "fn f() { let x = [1, 2u8, 3.0]; }",
+                "Mismatched types, expected i32 found u8",
+            ),
+            (
+                "fn f() { let x = []; }",
+                "Type of empty array must be known",
+            ),
+            (
+                "fn f() { let x = [1; 2]; x[0] = 2; }",
+                "Cannot assign to immutable variable x",
+            ),
+        ];
+
+        for (source, message) in cases {
+            assert_eq!(compile_error(source).message(), message, "{}", source);
+        }
+    }
+
+    #[test]
+    fn slices() {
+        let program = compile(
+            "
+            fn sum(values: &[i32]) -> i32 {
+                let mut total = 0;
+                let mut i: usize = 0;
+
+                while i < values.len() {
+                    total += values[i];
+                    i += 1;
+                }
+
+                total
+            }
+
+            fn fill(values: &[i32], value: i32) {
+                let mut i: usize = 0;
+
+                while i < values.len() {
+                    values[i] = value;
+                    i += 1;
+                }
+            }
+
+            fn main(x: i32) -> i32 {
+                let mut values = [1, 2, 3, 4, 5];
+                let all: &[i32] = &values;
+
+                fill(all[3..], x);
+
+                let total = sum(all) * 1000 + sum(all[1..3]) * 10;
+                let lengths = values.len() + all[..2].len() + \"abc\".len() + all[2..2].len();
+
+                total + lengths as i32
+            }
+
+            fn slice(start: usize, end: usize) -> usize {
+                let values = [1u8, 2, 3];
+                let slice: &[u8] = &values;
+                slice[start..end].len()
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[10]), 26_000 + 50 + 5 + 2 + 3);
+        assert_eq!(run(&program, "slice", &[1, 3]), 2);
+
+        let source = "slice[start..end]";
+
+        for (start, end) in [(2, 1), (0, 4)] {
+            let error = try_run(&program, "slice", &[start, end]).unwrap_err();
+
+            assert_eq!(error.message(), "Index out of bounds");
+            assert_eq!(error.span().unwrap().length(), source.len());
+        }
+
+        let cases = [
+            (
+                "fn f(x: [i32]) {}",
+                "Slices must be behind a reference, like &[T]",
+            ),
+            (
+                "fn f() { let x = 1..2; }",
+                "Ranges can only be used to slice arrays and slices",
+            ),
+            (
+                "fn f() { let x: &[i32] = &[1u8, 2]; }",
+                "Mismatched types, expected &[i32] found &[u8; 2]",
+            ),
+            (
+                "fn f(x: &[i32]) -> i32 { x[true..] }",
+                "Array index must be an integer, found bool",
+            ),
+            (
+                "fn f(x: &[i32]) -> usize { x.len(1) }",
+                "Method len takes 0 arguments but 1 were given",
+            ),
+            (
+                "fn f() { let x = \"a\".first(); }",
+                "No method first on type str",
+            ),
+            (
+                "struct S { len: usize } fn f(s: S) -> usize { s.len() }",
+                "Cannot call value of type usize",
+            ),
+        ];
+
+        for (source, message) in cases {
+            assert_eq!(compile_error(source).message(), message, "{}", source);
+        }
+    }
+
+    #[test]
+    fn operators() {
+        let program = compile(
+            "
+            fn arith(a: i32, b: i32) -> i32 {
+                let x = (a - b) * 3;
+                return (x / 2) % 7;
+            }
+
+            fn unsigned(a: u8, b: u8) -> u8 {
+                return (a / b) + (a % b);
+            }
+
+            fn bits(a: u32, b: u32) -> u32 {
+                return ((a & b) | (a ^ b)) ^ !a;
+            }
+
+            fn shifts(a: i8, b: u8) -> i8 {
+                return (a >> b) + (a << 1);
+            }
+
+            fn compare(a: i16, b: i16) -> bool {
+                return (a < b) == (-a > -b);
+            }
+
+            fn unsigned_less(a: u64, b: u64) -> bool {
+                return a < b;
+            }
+
+            fn float_compare(a: f64, b: f64) -> bool {
+                return a <= b;
+            }
+
+            fn negate(x: f32) -> f32 {
+                return -x * 2.0;
+            }
+
+            fn not(a: bool, b: bool) -> bool {
+                return !a ^ b;
+            }
+
+            fn min() -> i8 {
+                return -128;
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "arith", &[10, 3]), 3);
+        assert_eq!(run(&program, "arith", &[-10i64 as u64, 3]), -5i64 as u64);
+        assert_eq!(run(&program, "unsigned", &[200, 7]), 28 + 4);
+        assert_eq!(
+            run(&program, "bits", &[0b1100, 0b1010]),
+            0b1110 ^ !0b1100u32 as u64
+        );
+        assert_eq!(run(&program, "shifts", &[-8i64 as u64, 1]), -20i64 as u64);
+        assert_eq!(run(&program, "shifts", &[-8i64 as u64, 9]), -20i64 as u64);
+        assert_eq!(run(&program, "compare", &[-1i64 as u64, 2]), 1);
+        assert_eq!(run(&program, "unsigned_less", &[u64::MAX, 2]), 0);
+        assert_eq!(
+            run(&program, "float_compare", &[1f64.to_bits(), 1f64.to_bits()]),
+            1
+        );
+        assert_eq!(
+            run(
+                &program,
+                "float_compare",
+                &[f64::NAN.to_bits(), 1f64.to_bits()]
+            ),
+            0
+        );
+        assert_eq!(
+            run(&program, "negate", &[1.5f32.to_bits() as u64]),
+            (-3f32).to_bits() as u64
+        );
+        assert_eq!(run(&program, "not", &[1, 0]), 0);
+        assert_eq!(run(&program, "not", &[0, 0]), 1);
+        assert_eq!(run(&program, "min", &[]), -128i64 as u64);
+
+        assert!(try_run(&program, "unsigned", &[1, 0]).is_err());
+
+        let cases = [
+            (
+                "fn f(a: u8) -> u8 { return -a; }",
+                "Cannot apply operator - to u8",
+            ),
+            (
+                "fn f(a: bool) -> bool { return a + a; }",
+                "Operator not supported for bool and bool",
+            ),
+            (
+                "fn f(a: f32) -> f32 { return a % a; }",
+                "Operator not supported for f32 and f32",
+            ),
+            (
+                "fn f(a: i32, b: i64) -> bool { return a < b; }",
+                "Operator not supported for i32 and i64",
+            ),
+            (
+                "fn f() -> i8 { return -129; }",
+                "Integer literal out of range for i8, the minimum is -128",
+            ),
+            (
+                "fn f() -> u8 { return -1; }",
+                "Cannot apply operator - to u8",
+            ),
+            (
+                "fn f(a: i32) -> bool { return a && a; }",
+                "Mismatched types, expected bool found i32",
+            ),
+        ];
+
+        for (source, message) in cases {
+            assert_eq!(compile_error(source).message(), message, "{}", source);
+        }
+    }
+
+    #[test]
+    fn division_traps() {
+        let source = "
+            fn div(a: i8, b: i8) -> i8 {
+                a / b
+            }
+
+            fn rem(a: u32, b: u32) -> u32 {
+                let mut a = a;
+                a %= b;
+                a
+            }
+            ";
+        let program = compile(source);
+
+        assert_eq!(run(&program, "div", &[-128i64 as u64, 2]), -64i64 as u64);
+        assert_eq!(run(&program, "rem", &[7, 4]), 3);
+
+        let cases = [
+            ("div", [1, 0], "Division by zero", "a / b"),
+            (
+                "div",
+                [-128i64 as u64, -1i64 as u64],
+                "Division overflow",
+                "a / b",
+            ),
+            ("rem", [1, 0], "Division by zero", "a %= b"),
+        ];
+
+        for (label, arguments, message, span) in cases {
+            let error = try_run(&program, label, &arguments).unwrap_err();
+            let error_span = error.span().unwrap();
+
+            assert_eq!(error.message(), message);
+            assert_eq!(error_span.index(), source.find(span).unwrap());
+            assert_eq!(error_span.length(), span.len());
+        }
+    }
+
+    #[test]
+    fn short_circuit() {
+        let program = compile(
+            "
+            struct Counter {
+                count: i32,
+            }
+
+            fn bump(counter: &Counter) -> bool {
+                counter.count = counter.count + 1;
+                return counter.count > 1;
+            }
+
+            fn run(a: bool, b: bool) -> i32 {
+                let counter = Counter { count: 0 };
+                let x = a && bump(&counter);
+                let y = b || bump(&counter);
+
+                if x || y {
+                    return counter.count + 10;
+                }
+
+                return counter.count;
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "run", &[1, 1]), 11);
+        assert_eq!(run(&program, "run", &[0, 1]), 10);
+        assert_eq!(run(&program, "run", &[1, 0]), 12);
+        assert_eq!(run(&program, "run", &[0, 0]), 1);
+    }
+
+    #[test]
+    fn assignment() {
+        let program = compile(
+            "
+            struct Point {
+                x: i32,
+                y: i32,
+            }
+
+            fn set(p: &i32, value: i32) {
+                *p = value;
+            }
+
+            fn main(mut a: i32) -> i32 {
+                let mut x = 1;
+                x = x + a;
+                x += 10;
+                x *= 2;
+                x -= 1;
+                x /= 3;
+                x %= 5;
+                x <<= 3;
+                x >>= 1;
+                x |= 1;
+                x &= 7;
+                x ^= 2;
+
+                let mut point = Point { x: 0, y: 0 };
+                point.x = x;
+                point.y += 4;
+
+                set(&a, 100);
+                a += point.x + point.y;
+
+                return a;
+            }
+            ",
+        );
+
+        // ((((((1 + 2 + 10) * 2 - 1) / 3) % 5) << 3) >> 1 | 1) & 7 ^ 2 = 7
+        assert_eq!(run(&program, "main", &[2]), 100 + 7 + 4);
+
+        let cases = [
+            ("fn f() { let x = 1; x = 2; }", "x", Some("x")),
+            ("fn f(x: i32) { x += 2; }", "x", Some("x")),
+            (
+                "struct A { a: i32 } fn f() { let x = A { a: 1 }; x.a = 2; }",
+                "x.a",
+                Some("x"),
+            ),
+            ("fn f() { f = f; }", "f", None),
+            ("fn f() { let mut x = 1; x = 1u8; }", "1u8", None),
+            ("fn f(a: bool) { let mut x = 1; x += a; }", "+=", None),
+        ];
+
+        for (source, span, hint) in cases {
+            let error = compile_error(source);
+
+            let text = |span: Span| &source[span.index()..span.end()];
+
+            assert_eq!(text(error.span().unwrap()), span, "{}", source);
+            assert_eq!(error.hints().first().map(|hint| text(hint.span())), hint);
+        }
+    }
+
+    #[test]
+    fn immutable_assignment() {
+        let source = "fn f(a: i32) { a = 1; }";
+        let error = compile_error(source);
+
+        let text = |span: Span| &source[span.index()..span.end()];
+
+        assert_eq!(error.message(), "Cannot assign to immutable variable a");
+        assert_eq!(error.span().map(text), Some("a"));
+        assert_eq!(error.span().unwrap().index(), source.find("a =").unwrap());
+
+        let hint = &error.hints()[0];
+        assert_eq!(hint.message(), "declared here, consider adding mut");
+        assert_eq!(hint.span().index(), source.find("a:").unwrap());
+    }
+
+    #[test]
+    fn structs() {
+        let program = compile(
+            "
+            struct Point {
+                x: i32,
+                y: i32,
+            }
+
+            struct Line {
+                flag: u8,
+                start: Point,
+                end: Point,
+            }
+
+            fn point(x: i32, y: i32) -> Point {
+                return Point { y: y, x: x };
+            }
+
+            fn length(line: Line) -> i32 {
+                return line.end.x + line.end.y + line.start.x + line.start.y;
+            }
+
+            fn move_end(line: &Line, x: i32) {
+                line.end.x = x;
+            }
+
+            fn main(x: i32, flag: u8) -> i32 {
+                let mut line = Line { flag: flag, start: point(1, 2), end: point(x, 4) };
+                let before = length(line);
+
+                move_end(&line, 10);
+                line.start = line.end;
+
+                let y = &line.start.y;
+                return before + length(line) + *y;
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[3, 1]), 10 + 28 + 4);
+    }
+
+    #[test]
+    fn invalid_structs() {
+        let cases = [
+            (
+                "struct A { a: A }",
+                "Struct A contains itself and has infinite size",
+            ),
+            (
+                "struct A { b: B } struct B { a: A }",
+                "Struct A contains itself and has infinite size",
+            ),
+            (
+                "struct A { x: i32, x: i32 }",
+                "Field x declared more than once in A",
+            ),
+            (
+                "struct A { x: i32 } fn f() { let a = A {}; }",
+                "Missing field x in initializer of A",
+            ),
+            (
+                "struct A { x: i32 } fn f() { let a = A { x: 1, y: 2 }; }",
+                "Struct A has no field y",
+            ),
+            (
+                "struct A { x: i32 } fn f(a: A) -> i32 { return a.y; }",
+                "Struct A has no field y",
+            ),
+        ];
+
+        for (source, message) in cases {
+            assert_eq!(compile_error(source).message(), message, "{}", source);
+        }
+    }
+
+    #[test]
+    fn all_branches_return() {
+        let program = compile(
+            "
+            fn pick(a: bool, x: i32, y: i32) -> i32 {
+                if a {
+                    return x;
+                } else {
+                    return y;
+                }
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "pick", &[1, 5, 6]), 5);
+        assert_eq!(run(&program, "pick", &[0, 5, 6]), 6);
+    }
+
+    #[test]
+    fn missing_return() {
+        let source = "fn pick(a: bool) -> i32 { if a { return 1; } }";
+        let error = compile_error(source);
+
+        let text = |span: Span| &source[span.index()..span.end()];
+
+        assert_eq!(error.message(), "Function pick must return");
+        assert_eq!(error.span().unwrap().index(), source.len() - 1);
+        assert_eq!(text(error.hints()[0].span()), "i32");
+    }
+
+    #[test]
+    fn break_and_continue() {
+        let program = compile(
+            "
+            fn pick(a: bool, x: i32) -> i32 {
+                let y = loop {
+                    if a {
+                        break x + x;
+                    }
+
+                    break x;
+                };
+
+                'outer: loop {
+                    while a {
+                        loop {
+                            break 'outer;
+                        }
+                    }
+
+                    return y;
+                }
+
+                return loop {
+                    if a {
+                        break y + 1;
+                    } else {
+                        continue;
+                    }
+                };
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "pick", &[1, 3]), 7);
+        assert_eq!(run(&program, "pick", &[0, 3]), 3);
+    }
+
+    #[test]
+    fn invalid_break() {
+        // each source, with the error message and the text of its span
+        let cases = [
+            (
+                "fn f() { break; }",
+                "Cannot break or continue outside of a loop",
+                "break",
+            ),
+            (
+                "fn f() { loop { continue 'a; } }",
+                "Use of undeclared label 'a",
+                "'a",
+            ),
+            (
+                "fn f(a: bool) { while a { break 1; } }",
+                "Only loop can break with a value",
+                "1",
+            ),
+            (
+                "fn f(a: bool) { loop { if a { break 1; } break; } }",
+                "Mismatched types, expected i32 found void",
+                "break",
+            ),
+        ];
+
+        for (source, message, span) in cases {
+            let error = compile_error(source);
+            let text = |span: Span| &source[span.index()..span.end()];
+
+            assert_eq!(error.message(), message, "{}", source);
+            assert_eq!(text(error.span().unwrap()), span, "{}", source);
+        }
+    }
+
+    #[test]
+    fn string_literals() {
+        let program = compile(
+            r#"
+            fn greeting() -> str {
+                return "hello\n";
+            }
+
+            fn main(x: i32) -> i32 {
+                let a: str = greeting();
+                let b = "hello\n";
+                let c = "";
+                return x;
+            }
+            "#,
+        );
+
+        let data: Vec<_> = program.data.iter().map(|(_, bytes)| bytes).collect();
+        assert_eq!(data, [&b"hello\n"[..], b""]);
+        assert_eq!(run(&program, "main", &[3]), 3);
+
+        let error = compile_error(r#"fn f() { let x: i32 = "a"; }"#);
+        assert_eq!(error.message(), "Mismatched types, expected i32 found str");
+    }
+
+    #[test]
+    fn floats() {
+        let program = compile(
+            "
+            fn add(a: f64, b: f64) -> f64 {
+                return a + b + 0.5;
+            }
+
+            fn to_int(x: f64) -> i32 {
+                return x as i32;
+            }
+
+            fn to_byte(x: f64) -> u8 {
+                return x as u8;
+            }
+
+            fn halve(x: i32) -> f32 {
+                let y = x as f32 + 0.5 as f32;
+                return y;
+            }
+
+            fn widen(x: f32) -> f64 {
+                return x as f64;
+            }
+            ",
+        );
+
+        let add = run(&program, "add", &[1.25f64.to_bits(), 2.0f64.to_bits()]);
+        assert_eq!(f64::from_bits(add), 3.75);
+
+        assert_eq!(
+            run(&program, "to_int", &[(-1.9f64).to_bits()]),
+            -1i64 as u64
+        );
+        assert_eq!(run(&program, "to_byte", &[300.0f64.to_bits()]), 255);
+        assert_eq!(run(&program, "to_byte", &[(-3.0f64).to_bits()]), 0);
+        assert_eq!(run(&program, "to_byte", &[f64::NAN.to_bits()]), 0);
+
+        let halve = run(&program, "halve", &[3]);
+        assert_eq!(f32::from_bits(halve as u32), 3.5);
+
+        let widen = run(&program, "widen", &[1.5f32.to_bits() as u64]);
+        assert_eq!(f64::from_bits(widen), 1.5);
+
+        let error = compile_error("fn f(x: &i32) -> i64 { return x as i64; }");
+        assert_eq!(error.message(), "Cannot cast &i32 to i64");
+
+        let error = compile_error("fn f(x: f32) -> f32 { return x + 1.0f64; }");
+        assert_eq!(error.message(), "Operator not supported for f32 and f64");
+    }
+
+    #[test]
+    fn arguments() {
+        let program = compile("fn add(a: i32, b: i32) -> i32 { return a + b; }");
+
+        assert_eq!(run(&program, "add", &[2, 3]), 5);
+        assert_eq!(
+            run(&program, "add", &[i32::MAX as u64, 1]),
+            i32::MIN as i64 as u64
+        );
+    }
+
+    #[test]
+    fn references() {
+        let program = compile(
+            "
+            fn main() -> i32 {
+                let x = foo(2);
+                let y = &x;
+                return *y + bar(&x);
+            }
+
+            fn foo(x: i32) -> i32 {
+                let y = &x;
+                return *y;
+            }
+
+            fn bar(x: &i32) -> i32 {
+                return *x;
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[]), 4);
+    }
+
+    #[test]
+    fn if_else() {
+        let program = compile(
+            "
+            fn pick(a: bool, b: bool) -> i32 {
+                if a {
+                    return 1;
+                } else if b {
+                    return 2;
+                }
+
+                let x = 3;
+
+                if b {} else {
+                    return x;
+                }
+
+                return 4;
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "pick", &[1, 0]), 1);
+        assert_eq!(run(&program, "pick", &[1, 1]), 1);
+        assert_eq!(run(&program, "pick", &[0, 1]), 2);
+        assert_eq!(run(&program, "pick", &[0, 0]), 3);
+    }
+
+    #[test]
+    fn block_expressions() {
+        let program = compile(
+            "
+            struct Pair {
+                a: i32,
+                b: i32,
+            }
+
+            fn pair(a: i32) -> Pair {
+                Pair { a: a, b: { let b = a * 2; b } }
+            }
+
+            fn max(a: i32, b: i32) -> i32 {
+                if a > b { a } else { b }
+            }
+
+            fn main(x: i32) -> i32 {
+                let y = {
+                    let x = x + 1;
+                    x * 10
+                };
+
+                let z: u8 = { 2 };
+
+                pair(y).b + max(x, 3) + z as i32
+            }
+            ",
+        );
+
+        assert_eq!(run(&program, "main", &[1]), 40 + 3 + 2);
+        assert_eq!(run(&program, "main", &[5]), 120 + 5 + 2);
+
+        let error = compile_error("fn f() -> i32 { { let x = 1; } x }");
+        assert_eq!(error.message(), "x not defined");
+
+        let error = compile_error("fn f() -> i32 { 1u8 }");
+        assert_eq!(error.message(), "Mismatched types, expected i32 found u8");
+
+        let error = compile_error("fn f() -> i32 { 1; }");
+        assert_eq!(error.message(), "Function f must return");
     }
 }
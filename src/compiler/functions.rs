@@ -2,8 +2,9 @@ use std::collections::HashMap;
 
 use crate::{
     ast,
+    diagnostic::Diagnostics,
     error::Error,
-    id::{FunctionId, FunctionIds, FunctionSignatureId, FunctionSignatureIds, TypeId},
+    id::{FunctionId, FunctionIds, FunctionSignatureId, FunctionSignatureIds, ModuleId, TypeId},
 };
 
 use super::{Declarations, Type, Types};
@@ -54,11 +55,12 @@ impl FunctionArgument {
     pub fn from_ast(
         declarations: &Declarations,
         types: &mut Types,
+        module: ModuleId,
         ast: &ast::FunctionArgument,
     ) -> Result<Self, Error> {
         Ok(Self {
             ident: ast.ident.clone(),
-            ty: declarations.resolve_type_id(types, &ast.ty.ty)?,
+            ty: declarations.resolve_type_id(types, module, &ast.ty.ty)?,
         })
     }
 }
@@ -75,16 +77,22 @@ impl FunctionDeclaration {
         declarations: &Declarations,
         types: &mut Types,
         signatures: &mut FunctionSignatures,
+        module: ModuleId,
         ast: &ast::FunctionDeclaration,
     ) -> Result<Self, Error> {
         let mut arguments = Vec::with_capacity(ast.args.len());
 
         for arg in ast.args.iter() {
-            arguments.push(FunctionArgument::from_ast(declarations, types, arg)?);
+            arguments.push(FunctionArgument::from_ast(
+                declarations,
+                types,
+                module,
+                arg,
+            )?);
         }
 
         let return_type = if let Some(ref return_type) = ast.return_type {
-            declarations.resolve_type_id(types, &return_type.ty)?
+            declarations.resolve_type_id(types, module, &return_type.ty)?
         } else {
             types.get_type_id(&Type::Void)
         };
@@ -109,18 +117,25 @@ pub struct FunctionDeclarations {
 }
 
 impl FunctionDeclarations {
+    /// Resolves the signatures of every function, reporting errors to `diagnostics`.
     pub fn new(
         declarations: &Declarations,
         types: &mut Types,
         signatures: &mut FunctionSignatures,
-    ) -> Result<Self, Error> {
+        diagnostics: &mut Diagnostics,
+    ) -> Self {
         let mut this = Self::default();
 
-        for (&id, function) in declarations.functions.iter() {
-            this.insert(declarations, types, signatures, id, function)?;
+        let mut ids: Vec<_> = declarations.functions.keys().copied().collect();
+        ids.sort();
+
+        for id in ids {
+            let (module, ref function) = declarations.functions[&id];
+            let result = this.insert(declarations, types, signatures, module, id, function);
+            diagnostics.report(result);
         }
 
-        Ok(this)
+        this
     }
 
     pub fn insert(
@@ -128,10 +143,12 @@ impl FunctionDeclarations {
         path_stage: &Declarations,
         types: &mut Types,
         signatures: &mut FunctionSignatures,
+        module: ModuleId,
         id: FunctionId,
         function: &ast::FunctionDeclaration,
     ) -> Result<(), Error> {
-        let function = FunctionDeclaration::from_ast(path_stage, types, signatures, function)?;
+        let function =
+            FunctionDeclaration::from_ast(path_stage, types, signatures, module, function)?;
         self.functions.insert(id, function);
 
         Ok(())
@@ -12,27 +12,157 @@ pub use value::*;
 
 use crate::{
     ast,
-    error::Error,
-    ir::{Blocks, Program},
+    diagnostic::Diagnostics,
+    ir::{Blocks, Program, ReadOnlyData},
 };
 
-pub fn compile_program(program: ast::Program) -> Result<Program, Error> {
+/// Options for [`compile_program`].
+#[derive(Clone, Debug)]
+pub struct CompileOptions {
+    /// Warn when a `let` shadows a variable that is still in scope.
+    pub warn_shadowing: bool,
+    /// Trap when an array is indexed out of bounds, instead of accessing memory outside of it.
+    pub bounds_checks: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            warn_shadowing: false,
+            bounds_checks: true,
+        }
+    }
+}
+
+/// Compiles `program`, reporting errors and warnings to `diagnostics`.
+///
+/// Each stage reports every error it finds, but later stages only run if the earlier ones
+/// succeeded. Returns [`None`] if any errors were reported.
+pub fn compile_program(
+    program: ast::Program,
+    options: &CompileOptions,
+    diagnostics: &mut Diagnostics,
+) -> Option<Program> {
     let mut types = Types::default();
     let mut signatures = FunctionSignatures::default();
-    let declarations = Declarations::from_program(program)?;
+
+    let declarations = Declarations::from_program(program, diagnostics);
+    if diagnostics.has_errors() {
+        return None;
+    }
+
+    declarations.resolve_structs(&mut types, diagnostics);
+    if diagnostics.has_errors() {
+        return None;
+    }
+
     let function_declarations =
-        FunctionDeclarations::new(&declarations, &mut types, &mut signatures)?;
-    let function_compiler = FunctionCompiler::new(&declarations, &function_declarations);
+        FunctionDeclarations::new(&declarations, &mut types, &mut signatures, diagnostics);
+    if diagnostics.has_errors() {
+        return None;
+    }
+
+    let function_compiler = FunctionCompiler::new(&declarations, &function_declarations, options);
 
     let mut blocks = Blocks::new();
-    let functions = function_compiler.compile_program(&mut blocks, &mut types, &mut signatures)?;
+    let mut data = ReadOnlyData::new();
+    let functions = function_compiler.compile_program(
+        &mut blocks,
+        &mut data,
+        &mut types,
+        &mut signatures,
+        diagnostics,
+    );
+    if diagnostics.has_errors() {
+        return None;
+    }
 
     let program = Program {
         types,
         signatures,
         blocks,
         functions,
+        data,
+    };
+
+    Some(program)
+}
+
+#[cfg(all(test, feature = "parse"))]
+mod tests {
+    use crate::{
+        span::Span,
+        test_support::{compile_error, try_compile},
     };
 
-    Ok(program)
+    #[test]
+    fn error_spans() {
+        // each source, with the text of the error span and of the first hint
+        let cases = [
+            ("fn f(a: i32) -> u8 { return a; }", "a", Some("u8")),
+            ("fn f() {} fn g() { f(1); }", "f(1)", Some("fn f()")),
+            ("fn f(a: u8) {} fn g(b: i32) { f(b); }", "b", Some("a: u8")),
+            (
+                "struct A { x: i32 } fn f() -> A { return A { y: 1 }; }",
+                "y",
+                Some("A"),
+            ),
+            (
+                "struct A { x: i32 } fn f() -> A { return A {}; }",
+                "A",
+                Some("x"),
+            ),
+            ("fn f() -> i32 { }", "}", Some("i32")),
+            ("fn f() { g(); }", "g", None),
+            ("fn f() { a::g(); }", "a", None),
+            ("fn f(a: i32) { let b: u8 = a; }", "a", Some("u8")),
+            ("fn f(a: i32) { if a { } }", "a", None),
+            ("fn f() { loop { break 'a; } }", "'a", None),
+            ("struct A { a: A }", "A", Some("a")),
+        ];
+
+        for (source, span, hint) in cases {
+            let error = compile_error(source);
+
+            let text = |span: Span| &source[span.index()..span.end()];
+
+            assert_eq!(text(error.span().unwrap()), span, "{}", source);
+            assert_eq!(error.hints().first().map(|hint| text(hint.span())), hint);
+        }
+    }
+
+    #[test]
+    fn multiple_errors() {
+        let source = "
+            fn a() -> i32 { return b; }
+            fn b() { let x: u8 = 256; }
+            fn c() -> i32 { return 1; }
+            fn d() { e(); }
+        ";
+
+        let diagnostics = try_compile(source).unwrap_err();
+        let messages: Vec<_> = diagnostics.errors().map(|error| error.message()).collect();
+
+        assert_eq!(
+            messages,
+            [
+                "Mismatched types, expected i32 found function",
+                "Integer literal out of range for u8, the maximum is 255",
+                "e not defined",
+            ]
+        );
+
+        // later stages don't run after errors, so `C` and `g` aren't reported
+        let source = "fn a() {} fn a() {} struct B { c: C } use d::e; fn f() { g(); }";
+        let diagnostics = try_compile(source).unwrap_err();
+        let messages: Vec<_> = diagnostics.errors().map(|error| error.message()).collect();
+
+        assert_eq!(
+            messages,
+            [
+                "Function a declared more than once",
+                "Unresolved import d::e"
+            ]
+        );
+    }
 }
@@ -1,16 +1,30 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     ast,
+    error::Error,
     id::{FunctionSignatureId, StructId, TypeId, TypeIds},
     ir::MemoryType,
+    span::Spanned,
 };
 
+/// The size of pointers in bytes, that the compiler lays out types for.
+pub const PTR_SIZE: u64 = 8;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Type {
     Void,
     Memory(MemoryType),
+    /// A string slice, a pointer to UTF-8 bytes followed by the length in bytes.
+    Str,
     Struct(StructId),
+    /// A fixed-size array, the elements are laid out one after another.
+    Array {
+        element: TypeId,
+        length: u64,
+    },
+    /// A reference to a sequence of elements, a pointer followed by the number of elements.
+    Slice(TypeId),
     Reference(TypeId),
     Function(FunctionSignatureId),
 }
@@ -23,12 +37,67 @@ impl Type {
         }
     }
 
-    pub fn is_reference(&self) -> bool {
+    pub fn is_float(&self) -> bool {
         match self {
-            Self::Reference(_) => true,
+            Self::Memory(ty) => ty.is_float(),
             _ => false,
         }
     }
+
+    pub fn is_reference(&self) -> bool {
+        matches!(self, Self::Reference(_))
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self, Self::Struct(_))
+    }
+
+    /// Returns true if `self` doesn't fit in a register, and is passed around by address like
+    /// structs.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(
+            self,
+            Self::Str | Self::Struct(_) | Self::Array { .. } | Self::Slice(_)
+        )
+    }
+
+    /// Returns true if `self` is a pointer and a length, which can be held in two registers,
+    /// see [`ValueKind::Pair`](super::ValueKind::Pair).
+    pub fn is_fat(&self) -> bool {
+        matches!(self, Self::Str | Self::Slice(_))
+    }
+
+    /// Returns true if `self` is passed to and returned from functions by address, which is the
+    /// case for aggregates that aren't [fat](Self::is_fat).
+    pub fn is_passed_by_address(&self) -> bool {
+        self.is_aggregate() && !self.is_fat()
+    }
+
+    /// Gets the types of the registers `self` is passed to and returned from functions in.
+    ///
+    /// Void takes no registers, and fat values take two, their pointer and length.
+    pub fn abi_types(&self) -> Vec<MemoryType> {
+        match self {
+            ty if ty.is_fat() => vec![MemoryType::Usize, MemoryType::Usize],
+            ty => ty.memory_type().into_iter().collect(),
+        }
+    }
+
+    /// Gets the [`MemoryType`] of a register holding `self`.
+    ///
+    /// Aggregates don't fit in a register, and are passed around by address instead.
+    pub fn memory_type(&self) -> Option<MemoryType> {
+        match self {
+            Self::Void => None,
+            Self::Memory(ty) => Some(*ty),
+            Self::Str
+            | Self::Struct(_)
+            | Self::Array { .. }
+            | Self::Slice(_)
+            | Self::Reference(_)
+            | Self::Function(_) => Some(MemoryType::Usize),
+        }
+    }
 }
 
 impl From<MemoryType> for Type {
@@ -37,9 +106,32 @@ impl From<MemoryType> for Type {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct StructField {
+    pub ident: ast::Ident,
+    pub ty: TypeId,
+}
+
 #[derive(Clone, Debug)]
 pub struct Struct {
     pub ident: ast::Ident,
+    pub fields: Vec<StructField>,
+}
+
+impl Struct {
+    /// Gets the index and declaration of the field `ident`.
+    pub fn field(&self, ident: &ast::Ident) -> Option<(usize, &StructField)> {
+        (self.fields.iter().enumerate()).find(|(_, field)| field.ident == *ident)
+    }
+}
+
+/// The memory layout of a [`Struct`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructLayout {
+    pub size: u64,
+    pub align: u64,
+    /// The offset of each field in bytes, in declaration order.
+    pub offsets: Vec<u64>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -57,8 +149,8 @@ impl Types {
         } else {
             let id = self.type_ids.generate();
 
-            self.id_to_type.insert(id, ty.clone());
-            self.type_to_id.insert(ty.clone(), id);
+            self.id_to_type.insert(id, *ty);
+            self.type_to_id.insert(*ty, id);
 
             id
         }
@@ -67,4 +159,161 @@ impl Types {
     pub fn get_type(&self, id: TypeId) -> Type {
         self.id_to_type[&id]
     }
+
+    /// Gets the name of `ty` as written in source, for error messages.
+    pub fn name_of(&self, ty: Type) -> String {
+        match ty {
+            Type::Void => String::from("void"),
+            Type::Memory(ty) => String::from(match ty {
+                MemoryType::U8 => "u8",
+                MemoryType::U16 => "u16",
+                MemoryType::U32 => "u32",
+                MemoryType::U64 => "u64",
+                MemoryType::Usize => "usize",
+                MemoryType::I8 => "i8",
+                MemoryType::I16 => "i16",
+                MemoryType::I32 => "i32",
+                MemoryType::I64 => "i64",
+                MemoryType::Isize => "isize",
+                MemoryType::F32 => "f32",
+                MemoryType::F64 => "f64",
+                MemoryType::Bool => "bool",
+            }),
+            Type::Str => String::from("str"),
+            Type::Struct(id) => self.structs[&id].ident.to_string(),
+            Type::Array { element, length } => {
+                format!("[{}; {}]", self.name_of(self.get_type(element)), length)
+            }
+            Type::Slice(element) => format!("&[{}]", self.name_of(self.get_type(element))),
+            Type::Reference(id) => format!("&{}", self.name_of(self.get_type(id))),
+            Type::Function(_) => String::from("function"),
+        }
+    }
+
+    /// Gets the size of `ty` in bytes.
+    pub fn size_of(&self, ty: Type, ptr_size: u64) -> u64 {
+        match ty {
+            Type::Void => 0,
+            Type::Memory(ty) => ty.size(ptr_size),
+            Type::Str | Type::Slice(_) => ptr_size * 2,
+            Type::Struct(id) => self.layout_of(id, ptr_size).size,
+            Type::Array { element, length } => {
+                self.size_of(self.get_type(element), ptr_size) * length
+            }
+            Type::Reference(_) | Type::Function(_) => ptr_size,
+        }
+    }
+
+    /// Gets the alignment of `ty` in bytes.
+    pub fn align_of(&self, ty: Type, ptr_size: u64) -> u64 {
+        match ty {
+            Type::Void => 1,
+            Type::Memory(ty) => ty.size(ptr_size),
+            Type::Str | Type::Slice(_) => ptr_size,
+            Type::Struct(id) => self.layout_of(id, ptr_size).align,
+            Type::Array { element, .. } => self.align_of(self.get_type(element), ptr_size),
+            Type::Reference(_) | Type::Function(_) => ptr_size,
+        }
+    }
+
+    /// Computes the layout of the struct `id`.
+    ///
+    /// Fields are placed in declaration order, each at the next offset aligned to the field, and
+    /// the size is rounded up to the alignment of the struct.
+    pub fn layout_of(&self, id: StructId, ptr_size: u64) -> StructLayout {
+        let mut layout = StructLayout {
+            size: 0,
+            align: 1,
+            offsets: Vec::new(),
+        };
+
+        for field in self.structs[&id].fields.iter() {
+            let ty = self.get_type(field.ty);
+            let align = self.align_of(ty, ptr_size);
+
+            let offset = layout.size.next_multiple_of(align);
+            layout.offsets.push(offset);
+
+            layout.size = offset + self.size_of(ty, ptr_size);
+            layout.align = layout.align.max(align);
+        }
+
+        layout.size = layout.size.next_multiple_of(layout.align);
+        layout
+    }
+
+    /// Checks that the struct `id` doesn't contain itself, which would give it an infinite size.
+    pub fn check_recursion(&self, id: StructId) -> Result<(), Error> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![id];
+
+        while let Some(current) = stack.pop() {
+            for field in self.structs[&current].fields.iter() {
+                let mut ty = self.get_type(field.ty);
+
+                // arrays contain their elements directly
+                while let Type::Array { element, .. } = ty {
+                    ty = self.get_type(element);
+                }
+
+                if let Type::Struct(field_struct) = ty {
+                    if field_struct == id {
+                        let ident = &self.structs[&id].ident;
+
+                        return Err(Error::spanned(
+                            format!("Struct {} contains itself and has infinite size", ident),
+                            ident.span(),
+                        )
+                        .with_hint("contains itself through this field", field.ident.span()));
+                    }
+
+                    if visited.insert(field_struct) {
+                        stack.push(field_struct);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast, id::StructIds, ir::MemoryType, span::Span};
+
+    use super::{Struct, StructField, StructLayout, Type, Types};
+
+    #[test]
+    fn struct_layout() {
+        let mut types = Types::default();
+        let id = StructIds::default().generate();
+
+        let fields = [
+            MemoryType::U8,
+            MemoryType::I32,
+            MemoryType::U16,
+            MemoryType::Usize,
+            MemoryType::U8,
+        ];
+
+        let fields = (fields.iter().enumerate())
+            .map(|(i, &ty)| StructField {
+                ident: ast::Ident::new(format!("f{}", i), Span::null()),
+                ty: types.get_type_id(&Type::Memory(ty)),
+            })
+            .collect();
+
+        let ident = ast::Ident::new("S", Span::null());
+        types.structs.insert(id, Struct { ident, fields });
+
+        let layout = StructLayout {
+            size: 32,
+            align: 8,
+            offsets: vec![0, 4, 8, 16, 24],
+        };
+        assert_eq!(types.layout_of(id, 8), layout);
+        assert_eq!(types.layout_of(id, 4).offsets, [0, 4, 8, 12, 16]);
+        assert_eq!(types.size_of(Type::Struct(id), 4), 20);
+    }
 }
@@ -23,10 +23,31 @@ impl Value {
 #[derive(Clone, Copy, Debug)]
 pub enum ValueKind {
     Register(Register),
-    Stack(StackSlot),
+    /// Stored in a stack slot, at an offset in bytes.
+    Stack(StackSlot, u32),
+    /// Stored in memory at the address held in a register, plus an offset in bytes.
+    Address(Register, u32),
+    /// Held in two registers, the pointer and length of a [fat](Type::is_fat) value.
+    Pair(Register, Register),
     Function(FunctionId),
 }
 
+impl ValueKind {
+    /// Returns true if the value is stored in memory, and can be referenced and assigned to.
+    pub const fn is_place(&self) -> bool {
+        matches!(self, Self::Stack(..) | Self::Address(..))
+    }
+
+    /// Gets the kind of the value `offset` bytes into `self`, if `self` is stored in memory.
+    pub const fn offset(self, offset: u32) -> Option<Self> {
+        match self {
+            Self::Stack(slot, base) => Some(Self::Stack(slot, base + offset)),
+            Self::Address(register, base) => Some(Self::Address(register, base + offset)),
+            _ => None,
+        }
+    }
+}
+
 impl From<Register> for ValueKind {
     fn from(register: Register) -> Self {
         Self::Register(register)
@@ -35,7 +56,7 @@ impl From<Register> for ValueKind {
 
 impl From<StackSlot> for ValueKind {
     fn from(slot: StackSlot) -> Self {
-        Self::Stack(slot)
+        Self::Stack(slot, 0)
     }
 }
 
@@ -0,0 +1,507 @@
+use std::fmt::Write;
+
+use crate::{
+    error::{Error, Severity},
+    source::{LineColumn, Source, Sources},
+    span::Span,
+};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+const TAB_WIDTH: usize = 4;
+
+/// Collects the errors and warnings of a compilation.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Error>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Error) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Pushes the error of `result` if there is one, and returns the value otherwise.
+    pub fn report<T>(&mut self, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Error> {
+        self.diagnostics.iter()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Error> {
+        self.iter().filter(|diagnostic| !diagnostic.is_warning())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Error> {
+        self.iter().filter(|diagnostic| diagnostic.is_warning())
+    }
+}
+
+impl Extend<Error> for Diagnostics {
+    fn extend<T: IntoIterator<Item = Error>>(&mut self, iter: T) {
+        self.diagnostics.extend(iter);
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Error;
+    type IntoIter = std::vec::IntoIter<Error>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+/// A span to underline in a code frame.
+struct Label<'a> {
+    span: Span,
+    message: &'a str,
+    primary: bool,
+    color: &'static str,
+}
+
+/// A label resolved to its lines in a source.
+struct LineLabel<'a> {
+    /// The first display column, starting at 0.
+    start: usize,
+    /// The display column past the end of the underline.
+    end: usize,
+    message: &'a str,
+    primary: bool,
+    color: &'static str,
+}
+
+/// Renders [`Error`]s for the terminal, with code frames underlining the spans of the error and
+/// its hints.
+pub struct Renderer<'a> {
+    sources: &'a Sources,
+    color: bool,
+    location: bool,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(sources: &'a Sources) -> Self {
+        Self {
+            sources,
+            color: false,
+            location: false,
+        }
+    }
+
+    /// Use ANSI escape codes to color the output.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Include the location in the compiler where the error was created.
+    pub fn with_location(mut self, location: bool) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Renders every diagnostic in `diagnostics`, separated by empty lines.
+    pub fn render_all(&self, diagnostics: &Diagnostics) -> String {
+        let rendered: Vec<_> = diagnostics.iter().map(|error| self.render(error)).collect();
+        rendered.join("\n")
+    }
+
+    pub fn render(&self, error: &Error) -> String {
+        let mut out = String::new();
+
+        let (severity, primary) = match error.severity() {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+
+        let _ = writeln!(
+            out,
+            "{}{}",
+            self.paint(primary, severity),
+            self.paint(BOLD, &format!(": {}", error.message())),
+        );
+
+        let mut labels = Vec::new();
+
+        if let Some(span) = error.span() {
+            labels.push(Label {
+                span,
+                message: "",
+                primary: true,
+                color: primary,
+            });
+        }
+
+        for hint in error.hints() {
+            labels.push(Label {
+                span: hint.span(),
+                message: hint.message(),
+                primary: false,
+                color: BLUE,
+            });
+        }
+
+        // labels in sources we don't know are rendered as plain notes
+        let (known, unknown): (Vec<_>, Vec<_>) =
+            (labels.into_iter()).partition(|label| self.sources.get(label.span.source()).is_some());
+
+        let gutter = (known.iter())
+            .map(|label| self.range(label.span).1.line)
+            .max()
+            .map_or(0, |line| line.to_string().len());
+
+        let mut sources = Vec::new();
+        for label in known.iter() {
+            if !sources.contains(&label.span.source()) {
+                sources.push(label.span.source());
+            }
+        }
+
+        for id in sources {
+            let source = self.sources.get(id).unwrap();
+            let labels: Vec<_> = (known.iter())
+                .filter(|label| label.span.source() == id)
+                .collect();
+
+            self.render_frame(&mut out, source, &labels, gutter);
+        }
+
+        for label in unknown.iter().filter(|label| !label.message.is_empty()) {
+            let _ = writeln!(
+                out,
+                "{:gutter$} {} hint: {}",
+                "",
+                self.paint(BLUE, "="),
+                label.message,
+            );
+        }
+
+        if self.location {
+            let _ = writeln!(
+                out,
+                "{:gutter$} {} note: emitted at {}",
+                "",
+                self.paint(BLUE, "="),
+                error.location(),
+            );
+        }
+
+        out
+    }
+
+    fn render_frame(&self, out: &mut String, source: &Source, labels: &[&Label], gutter: usize) {
+        // the frame is headed by the primary label, or the first label if there is none
+        let first = (labels.iter())
+            .find(|label| label.primary)
+            .unwrap_or(&labels[0]);
+
+        let _ = writeln!(
+            out,
+            "{:gutter$}{} {}:{}",
+            "",
+            self.paint(BLUE, "-->"),
+            source.path().display(),
+            self.range(first.span).0,
+        );
+        let _ = writeln!(out, "{:gutter$} {}", "", self.paint(BLUE, "|"));
+
+        let mut lines: Vec<usize> = Vec::new();
+
+        for label in labels.iter() {
+            let (start, end) = self.range(label.span);
+            lines.extend(start.line..=end.line);
+        }
+
+        lines.sort_unstable();
+        lines.dedup();
+
+        let mut previous = None;
+
+        for line in lines {
+            if previous.is_some_and(|previous| previous + 1 < line) {
+                let _ = writeln!(out, "{}", self.paint(BLUE, "..."));
+            }
+
+            previous = Some(line);
+
+            let text = source.line(line).unwrap_or_default();
+            let line_labels: Vec<_> = (labels.iter())
+                .filter_map(|label| self.line_label(source, label, line))
+                .collect();
+
+            let _ = writeln!(
+                out,
+                "{} {}",
+                self.paint(BLUE, &format!("{:>gutter$} |", line)),
+                expand_tabs(text),
+            );
+
+            self.render_underlines(out, &line_labels, gutter);
+        }
+    }
+
+    /// Renders the underlines of the labels on one line, followed by their messages.
+    ///
+    /// The message of the rightmost label is written after the underlines, the others are
+    /// written below, starting at their underline.
+    fn render_underlines(&self, out: &mut String, labels: &[LineLabel], gutter: usize) {
+        if labels.is_empty() {
+            return;
+        }
+
+        let mut labels: Vec<_> = labels.iter().collect();
+        labels.sort_by_key(|label| (label.start, !label.primary));
+
+        let mut underline = String::new();
+        let mut column = 0;
+
+        for label in labels.iter() {
+            // overlapping labels are cut off at the end of the label before them
+            let start = label.start.max(column);
+            let end = label.end.max(start + 1);
+
+            let marker = if label.primary { "^" } else { "-" };
+            let color = label.color;
+
+            underline.push_str(&" ".repeat(start - column));
+            underline.push_str(&self.paint(color, &marker.repeat(end - start)));
+            column = end;
+        }
+
+        let last = labels.iter().max_by_key(|label| label.end).unwrap();
+
+        let _ = write!(
+            out,
+            "{:gutter$} {} {}",
+            "",
+            self.paint(BLUE, "|"),
+            underline
+        );
+
+        if !last.message.is_empty() {
+            let _ = write!(out, " {}", self.paint(last.color, last.message));
+        }
+
+        let _ = writeln!(out);
+
+        for label in labels.iter().rev() {
+            if std::ptr::eq(*label, *last) || label.message.is_empty() {
+                continue;
+            }
+
+            let _ = writeln!(
+                out,
+                "{:gutter$} {} {:start$}{}",
+                "",
+                self.paint(BLUE, "|"),
+                "",
+                self.paint(label.color, label.message),
+                start = label.start,
+            );
+        }
+    }
+
+    /// Gets the part of `label` on `line`, the message is only kept on the last line.
+    fn line_label<'l>(
+        &self,
+        source: &Source,
+        label: &'l Label,
+        line: usize,
+    ) -> Option<LineLabel<'l>> {
+        let (start, end) = self.range(label.span);
+
+        if line < start.line || line > end.line {
+            return None;
+        }
+
+        let text = source.line(line).unwrap_or_default();
+
+        let start_column = if line == start.line {
+            start.column - 1
+        } else {
+            // continued lines are underlined from their first non-whitespace character
+            text.chars().take_while(|c| c.is_whitespace()).count()
+        };
+
+        let end_column = if line == end.line {
+            end.column - 1
+        } else {
+            text.chars().count()
+        };
+
+        Some(LineLabel {
+            start: display_width(text, start_column),
+            end: display_width(text, end_column),
+            message: if line == end.line { label.message } else { "" },
+            primary: label.primary,
+            color: label.color,
+        })
+    }
+
+    fn range(&self, span: Span) -> (LineColumn, LineColumn) {
+        let range = self.sources.range(span).unwrap();
+        (range.start, range.end)
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            String::from(text)
+        }
+    }
+}
+
+/// The display width of the first `chars` characters of `text`.
+fn display_width(text: &str, chars: usize) -> usize {
+    let tab = |c| if c == '\t' { TAB_WIDTH } else { 1 };
+    text.chars().take(chars).map(tab).sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::Source;
+
+    use super::*;
+
+    #[test]
+    fn render_error() {
+        let mut sources = Sources::new();
+        let id = sources.insert(Source::new(
+            "main.fn",
+            "mod a { fn f() {} }\nfn g() {\n    a::f();\n}\n",
+        ));
+
+        let error = Error::spanned("Function f is private", Span::new(id, 36, 1))
+            .with_hint("declared here", Span::new(id, 11, 1));
+
+        let rendered = Renderer::new(&sources).render(&error);
+        let expected = "\
+error: Function f is private
+ --> main.fn:3:8
+  |
+1 | mod a { fn f() {} }
+  |            - declared here
+...
+3 |     a::f();
+  |        ^
+";
+
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_labels_on_one_line() {
+        let mut sources = Sources::new();
+        let id = sources.insert(Source::new("main.fn", "fn f() {} fn f() {}"));
+
+        let error = Error::spanned("Function f declared more than once", Span::new(id, 13, 1))
+            .with_hint("first declared here", Span::new(id, 3, 1))
+            .with_hint("second", Span::new(id, 10, 2));
+
+        let rendered = Renderer::new(&sources).render(&error);
+        let expected = "\
+error: Function f declared more than once
+ --> main.fn:1:14
+  |
+1 | fn f() {} fn f() {}
+  |    -      -- ^
+  |           second
+  |    first declared here
+";
+
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_multiline_span() {
+        let mut sources = Sources::new();
+        let id = sources.insert(Source::new("main.fn", "fn f() {\n\tloop {}\n}"));
+
+        let error = Error::spanned("Function must return", Span::new(id, 7, 12));
+        let rendered = Renderer::new(&sources).render(&error);
+        let expected = "\
+error: Function must return
+ --> main.fn:1:8
+  |
+1 | fn f() {
+  |        ^
+2 |     loop {}
+  |     ^^^^^^^
+3 | }
+  | ^
+";
+
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_without_source() {
+        let sources = Sources::new();
+        let error = Error::new("Function main not defined");
+
+        let rendered = Renderer::new(&sources).render(&error);
+        assert_eq!(rendered, "error: Function main not defined\n");
+
+        let rendered = Renderer::new(&sources).with_color(true).render(&error);
+        assert!(rendered.contains(RED));
+    }
+
+    #[test]
+    fn render_diagnostics() {
+        let mut sources = Sources::new();
+        let id = sources.insert(Source::new("main.fn", "let x = 1;"));
+
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.push(Error::warning("Unused variable x", Span::new(id, 4, 1)));
+        diagnostics.push(Error::new("Function main not defined"));
+
+        assert!(diagnostics.has_errors());
+        assert_eq!(diagnostics.warnings().count(), 1);
+
+        let rendered = Renderer::new(&sources).render_all(&diagnostics);
+        let expected = "\
+warning: Unused variable x
+ --> main.fn:1:5
+  |
+1 | let x = 1;
+  |     ^
+
+error: Function main not defined
+";
+
+        assert_eq!(rendered, expected);
+    }
+}
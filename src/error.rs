@@ -5,8 +5,16 @@ use crate::{
     span::Span,
 };
 
+/// How severe an [`Error`] is, only errors stop compilation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
 #[derive(Clone, Debug)]
 pub struct Error {
+    severity: Severity,
     message: String,
     span: Option<Span>,
     hints: Vec<ErrorHint>,
@@ -17,6 +25,7 @@ impl Error {
     #[track_caller]
     pub fn new(message: impl Into<String>) -> Self {
         Self {
+            severity: Severity::Error,
             message: message.into(),
             span: None,
             hints: Vec::new(),
@@ -27,6 +36,7 @@ impl Error {
     #[track_caller]
     pub fn spanned(message: impl Into<String>, span: Span) -> Self {
         Self {
+            severity: Severity::Error,
             message: message.into(),
             span: Some(span),
             hints: Vec::new(),
@@ -34,6 +44,14 @@ impl Error {
         }
     }
 
+    #[track_caller]
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::spanned(message, span)
+        }
+    }
+
     pub fn add_hint(&mut self, hint: ErrorHint) {
         self.hints.push(hint);
     }
@@ -43,6 +61,14 @@ impl Error {
         self
     }
 
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+
     pub fn message(&self) -> &String {
         &self.message
     }
@@ -54,13 +80,20 @@ impl Error {
     pub fn hints(&self) -> &[ErrorHint] {
         &self.hints
     }
+
+    /// The location in the compiler where the error was created.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Expected {
     Ident,
+    Label,
     String,
     Integer,
+    Float,
     Symbol(Symbol),
     Keyword(Keyword),
     Expression,
@@ -70,8 +103,10 @@ impl Display for Expected {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Ident => write!(f, "identifier"),
+            Self::Label => write!(f, "label"),
             Self::String => write!(f, "string literal"),
             Self::Integer => write!(f, "integer"),
+            Self::Float => write!(f, "float"),
             Self::Symbol(symbol) => write!(f, "'{}'", symbol),
             Self::Keyword(keyword) => write!(f, "'{}'", keyword),
             Self::Expression => write!(f, "expression"),
@@ -92,8 +127,10 @@ impl Error {
 
     #[track_caller]
     pub fn expected_any(expected: &[Expected], found: impl Display, span: Span) -> Self {
+        let expected: Vec<_> = expected.iter().map(ToString::to_string).collect();
+
         Self::spanned(
-            format!("Expected any of {:?} found {}", expected, found),
+            format!("Expected one of {} found {}", expected.join(", "), found),
             span,
         )
     }
@@ -1,12 +1,12 @@
 macro_rules! id {
     ($generator:ident[$ident:ident]: $ty:ty) => {
         #[repr(transparent)]
-        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
         pub struct $ident($ty);
 
-        impl Into<$ty> for $ident {
-            fn into(self) -> $ty {
-                self.0
+        impl From<$ident> for $ty {
+            fn from(id: $ident) -> Self {
+                id.0
             }
         }
 
@@ -31,3 +31,4 @@ id!(TypeIds[TypeId]: usize);
 id!(ModuleIds[ModuleId]: usize);
 id!(StructIds[StructId]: usize);
 id!(BlockIds[BlockId]: usize);
+id!(DataIds[DataId]: usize);
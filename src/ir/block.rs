@@ -4,7 +4,7 @@ use crate::id::{BlockId, BlockIds};
 
 use super::Instruction;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct Block {
     instructions: Vec<Instruction>,
 }
@@ -19,6 +19,10 @@ impl Block {
     pub fn push(&mut self, instruction: Instruction) {
         self.instructions.push(instruction);
     }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use crate::id::{DataId, DataIds};
+
+/// The read-only data of a program, like the bytes of string literals.
+///
+/// Backends place the data in a read-only section, and [`Instruction::DataAddr`] gets the address
+/// of it.
+///
+/// [`Instruction::DataAddr`]: super::Instruction::DataAddr
+#[derive(Clone, Debug, Default)]
+pub struct ReadOnlyData {
+    data_ids: DataIds,
+    data: HashMap<DataId, Vec<u8>>,
+    /// Identical data is only stored once.
+    ids: HashMap<Vec<u8>, DataId>,
+}
+
+impl ReadOnlyData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `bytes`, returning the id of identical data if it was already inserted.
+    pub fn insert(&mut self, bytes: impl Into<Vec<u8>>) -> DataId {
+        let bytes = bytes.into();
+
+        if let Some(&id) = self.ids.get(&bytes) {
+            return id;
+        }
+
+        let id = self.data_ids.generate();
+        self.data.insert(id, bytes.clone());
+        self.ids.insert(bytes, id);
+
+        id
+    }
+
+    pub fn get(&self, id: DataId) -> Option<&[u8]> {
+        self.data.get(&id).map(Vec::as_slice)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Iterates the data in the order it was inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (DataId, &[u8])> {
+        let mut data: Vec<_> = self.data.iter().collect();
+        data.sort_by_key(|(&id, _)| id);
+
+        data.into_iter().map(|(&id, bytes)| (id, bytes.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadOnlyData;
+
+    #[test]
+    fn deduplicate() {
+        let mut data = ReadOnlyData::new();
+
+        let a = data.insert("hello");
+        let b = data.insert("world");
+
+        assert_ne!(a, b);
+        assert_eq!(data.insert("hello"), a);
+        assert_eq!(data.get(b), Some(&b"world"[..]));
+        assert_eq!(data.iter().map(|(id, _)| id).collect::<Vec<_>>(), [a, b]);
+    }
+}
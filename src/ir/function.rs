@@ -2,12 +2,23 @@ use std::collections::HashMap;
 
 use crate::id::{BlockId, FunctionId, FunctionSignatureId};
 
-use super::Stack;
+use super::{Register, Stack};
 
 #[derive(Clone, Debug)]
 pub struct Function {
     pub label: Option<String>,
     pub signature: FunctionSignatureId,
+    /// Register holding the address the return value is written to, when the function returns a
+    /// struct or array.
+    ///
+    /// The address is passed before the other arguments, and is also returned by the function.
+    pub return_pointer: Option<Register>,
+    /// Registers holding the arguments when the function is entered, as many for each argument as
+    /// its type has [ABI types](crate::compiler::Type::abi_types).
+    pub arguments: Vec<Register>,
+    /// The number of registers used by the function.
+    pub registers: u32,
+    /// Blocks of the function, the first block is the entry point.
     pub blocks: Vec<BlockId>,
     pub stack: Stack,
 }
@@ -21,4 +32,18 @@ impl Functions {
     pub fn insert(&mut self, id: FunctionId, function: Function) {
         self.functions.insert(id, function);
     }
+
+    pub fn get(&self, id: FunctionId) -> Option<&Function> {
+        self.functions.get(&id)
+    }
+
+    pub fn get_by_label(&self, label: &str) -> Option<FunctionId> {
+        let mut functions = self.functions.iter();
+        let (&id, _) = functions.find(|(_, function)| function.label.as_deref() == Some(label))?;
+        Some(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (FunctionId, &Function)> {
+        self.functions.iter().map(|(&id, function)| (id, function))
+    }
 }
@@ -1,4 +1,7 @@
-use crate::id::FunctionId;
+use crate::{
+    id::{BlockId, DataId, FunctionId},
+    span::Span,
+};
 
 use super::{Block, Immediate, MemoryType, Register, RegisterAllocator, StackSlot};
 
@@ -23,21 +26,157 @@ macro_rules! instructions {
     };
 }
 
+/// The condition of an [`Instruction::ICmp`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    SignedLessThan,
+    SignedLessThanOrEqual,
+    SignedGreaterThan,
+    SignedGreaterThanOrEqual,
+    UnsignedLessThan,
+    UnsignedLessThanOrEqual,
+    UnsignedGreaterThan,
+    UnsignedGreaterThanOrEqual,
+}
+
+impl Comparison {
+    /// Gets the less than comparison for signed or unsigned integers.
+    pub const fn less_than(signed: bool) -> Self {
+        if signed {
+            Self::SignedLessThan
+        } else {
+            Self::UnsignedLessThan
+        }
+    }
+
+    /// Gets the less than or equal comparison for signed or unsigned integers.
+    pub const fn less_than_or_equal(signed: bool) -> Self {
+        if signed {
+            Self::SignedLessThanOrEqual
+        } else {
+            Self::UnsignedLessThanOrEqual
+        }
+    }
+
+    /// Gets the greater than comparison for signed or unsigned integers.
+    pub const fn greater_than(signed: bool) -> Self {
+        if signed {
+            Self::SignedGreaterThan
+        } else {
+            Self::UnsignedGreaterThan
+        }
+    }
+
+    /// Gets the greater than or equal comparison for signed or unsigned integers.
+    pub const fn greater_than_or_equal(signed: bool) -> Self {
+        if signed {
+            Self::SignedGreaterThanOrEqual
+        } else {
+            Self::UnsignedGreaterThanOrEqual
+        }
+    }
+
+    /// Evaluates the comparison on two integers sign or zero extended to 64 bits.
+    pub const fn evaluate(&self, lhs: u64, rhs: u64) -> bool {
+        match self {
+            Self::Equal => lhs == rhs,
+            Self::NotEqual => lhs != rhs,
+            Self::SignedLessThan => (lhs as i64) < (rhs as i64),
+            Self::SignedLessThanOrEqual => (lhs as i64) <= (rhs as i64),
+            Self::SignedGreaterThan => (lhs as i64) > (rhs as i64),
+            Self::SignedGreaterThanOrEqual => (lhs as i64) >= (rhs as i64),
+            Self::UnsignedLessThan => lhs < rhs,
+            Self::UnsignedLessThanOrEqual => lhs <= rhs,
+            Self::UnsignedGreaterThan => lhs > rhs,
+            Self::UnsignedGreaterThanOrEqual => lhs >= rhs,
+        }
+    }
+}
+
+/// The condition of an [`Instruction::FCmp`], every comparison with NaN is false except
+/// [`FloatComparison::NotEqual`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FloatComparison {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+}
+
+impl FloatComparison {
+    pub fn evaluate(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Equal => lhs == rhs,
+            Self::NotEqual => lhs != rhs,
+            Self::LessThan => lhs < rhs,
+            Self::LessThanOrEqual => lhs <= rhs,
+            Self::GreaterThan => lhs > rhs,
+            Self::GreaterThanOrEqual => lhs >= rhs,
+        }
+    }
+}
+
+/// Why an [`Instruction::Trap`] aborted execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TrapCode {
+    IndexOutOfBounds,
+    DivisionByZero,
+    /// Signed division of the minimum value by -1.
+    DivisionOverflow,
+}
+
+impl TrapCode {
+    pub const fn message(&self) -> &'static str {
+        match self {
+            Self::IndexOutOfBounds => "Index out of bounds",
+            Self::DivisionByZero => "Division by zero",
+            Self::DivisionOverflow => "Division overflow",
+        }
+    }
+}
+
 instructions! {
     0: Noop {},
     1: IConst { dst: Register, imm: Immediate, ty: MemoryType },
-    16: Call { dst: Register, function: FunctionId, arguments: Vec<Register> },
-    18: Return { src: Register },
+    2: FConst { dst: Register, imm: Immediate, ty: MemoryType },
+    16: Call { dsts: Vec<Register>, function: FunctionId, arguments: Vec<Register> },
+    17: Jump { block: BlockId },
+    18: Return { srcs: Vec<Register> },
+    19: Branch { condition: Register, then_block: BlockId, else_block: BlockId },
+    20: Unreachable {},
+    21: Trap { code: TrapCode, span: Span },
     32: Add { dst: Register, lhs: Register, rhs: Register },
     33: Sub { dst: Register, lhs: Register, rhs: Register },
     34: Mul { dst: Register, lhs: Register, rhs: Register },
     35: Div { dst: Register, lhs: Register, rhs: Register },
+    36: FAdd { dst: Register, lhs: Register, rhs: Register },
+    37: FSub { dst: Register, lhs: Register, rhs: Register },
+    38: FMul { dst: Register, lhs: Register, rhs: Register },
+    39: FDiv { dst: Register, lhs: Register, rhs: Register },
+    40: ICmp { dst: Register, comparison: Comparison, lhs: Register, rhs: Register },
+    41: FCmp { dst: Register, comparison: FloatComparison, lhs: Register, rhs: Register },
+    42: Rem { dst: Register, lhs: Register, rhs: Register },
+    43: FNeg { dst: Register, src: Register },
     48: FuncAddr { dst: Register, function: FunctionId, ty: MemoryType },
+    49: DataAddr { dst: Register, data: DataId, ty: MemoryType },
+    56: IConvert { dst: Register, src: Register, ty: MemoryType },
+    57: FConvert { dst: Register, src: Register, ty: MemoryType },
+    58: IntToFloat { dst: Register, src: Register, ty: MemoryType },
+    59: FloatToInt { dst: Register, src: Register, ty: MemoryType },
     64: StackLoad { dst: Register, slot: StackSlot, ty: MemoryType, offset: u32 },
     65: StackStore { src: Register, slot: StackSlot, ty: MemoryType, offset: u32 },
     66: StackAddr { dst: Register, slot: StackSlot, ty: MemoryType },
     72: Load { dst: Register, src: Register, ty: MemoryType, offset: u32 },
     73: Store { dst: Register, src: Register, ty: MemoryType, offset: u32 },
+    80: And { dst: Register, lhs: Register, rhs: Register },
+    81: Or { dst: Register, lhs: Register, rhs: Register },
+    82: Xor { dst: Register, lhs: Register, rhs: Register },
+    83: Shl { dst: Register, lhs: Register, rhs: Register },
+    84: Shr { dst: Register, lhs: Register, rhs: Register },
 }
 
 pub struct InstructionBuilder<'a> {
@@ -45,7 +184,41 @@ pub struct InstructionBuilder<'a> {
     pub(crate) block: &'a mut Block,
 }
 
+impl Instruction {
+    /// Returns true if `self` ends a block.
+    pub const fn is_terminator(&self) -> bool {
+        matches!(
+            self,
+            Self::Jump { .. }
+                | Self::Return { .. }
+                | Self::Branch { .. }
+                | Self::Unreachable {}
+                | Self::Trap { .. }
+        )
+    }
+
+    /// Gets the blocks `self` may continue in, if it's a terminator.
+    pub fn successors(&self) -> Vec<BlockId> {
+        match *self {
+            Self::Jump { block } => vec![block],
+            Self::Branch {
+                then_block,
+                else_block,
+                ..
+            } => vec![then_block, else_block],
+            _ => Vec::new(),
+        }
+    }
+}
+
 impl<'a> InstructionBuilder<'a> {
+    pub fn new(register_allocator: &'a mut RegisterAllocator, block: &'a mut Block) -> Self {
+        Self {
+            register_allocator,
+            block,
+        }
+    }
+
     fn push(&mut self, instruction: Instruction) {
         self.block.push(instruction);
     }
@@ -70,20 +243,62 @@ impl<'a> InstructionBuilder<'a> {
         dst
     }
 
-    pub fn call(&mut self, function: FunctionId, arguments: impl Into<Vec<Register>>) -> Register {
+    /// Creates a float constant, `imm` holds the bits of a float of `ty`.
+    pub fn fconst(&mut self, imm: impl Into<Immediate>, ty: MemoryType) -> Register {
         let dst = self.allocate_register();
 
-        self.push(Instruction::Call {
+        self.push(Instruction::FConst {
             dst,
+            imm: imm.into(),
+            ty,
+        });
+
+        dst
+    }
+
+    /// Calls `function`, returning the `results` registers holding the returned value.
+    pub fn call(
+        &mut self,
+        function: FunctionId,
+        arguments: impl Into<Vec<Register>>,
+        results: usize,
+    ) -> Vec<Register> {
+        let dsts: Vec<_> = (0..results).map(|_| self.allocate_register()).collect();
+
+        self.push(Instruction::Call {
+            dsts: dsts.clone(),
             function,
             arguments: arguments.into(),
         });
 
-        dst
+        dsts
+    }
+
+    pub fn jump(&mut self, block: BlockId) {
+        self.push(Instruction::Jump { block });
+    }
+
+    /// Returns the value held in `srcs`, see [`Type::abi_types`](crate::compiler::Type::abi_types).
+    pub fn ret(&mut self, srcs: impl Into<Vec<Register>>) {
+        self.push(Instruction::Return { srcs: srcs.into() });
+    }
+
+    /// Jumps to `then_block` if `condition` is non-zero, otherwise to `else_block`.
+    pub fn branch(&mut self, condition: Register, then_block: BlockId, else_block: BlockId) {
+        self.push(Instruction::Branch {
+            condition,
+            then_block,
+            else_block,
+        });
+    }
+
+    pub fn unreachable(&mut self) {
+        self.push(Instruction::Unreachable {});
     }
 
-    pub fn ret(&mut self, src: Register) {
-        self.push(Instruction::Return { src });
+    /// Aborts execution, `span` is the source that caused the trap.
+    pub fn trap(&mut self, code: TrapCode, span: Span) {
+        self.push(Instruction::Trap { code, span });
     }
 
     pub fn add(&mut self, lhs: Register, rhs: Register) -> Register {
@@ -118,12 +333,192 @@ impl<'a> InstructionBuilder<'a> {
         dst
     }
 
+    /// The remainder of `lhs / rhs`, with the sign of `lhs` for signed integers.
+    pub fn rem(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::Rem { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn and(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::And { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn or(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::Or { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn xor(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::Xor { dst, lhs, rhs });
+
+        dst
+    }
+
+    /// Shifts `lhs` left, the shift amount `rhs` is masked to the bit width of `lhs`.
+    pub fn shl(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::Shl { dst, lhs, rhs });
+
+        dst
+    }
+
+    /// Shifts `lhs` right, arithmetic for signed integers and logical for unsigned, the shift
+    /// amount `rhs` is masked to the bit width of `lhs`.
+    pub fn shr(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::Shr { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn fadd(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::FAdd { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn fsub(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::FSub { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn fmul(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::FMul { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn fdiv(&mut self, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::FDiv { dst, lhs, rhs });
+
+        dst
+    }
+
+    pub fn fneg(&mut self, src: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::FNeg { dst, src });
+
+        dst
+    }
+
+    /// Compares `lhs` and `rhs`, producing a [`MemoryType::Bool`].
+    pub fn icmp(&mut self, comparison: Comparison, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::ICmp {
+            dst,
+            comparison,
+            lhs,
+            rhs,
+        });
+
+        dst
+    }
+
+    pub fn eq(&mut self, lhs: Register, rhs: Register) -> Register {
+        self.icmp(Comparison::Equal, lhs, rhs)
+    }
+
+    pub fn ne(&mut self, lhs: Register, rhs: Register) -> Register {
+        self.icmp(Comparison::NotEqual, lhs, rhs)
+    }
+
+    pub fn lt(&mut self, lhs: Register, rhs: Register, signed: bool) -> Register {
+        self.icmp(Comparison::less_than(signed), lhs, rhs)
+    }
+
+    pub fn le(&mut self, lhs: Register, rhs: Register, signed: bool) -> Register {
+        self.icmp(Comparison::less_than_or_equal(signed), lhs, rhs)
+    }
+
+    pub fn gt(&mut self, lhs: Register, rhs: Register, signed: bool) -> Register {
+        self.icmp(Comparison::greater_than(signed), lhs, rhs)
+    }
+
+    pub fn ge(&mut self, lhs: Register, rhs: Register, signed: bool) -> Register {
+        self.icmp(Comparison::greater_than_or_equal(signed), lhs, rhs)
+    }
+
+    /// Compares the floats `lhs` and `rhs`, producing a [`MemoryType::Bool`].
+    pub fn fcmp(&mut self, comparison: FloatComparison, lhs: Register, rhs: Register) -> Register {
+        let dst = self.allocate_register();
+
+        self.push(Instruction::FCmp {
+            dst,
+            comparison,
+            lhs,
+            rhs,
+        });
+
+        dst
+    }
+
+    /// Converts the integer `src` to the integer type `ty`, extending by the signedness of `src`.
+    pub fn iconvert(&mut self, src: Register, ty: MemoryType) -> Register {
+        let dst = self.allocate_register();
+        self.push(Instruction::IConvert { dst, src, ty });
+        dst
+    }
+
+    /// Converts the float `src` to the float type `ty`.
+    pub fn fconvert(&mut self, src: Register, ty: MemoryType) -> Register {
+        let dst = self.allocate_register();
+        self.push(Instruction::FConvert { dst, src, ty });
+        dst
+    }
+
+    /// Converts the integer `src` to the float type `ty`, by the signedness of `src`.
+    pub fn int_to_float(&mut self, src: Register, ty: MemoryType) -> Register {
+        let dst = self.allocate_register();
+        self.push(Instruction::IntToFloat { dst, src, ty });
+        dst
+    }
+
+    /// Converts the float `src` to the integer type `ty`, rounding towards zero and saturating
+    /// at the bounds of `ty`. NaN is converted to zero.
+    pub fn float_to_int(&mut self, src: Register, ty: MemoryType) -> Register {
+        let dst = self.allocate_register();
+        self.push(Instruction::FloatToInt { dst, src, ty });
+        dst
+    }
+
     pub fn func_addr(&mut self, function: FunctionId, ty: MemoryType) -> Register {
         let dst = self.allocate_register();
         self.push(Instruction::FuncAddr { dst, function, ty });
         dst
     }
 
+    /// Gets the address of read-only `data`.
+    pub fn data_addr(&mut self, data: DataId, ty: MemoryType) -> Register {
+        let dst = self.allocate_register();
+        self.push(Instruction::DataAddr { dst, data, ty });
+        dst
+    }
+
     pub fn stack_load(&mut self, slot: StackSlot, ty: MemoryType, offset: u32) -> Register {
         let dst = self.allocate_register();
 
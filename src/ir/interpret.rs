@@ -0,0 +1,742 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::Error,
+    id::{DataId, FunctionId},
+};
+
+use super::{Function, Instruction, MemoryType, Program, Register};
+
+/// The address of the first byte of the interpreter stack.
+///
+/// Starting above zero makes null pointers trap instead of reading the stack.
+const STACK_BASE: u64 = 0x1000;
+
+/// The address of the first byte of read-only data, far above the stack.
+const DATA_BASE: u64 = 1 << 48;
+
+/// Function addresses are tagged with this bit, they cannot be dereferenced.
+const FUNCTION_TAG: u64 = 1 << 63;
+
+/// Bytes reserved on the stack for every call, standing in for the return address.
+const FRAME_HEADER: usize = 16;
+
+/// Wraps `value` to the width of `ty`, sign extending signed types.
+fn normalize(value: u64, ty: MemoryType, ptr_size: u64) -> u64 {
+    let bits = ty.size(ptr_size) * 8;
+
+    if bits >= 64 {
+        return value;
+    }
+
+    let value = value & ((1 << bits) - 1);
+
+    if ty.is_signed() && value >> (bits - 1) == 1 {
+        value | (u64::MAX << bits)
+    } else {
+        value
+    }
+}
+
+/// Reads the float of `ty` held in `value`, `f32` is held in the low 32 bits.
+fn to_float(value: u64, ty: MemoryType) -> f64 {
+    match ty {
+        MemoryType::F32 => f32::from_bits(value as u32) as f64,
+        _ => f64::from_bits(value),
+    }
+}
+
+/// Rounds `value` to a float of `ty`, and gets the bits held in a register.
+fn from_float(value: f64, ty: MemoryType) -> u64 {
+    match ty {
+        MemoryType::F32 => (value as f32).to_bits() as u64,
+        _ => value.to_bits(),
+    }
+}
+
+/// Converts the integer `value` of `from` to a float of `to`, rounding once.
+fn int_to_float(value: u64, from: MemoryType, to: MemoryType) -> u64 {
+    match (from.is_signed(), to) {
+        (true, MemoryType::F32) => ((value as i64) as f32).to_bits() as u64,
+        (false, MemoryType::F32) => (value as f32).to_bits() as u64,
+        (true, _) => ((value as i64) as f64).to_bits(),
+        (false, _) => (value as f64).to_bits(),
+    }
+}
+
+/// Converts `value` to an integer of `ty` like rust's `as`, truncating and saturating.
+fn float_to_int(value: f64, ty: MemoryType, ptr_size: u64) -> u64 {
+    let bits = ty.size(ptr_size) * 8;
+
+    if ty.is_signed() {
+        let max = (u64::MAX >> (65 - bits)) as i64;
+        (value as i64).clamp(-max - 1, max) as u64
+    } else {
+        (value as u64).min(u64::MAX >> (64 - bits))
+    }
+}
+
+struct Frame<'a> {
+    function: &'a Function,
+    /// The instructions of the current block.
+    instructions: &'a [Instruction],
+    /// The index of the next instruction in [`Self::instructions`].
+    index: usize,
+    /// Where the caller wants the returned value.
+    return_registers: Option<Vec<Register>>,
+    /// The length of the stack before the frame was entered.
+    stack_start: usize,
+    registers: Vec<u64>,
+    /// The type each register was last defined with, [`None`] until it's defined.
+    types: Vec<Option<MemoryType>>,
+    slots: Vec<u64>,
+}
+
+impl<'a> Frame<'a> {
+    fn get(&self, register: Register) -> u64 {
+        self.registers[register.to_u32() as usize]
+    }
+
+    fn ty(&self, register: Register) -> Result<MemoryType, Error> {
+        let ty = self.types[register.to_u32() as usize];
+        ty.ok_or_else(|| Error::new(format!("{:?} used before it was defined", register)))
+    }
+
+    fn set(&mut self, register: Register, value: u64, ty: MemoryType) {
+        self.registers[register.to_u32() as usize] = value;
+        self.types[register.to_u32() as usize] = Some(ty);
+    }
+}
+
+/// Executes an [`ir::Program`](Program) directly, without compiling it.
+///
+/// Registers are 64 bits wide and hold values wrapped to the width of their type, the stack is a
+/// byte-addressed buffer owned by the interpreter.
+pub struct Interpreter<'a> {
+    program: &'a Program,
+    ptr_size: u64,
+    stack: Vec<u8>,
+    stack_size: usize,
+    frames: Vec<Frame<'a>>,
+    /// Every [`ReadOnlyData`](super::ReadOnlyData) of the program, placed at [`DATA_BASE`].
+    data: Vec<u8>,
+    data_addresses: HashMap<DataId, u64>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        let mut data = Vec::new();
+        let mut data_addresses = HashMap::new();
+
+        for (id, bytes) in program.data.iter() {
+            data_addresses.insert(id, DATA_BASE + data.len() as u64);
+            data.extend_from_slice(bytes);
+        }
+
+        Self {
+            program,
+            ptr_size: 8,
+            stack: Vec::new(),
+            stack_size: 1 << 20,
+            frames: Vec::new(),
+            data,
+            data_addresses,
+        }
+    }
+
+    /// Sets the maximum size of the stack in bytes.
+    pub fn with_stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = stack_size;
+        self
+    }
+
+    pub const fn ptr_size(&self) -> u64 {
+        self.ptr_size
+    }
+
+    /// Calls `id` with `arguments`, returning the raw value returned.
+    ///
+    /// Functions returning `void` return `0`.
+    pub fn call(&mut self, id: FunctionId, arguments: &[u64]) -> Result<u64, Error> {
+        let function = (self.program.functions.get(id))
+            .ok_or_else(|| Error::new(format!("Function {:?} not defined", id)))?;
+
+        let signature = self.program.signatures.get_signature(function.signature);
+        let types = &self.program.types;
+        let abi_arguments = (signature.arguments.iter())
+            .map(|&ty| types.get_type(ty).abi_types().len())
+            .sum::<usize>();
+
+        if abi_arguments != arguments.len() {
+            return Err(Error::new("Arguments don't match function signature"));
+        }
+
+        if types.get_type(signature.return_type).is_aggregate() {
+            return Err(Error::new("Cannot call a function returning an aggregate"));
+        }
+
+        let result = self
+            .enter(function, arguments, None)
+            .and_then(|_| self.execute());
+
+        self.frames.clear();
+        self.stack.clear();
+
+        result
+    }
+
+    fn enter(
+        &mut self,
+        function: &'a Function,
+        arguments: &[u64],
+        return_registers: Option<Vec<Register>>,
+    ) -> Result<(), Error> {
+        let stack_start = self.stack.len();
+        let mut slots = Vec::new();
+
+        self.stack.resize(stack_start + FRAME_HEADER, 0);
+
+        for (_, allocation) in function.stack.iter() {
+            let ty = self.program.types.get_type(allocation.ty);
+            let size = self.program.types.size_of(ty, self.ptr_size) as usize;
+            let align = self.program.types.align_of(ty, self.ptr_size) as usize;
+
+            let offset = self.stack.len().next_multiple_of(align);
+            self.stack.resize(offset + size, 0);
+            slots.push(STACK_BASE + offset as u64);
+        }
+
+        if self.stack.len() > self.stack_size {
+            return Err(Error::new("Stack overflow"));
+        }
+
+        let block = self.program.blocks.get(function.blocks[0]).unwrap();
+
+        let mut frame = Frame {
+            function,
+            instructions: block.instructions(),
+            index: 0,
+            return_registers,
+            stack_start,
+            registers: vec![0; function.registers as usize],
+            types: vec![None; function.registers as usize],
+            slots,
+        };
+
+        let signature = self.program.signatures.get_signature(function.signature);
+        let mut arguments = arguments.iter().copied();
+
+        if let Some(register) = function.return_pointer {
+            let value = arguments.next().unwrap_or_default();
+            frame.set(register, value, MemoryType::Usize);
+        }
+
+        let types = (signature.arguments.iter())
+            .flat_map(|&ty| self.program.types.get_type(ty).abi_types());
+
+        for ((&register, ty), argument) in function.arguments.iter().zip(types).zip(arguments) {
+            let value = normalize(argument, ty, self.ptr_size);
+            frame.set(register, value, ty);
+        }
+
+        self.frames.push(frame);
+
+        Ok(())
+    }
+
+    fn memory(&mut self, address: u64, size: u64) -> Result<&mut [u8], Error> {
+        let start = address.wrapping_sub(STACK_BASE) as usize;
+        let end = start.wrapping_add(size as usize);
+
+        if address < STACK_BASE || end > self.stack.len() || end < start {
+            return Err(Error::new(format!(
+                "Invalid memory access at {:#x}",
+                address
+            )));
+        }
+
+        Ok(&mut self.stack[start..end])
+    }
+
+    /// Gets `size` bytes at `address` for reading, which can also be in read-only data.
+    fn read(&mut self, address: u64, size: u64) -> Result<&[u8], Error> {
+        if !(DATA_BASE..FUNCTION_TAG).contains(&address) {
+            return self.memory(address, size).map(|memory| &*memory);
+        }
+
+        let start = (address - DATA_BASE) as usize;
+        let end = start.wrapping_add(size as usize);
+
+        if end > self.data.len() || end < start {
+            return Err(Error::new(format!(
+                "Invalid memory access at {:#x}",
+                address
+            )));
+        }
+
+        Ok(&self.data[start..end])
+    }
+
+    fn load(&mut self, address: u64, ty: MemoryType) -> Result<u64, Error> {
+        let ptr_size = self.ptr_size;
+        let memory = self.read(address, ty.size(ptr_size))?;
+
+        let mut bytes = [0; 8];
+        bytes[..memory.len()].copy_from_slice(memory);
+
+        Ok(normalize(u64::from_le_bytes(bytes), ty, ptr_size))
+    }
+
+    fn store(&mut self, address: u64, value: u64, ty: MemoryType) -> Result<(), Error> {
+        if (DATA_BASE..FUNCTION_TAG).contains(&address) {
+            return Err(Error::new(format!(
+                "Write to read-only memory at {:#x}",
+                address
+            )));
+        }
+
+        let memory = self.memory(address, ty.size(self.ptr_size))?;
+
+        let len = memory.len();
+        memory.copy_from_slice(&value.to_le_bytes()[..len]);
+
+        Ok(())
+    }
+
+    fn frame(&mut self) -> &mut Frame<'a> {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn binary(
+        &mut self,
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+        f: impl FnOnce(u64, u64, MemoryType) -> Result<u64, Error>,
+    ) -> Result<(), Error> {
+        let ptr_size = self.ptr_size;
+        let frame = self.frame();
+
+        let ty = frame.ty(lhs)?;
+        let value = f(frame.get(lhs), frame.get(rhs), ty)?;
+
+        frame.set(dst, normalize(value, ty, ptr_size), ty);
+
+        Ok(())
+    }
+
+    fn float_binary(
+        &mut self,
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+        f: impl FnOnce(f64, f64) -> f64,
+    ) -> Result<(), Error> {
+        let frame = self.frame();
+
+        let ty = frame.ty(lhs)?;
+        let value = f(to_float(frame.get(lhs), ty), to_float(frame.get(rhs), ty));
+
+        frame.set(dst, from_float(value, ty), ty);
+
+        Ok(())
+    }
+
+    fn execute(&mut self) -> Result<u64, Error> {
+        let program = self.program;
+        let ptr_size = self.ptr_size;
+
+        loop {
+            let frame = self.frame();
+
+            let instruction = match frame.instructions.get(frame.index) {
+                Some(instruction) => instruction,
+                None => return Err(Error::new("Block has no terminator")),
+            };
+
+            frame.index += 1;
+
+            match *instruction {
+                Instruction::Noop {} => {}
+                Instruction::IConst { dst, imm, ty } => {
+                    frame.set(dst, normalize(u64::from(imm), ty, ptr_size), ty);
+                }
+                Instruction::FConst { dst, imm, ty } => {
+                    frame.set(dst, normalize(u64::from(imm), ty, ptr_size), ty);
+                }
+                Instruction::Call {
+                    ref dsts,
+                    function,
+                    ref arguments,
+                } => {
+                    let values: Vec<_> = arguments.iter().map(|&arg| frame.get(arg)).collect();
+                    let callee = self.program.functions.get(function).unwrap();
+
+                    self.enter(callee, &values, Some(dsts.clone()))?;
+                }
+                Instruction::Jump { block } => {
+                    let block = program.blocks.get(block).unwrap();
+
+                    frame.instructions = block.instructions();
+                    frame.index = 0;
+                }
+                Instruction::Return { ref srcs } => {
+                    let frame = self.frames.pop().unwrap();
+
+                    let signature = self
+                        .program
+                        .signatures
+                        .get_signature(frame.function.signature);
+                    let return_type = self.program.types.get_type(signature.return_type);
+
+                    // structs and arrays are written through the return pointer, not returned
+                    let values: Vec<_> = (srcs.iter())
+                        .zip(return_type.abi_types())
+                        .map(|(&src, ty)| (normalize(frame.get(src), ty, ptr_size), ty))
+                        .collect();
+
+                    self.stack.truncate(frame.stack_start);
+
+                    match frame.return_registers {
+                        Some(dsts) => {
+                            for (dst, (value, ty)) in dsts.into_iter().zip(values) {
+                                self.frame().set(dst, value, ty);
+                            }
+                        }
+                        None => return Ok(values.first().map_or(0, |&(value, _)| value)),
+                    }
+                }
+                Instruction::Branch {
+                    condition,
+                    then_block,
+                    else_block,
+                } => {
+                    let block = if frame.get(condition) != 0 {
+                        then_block
+                    } else {
+                        else_block
+                    };
+
+                    let block = program.blocks.get(block).unwrap();
+
+                    frame.instructions = block.instructions();
+                    frame.index = 0;
+                }
+                Instruction::Unreachable {} => {
+                    return Err(Error::new("Reached unreachable code"));
+                }
+                Instruction::Trap { code, span } => {
+                    return Err(Error::spanned(code.message(), span));
+                }
+                Instruction::Add { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, _| Ok(a.wrapping_add(b)))?;
+                }
+                Instruction::Sub { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, _| Ok(a.wrapping_sub(b)))?;
+                }
+                Instruction::Mul { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, _| Ok(a.wrapping_mul(b)))?;
+                }
+                Instruction::Div { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, ty| {
+                        if b == 0 {
+                            return Err(Error::new("Division by zero"));
+                        }
+
+                        if ty.is_signed() {
+                            let min = normalize(1 << (ty.size(ptr_size) * 8 - 1), ty, ptr_size);
+
+                            if a == min && b == u64::MAX {
+                                return Err(Error::new("Division overflow"));
+                            }
+
+                            Ok((a as i64 / b as i64) as u64)
+                        } else {
+                            Ok(a / b)
+                        }
+                    })?;
+                }
+                Instruction::Rem { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, ty| {
+                        if b == 0 {
+                            return Err(Error::new("Remainder by zero"));
+                        }
+
+                        if ty.is_signed() {
+                            Ok((a as i64).wrapping_rem(b as i64) as u64)
+                        } else {
+                            Ok(a % b)
+                        }
+                    })?;
+                }
+                Instruction::And { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, _| Ok(a & b))?;
+                }
+                Instruction::Or { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, _| Ok(a | b))?;
+                }
+                Instruction::Xor { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, _| Ok(a ^ b))?;
+                }
+                Instruction::Shl { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, ty| {
+                        let bits = ty.size(ptr_size) * 8;
+                        Ok(a << (b % bits))
+                    })?;
+                }
+                Instruction::Shr { dst, lhs, rhs } => {
+                    self.binary(dst, lhs, rhs, |a, b, ty| {
+                        let bits = ty.size(ptr_size) * 8;
+
+                        // signed values are sign extended, and unsigned zero extended
+                        if ty.is_signed() {
+                            Ok(((a as i64) >> (b % bits)) as u64)
+                        } else {
+                            Ok(a >> (b % bits))
+                        }
+                    })?;
+                }
+                Instruction::FAdd { dst, lhs, rhs } => {
+                    self.float_binary(dst, lhs, rhs, |a, b| a + b)?
+                }
+                Instruction::FSub { dst, lhs, rhs } => {
+                    self.float_binary(dst, lhs, rhs, |a, b| a - b)?
+                }
+                Instruction::FMul { dst, lhs, rhs } => {
+                    self.float_binary(dst, lhs, rhs, |a, b| a * b)?
+                }
+                Instruction::FDiv { dst, lhs, rhs } => {
+                    self.float_binary(dst, lhs, rhs, |a, b| a / b)?
+                }
+                Instruction::FNeg { dst, src } => {
+                    let ty = frame.ty(src)?;
+                    let value = -to_float(frame.get(src), ty);
+
+                    frame.set(dst, from_float(value, ty), ty);
+                }
+                Instruction::ICmp {
+                    dst,
+                    comparison,
+                    lhs,
+                    rhs,
+                } => {
+                    let value = comparison.evaluate(frame.get(lhs), frame.get(rhs));
+                    frame.set(dst, value as u64, MemoryType::Bool);
+                }
+                Instruction::FCmp {
+                    dst,
+                    comparison,
+                    lhs,
+                    rhs,
+                } => {
+                    let ty = frame.ty(lhs)?;
+                    let lhs = to_float(frame.get(lhs), ty);
+                    let rhs = to_float(frame.get(rhs), ty);
+
+                    let value = comparison.evaluate(lhs, rhs);
+                    frame.set(dst, value as u64, MemoryType::Bool);
+                }
+                Instruction::IConvert { dst, src, ty } => {
+                    frame.set(dst, normalize(frame.get(src), ty, ptr_size), ty);
+                }
+                Instruction::FConvert { dst, src, ty } => {
+                    let value = to_float(frame.get(src), frame.ty(src)?);
+                    frame.set(dst, from_float(value, ty), ty);
+                }
+                Instruction::IntToFloat { dst, src, ty } => {
+                    let value = int_to_float(frame.get(src), frame.ty(src)?, ty);
+                    frame.set(dst, value, ty);
+                }
+                Instruction::FloatToInt { dst, src, ty } => {
+                    let value = to_float(frame.get(src), frame.ty(src)?);
+                    frame.set(dst, float_to_int(value, ty, ptr_size), ty);
+                }
+                Instruction::FuncAddr { dst, function, ty } => {
+                    let address = FUNCTION_TAG | usize::from(function) as u64;
+                    frame.set(dst, address, ty);
+                }
+                Instruction::DataAddr { dst, data, ty } => {
+                    let address = self.data_addresses[&data];
+                    self.frame().set(dst, address, ty);
+                }
+                Instruction::StackLoad {
+                    dst,
+                    slot,
+                    ty,
+                    offset,
+                } => {
+                    let address = frame.slots[slot.to_usize()] + offset as u64;
+                    let value = self.load(address, ty)?;
+                    self.frame().set(dst, value, ty);
+                }
+                Instruction::StackStore {
+                    src,
+                    slot,
+                    ty,
+                    offset,
+                } => {
+                    let address = frame.slots[slot.to_usize()] + offset as u64;
+                    let value = frame.get(src);
+                    self.store(address, value, ty)?;
+                }
+                Instruction::StackAddr { dst, slot, ty } => {
+                    let address = frame.slots[slot.to_usize()];
+                    frame.set(dst, address, ty);
+                }
+                Instruction::Load {
+                    dst,
+                    src,
+                    ty,
+                    offset,
+                } => {
+                    let address = frame.get(src).wrapping_add(offset as u64);
+                    let value = self.load(address, ty)?;
+                    self.frame().set(dst, value, ty);
+                }
+                Instruction::Store {
+                    dst,
+                    src,
+                    ty,
+                    offset,
+                } => {
+                    let address = frame.get(dst).wrapping_add(offset as u64);
+                    let value = frame.get(src);
+                    self.store(address, value, ty)?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "parse"))]
+mod tests {
+    use crate::{
+        compiler::{FunctionSignature, Type},
+        id::FunctionIds,
+        ir::{
+            Function, InstructionBuilder, MemoryType, Program, RegisterAllocator, Stack,
+            StackAllocation,
+        },
+        test_support::{compile, run},
+    };
+
+    use super::Interpreter;
+
+    #[test]
+    fn stack_overflow() {
+        let program = compile("fn main() -> i32 { return main(); }");
+        let id = program.functions.get_by_label("main").unwrap();
+
+        let mut interpreter = Interpreter::new(&program).with_stack_size(64);
+        assert!(interpreter.call(id, &[]).is_err());
+    }
+
+    /// Builds `fn sum(n: i32) -> i32` summing `0..n` with a loop.
+    fn sum_program() -> Program {
+        let mut program = Program::default();
+        let i32_type = program.types.get_type_id(&Type::Memory(MemoryType::I32));
+        let signature = program.signatures.get_id(&FunctionSignature {
+            arguments: vec![i32_type],
+            return_type: i32_type,
+        });
+
+        let mut stack = Stack::new();
+        let sum = stack.allocate(StackAllocation { ty: i32_type });
+        let i = stack.allocate(StackAllocation { ty: i32_type });
+
+        let mut registers = RegisterAllocator::new();
+        let n = registers.allocate();
+
+        let blocks = &mut program.blocks;
+        let (entry, header, body, exit) = (
+            blocks.create(),
+            blocks.create(),
+            blocks.create(),
+            blocks.create(),
+        );
+
+        let mut ins = InstructionBuilder::new(&mut registers, blocks.get_mut(entry).unwrap());
+        let zero = ins.iconst(0, MemoryType::I32);
+        ins.stack_store(zero, sum, MemoryType::I32, 0);
+        ins.stack_store(zero, i, MemoryType::I32, 0);
+        ins.jump(header);
+
+        let mut ins = InstructionBuilder::new(&mut registers, blocks.get_mut(header).unwrap());
+        let value = ins.stack_load(i, MemoryType::I32, 0);
+        let condition = ins.lt(value, n, true);
+        ins.branch(condition, body, exit);
+
+        let mut ins = InstructionBuilder::new(&mut registers, blocks.get_mut(body).unwrap());
+        let value = ins.stack_load(i, MemoryType::I32, 0);
+        let total = ins.stack_load(sum, MemoryType::I32, 0);
+        let total = ins.add(total, value);
+        ins.stack_store(total, sum, MemoryType::I32, 0);
+        let one = ins.iconst(1, MemoryType::I32);
+        let value = ins.add(value, one);
+        ins.stack_store(value, i, MemoryType::I32, 0);
+        ins.jump(header);
+
+        let mut ins = InstructionBuilder::new(&mut registers, blocks.get_mut(exit).unwrap());
+        let total = ins.stack_load(sum, MemoryType::I32, 0);
+        ins.ret([total]);
+
+        let function = Function {
+            label: Some(String::from("sum")),
+            signature,
+            return_pointer: None,
+            arguments: vec![n],
+            registers: registers.count(),
+            blocks: vec![entry, header, body, exit],
+            stack,
+        };
+
+        let id = FunctionIds::default().generate();
+        program.functions.insert(id, function);
+
+        program
+    }
+
+    #[test]
+    fn loops() {
+        let program = sum_program();
+
+        assert_eq!(run(&program, "sum", &[0]), 0);
+        assert_eq!(run(&program, "sum", &[5]), 10);
+        assert_eq!(run(&program, "sum", &[-3i64 as u64]), 0);
+    }
+
+    #[test]
+    fn undefined_register() {
+        let mut program = Program::default();
+        let i32_type = program.types.get_type_id(&Type::Memory(MemoryType::I32));
+        let signature = program.signatures.get_id(&FunctionSignature {
+            arguments: Vec::new(),
+            return_type: i32_type,
+        });
+
+        let mut registers = RegisterAllocator::new();
+        let undefined = registers.allocate();
+        let entry = program.blocks.create();
+
+        let block = program.blocks.get_mut(entry).unwrap();
+        let mut ins = InstructionBuilder::new(&mut registers, block);
+        let value = ins.add(undefined, undefined);
+        ins.ret([value]);
+
+        let function = Function {
+            label: Some(String::from("f")),
+            signature,
+            return_pointer: None,
+            arguments: Vec::new(),
+            registers: registers.count(),
+            blocks: vec![entry],
+            stack: Stack::new(),
+        };
+
+        let id = FunctionIds::default().generate();
+        program.functions.insert(id, function);
+
+        let error = Interpreter::new(&program).call(id, &[]).unwrap_err();
+        assert!(error.message().ends_with("used before it was defined"));
+    }
+}
@@ -12,25 +12,46 @@ pub enum MemoryType {
     I32,
     I64,
     Isize,
+    F32,
+    F64,
+    /// A single byte holding either 0 or 1.
     Bool,
 }
 
 impl MemoryType {
     pub const fn is_integer(&self) -> bool {
-        match self {
-            Self::Bool => false,
-            _ => true,
-        }
+        !matches!(self, Self::Bool | Self::F32 | Self::F64)
+    }
+
+    pub const fn is_float(&self) -> bool {
+        matches!(self, Self::F32 | Self::F64)
+    }
+
+    pub const fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            Self::I8 | Self::I16 | Self::I32 | Self::I64 | Self::Isize
+        )
     }
 
     pub const fn size(&self, ptr_size: u64) -> u64 {
         match self {
-            Self::U8 | Self::I8 => 1,
+            Self::U8 | Self::I8 | Self::Bool => 1,
             Self::U16 | Self::I16 => 2,
-            Self::U32 | Self::I32 => 4,
-            Self::U64 | Self::I64 => 8,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
             Self::Usize | Self::Isize => ptr_size,
-            Self::Bool => 4,
+        }
+    }
+
+    /// The largest value of an integer type.
+    pub const fn max_value(&self, ptr_size: u64) -> u64 {
+        let bits = self.size(ptr_size) * 8;
+
+        if self.is_signed() {
+            u64::MAX >> (65 - bits)
+        } else {
+            u64::MAX >> (64 - bits)
         }
     }
 }
@@ -62,9 +83,34 @@ impl From<i32> for Immediate {
     }
 }
 
-impl Into<i32> for Immediate {
-    fn into(self) -> i32 {
-        i32::from_be_bytes([self.0[4], self.0[5], self.0[6], self.0[7]])
+impl From<Immediate> for i32 {
+    fn from(imm: Immediate) -> Self {
+        i32::from_be_bytes([imm.0[4], imm.0[5], imm.0[6], imm.0[7]])
+    }
+}
+
+/// Floats are stored as their bit pattern, `f32` in the low 32 bits.
+impl From<f32> for Immediate {
+    fn from(value: f32) -> Self {
+        Self::from(value.to_bits() as u64)
+    }
+}
+
+impl From<Immediate> for f32 {
+    fn from(imm: Immediate) -> Self {
+        f32::from_bits(u64::from(imm) as u32)
+    }
+}
+
+impl From<f64> for Immediate {
+    fn from(value: f64) -> Self {
+        Self::from(value.to_bits())
+    }
+}
+
+impl From<Immediate> for f64 {
+    fn from(imm: Immediate) -> Self {
+        f64::from_bits(u64::from(imm))
     }
 }
 
@@ -74,8 +120,8 @@ impl From<u64> for Immediate {
     }
 }
 
-impl Into<u64> for Immediate {
-    fn into(self) -> u64 {
-        u64::from_be_bytes(self.0)
+impl From<Immediate> for u64 {
+    fn from(imm: Immediate) -> Self {
+        u64::from_be_bytes(imm.0)
     }
 }
@@ -1,14 +1,18 @@
 mod block;
+mod data;
 mod function;
 mod instruction;
+mod interpret;
 mod memory;
 mod program;
 mod register;
 mod stack;
 
 pub use block::*;
+pub use data::*;
 pub use function::*;
 pub use instruction::*;
+pub use interpret::*;
 pub use memory::*;
 pub use program::*;
 pub use register::*;
@@ -1,6 +1,6 @@
 use crate::compiler::{FunctionSignatures, Types};
 
-use super::{Blocks, Functions};
+use super::{Blocks, Functions, ReadOnlyData};
 
 #[derive(Clone, Debug, Default)]
 pub struct Program {
@@ -8,4 +8,5 @@ pub struct Program {
     pub signatures: FunctionSignatures,
     pub blocks: Blocks,
     pub functions: Functions,
+    pub data: ReadOnlyData,
 }
@@ -33,4 +33,8 @@ impl Stack {
     pub fn get(&self, slot: StackSlot) -> Option<&StackAllocation> {
         self.slots.get(slot.0)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (StackSlot, &StackAllocation)> {
+        (self.slots.iter().enumerate()).map(|(i, allocation)| (StackSlot(i), allocation))
+    }
 }
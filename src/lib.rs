@@ -1,5 +1,6 @@
 pub mod ast;
 pub mod compiler;
+pub mod diagnostic;
 pub mod error;
 pub mod id;
 pub mod ir;
@@ -7,3 +8,5 @@ pub mod ir;
 pub mod parse;
 pub mod source;
 pub mod span;
+#[cfg(all(any(test, feature = "test-support"), feature = "parse"))]
+pub mod test_support;
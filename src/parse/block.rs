@@ -1,5 +1,5 @@
 use crate::{
-    ast::{Block, OpenBrace, Symbol, Token},
+    ast::{Block, Keyword, OpenBrace, Statement, Symbol, Token},
     error::Error,
 };
 
@@ -12,11 +12,22 @@ impl Parse for Block {
         let mut statements = Vec::new();
 
         loop {
-            if let Token::Symbol(Symbol::CloseBrace) = parser.peek_token()? {
-                break;
-            } else {
-                statements.push(parser.parse()?);
+            match parser.peek_token() {
+                // a `fn` means the block was never closed, which is reported when parsing `}`
+                Ok(
+                    Token::Symbol(Symbol::CloseBrace) | Token::Keyword(Keyword::Fn) | Token::Eof,
+                ) => break,
+                Ok(_) => {}
+                Err(error) => {
+                    parser.recover(error);
+                    continue;
+                }
             }
+
+            statements.push(parser.recovering(
+                |parser| parser.parse_with_struct_literals(true),
+                Statement::Error,
+            ));
         }
 
         Ok(Self {
@@ -1,10 +1,12 @@
 use crate::ast::{
-    Declaration, FunctionArgument, FunctionDeclaration, Keyword, Punctuated, Symbol, Token,
+    Declaration, FileModule, FunctionArgument, FunctionDeclaration, InlineModule, Keyword,
+    ModuleBody, ModuleDeclaration, Pub, Punctuated, StructDeclaration, StructField, Symbol, Token,
+    UseAlias, UseDeclaration, UseTree,
 };
 use crate::error::Expected;
 use crate::{ast::ReturnType, error::Error};
 
-use super::{Parse, Parser};
+use super::{statement::parse_mutability, Parse, Parser};
 
 impl Parse for ReturnType {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
@@ -15,9 +17,18 @@ impl Parse for ReturnType {
     }
 }
 
+fn parse_visibility(parser: &mut Parser) -> Result<Option<Pub>, Error> {
+    if let Token::Keyword(Keyword::Pub) = parser.peek_token()? {
+        Ok(Some(parser.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
 impl Parse for FunctionArgument {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         Ok(Self {
+            mutability: parse_mutability(parser)?,
             ident: parser.parse()?,
             ty: parser.parse()?,
         })
@@ -26,6 +37,8 @@ impl Parse for FunctionArgument {
 
 impl Parse for FunctionDeclaration {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        let doc = parser.take_doc_comment()?;
+        let visibility = parse_visibility(parser)?;
         let _fn = parser.parse()?;
         let ident = parser.parse()?;
         let open = parser.parse()?;
@@ -39,6 +52,8 @@ impl Parse for FunctionDeclaration {
         };
 
         Ok(Self {
+            doc,
+            visibility,
             _fn,
             ident,
             open,
@@ -50,15 +65,178 @@ impl Parse for FunctionDeclaration {
     }
 }
 
-impl Parse for Declaration {
+impl Parse for StructField {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            doc: parser.take_doc_comment()?,
+            ident: parser.parse()?,
+            ty: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for StructDeclaration {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            doc: parser.take_doc_comment()?,
+            visibility: parse_visibility(parser)?,
+            _struct: parser.parse()?,
+            ident: parser.parse()?,
+            open: parser.parse()?,
+            fields: Punctuated::parse_terminated(parser, Token::Symbol(Symbol::CloseBrace))?,
+            close: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for InlineModule {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        let open = parser.parse()?;
+        let mut declarations = Vec::new();
+
+        loop {
+            match parser.peek_token() {
+                Ok(Token::Symbol(Symbol::CloseBrace) | Token::Eof) => break,
+                Ok(_) => declarations.push(parser.recovering(Parser::parse, Declaration::Error)),
+                Err(error) => parser.recover(error),
+            }
+        }
+
+        Ok(Self {
+            open,
+            declarations,
+            close: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for ModuleBody {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         match parser.peek_token()? {
-            Token::Keyword(Keyword::Fn) => Ok(Self::Function(parser.parse()?)),
+            Token::Symbol(Symbol::OpenBrace) => Ok(Self::Inline(parser.parse()?)),
+            Token::Symbol(Symbol::SemiColon) => Ok(Self::File(FileModule {
+                semi_colon: parser.parse()?,
+                source: None,
+                declarations: Vec::new(),
+            })),
             tok => Err(Error::expected_any(
-                &[Expected::Keyword(Keyword::Fn)],
+                &[
+                    Expected::Symbol(Symbol::OpenBrace),
+                    Expected::Symbol(Symbol::SemiColon),
+                ],
                 tok,
                 parser.next_span()?,
             )),
         }
     }
 }
+
+impl Parse for ModuleDeclaration {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            doc: parser.take_doc_comment()?,
+            visibility: parse_visibility(parser)?,
+            _mod: parser.parse()?,
+            ident: parser.parse()?,
+            body: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for UseAlias {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            _as: parser.parse()?,
+            ident: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for UseTree {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        match parser.peek_token()? {
+            Token::Symbol(Symbol::Asterisk) => Ok(Self::Glob(parser.parse()?)),
+            Token::Symbol(Symbol::OpenBrace) => Ok(Self::Group {
+                open: parser.parse()?,
+                trees: Punctuated::parse_terminated(parser, Token::Symbol(Symbol::CloseBrace))?,
+                close: parser.parse()?,
+            }),
+            Token::Ident(_) | Token::Keyword(Keyword::Super) => {
+                let segment = parser.parse()?;
+
+                match parser.peek_token()? {
+                    Token::Symbol(Symbol::ColonColon) => Ok(Self::Path {
+                        segment,
+                        colon_colon: parser.parse()?,
+                        tree: parser.parse()?,
+                    }),
+                    Token::Keyword(Keyword::As) => Ok(Self::Name {
+                        segment,
+                        alias: Some(parser.parse()?),
+                    }),
+                    _ => Ok(Self::Name {
+                        segment,
+                        alias: None,
+                    }),
+                }
+            }
+            tok => Err(Error::expected_any(
+                &[
+                    Expected::Ident,
+                    Expected::Keyword(Keyword::Super),
+                    Expected::Symbol(Symbol::Asterisk),
+                    Expected::Symbol(Symbol::OpenBrace),
+                ],
+                tok,
+                parser.next_span()?,
+            )),
+        }
+    }
+}
+
+impl Parse for UseDeclaration {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        let visibility = parse_visibility(parser)?;
+        let _use = parser.parse()?;
+
+        let absolute = if let Token::Symbol(Symbol::ColonColon) = parser.peek_token()? {
+            Some(parser.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            visibility,
+            _use,
+            absolute,
+            tree: parser.parse()?,
+            semi_colon: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for Declaration {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        // look past the visibility, which is parsed by the declaration itself
+        let mut lookahead = parser.clone();
+        parse_visibility(&mut lookahead)?;
+
+        match lookahead.peek_token()? {
+            Token::Keyword(Keyword::Fn) => Ok(Self::Function(parser.parse()?)),
+            Token::Keyword(Keyword::Struct) => Ok(Self::Struct(parser.parse()?)),
+            Token::Keyword(Keyword::Mod) => Ok(Self::Module(parser.parse()?)),
+            Token::Keyword(Keyword::Use) => Ok(Self::Use(parser.parse()?)),
+            tok => Err(Error::expected_any(
+                &[
+                    Expected::Keyword(Keyword::Pub),
+                    Expected::Keyword(Keyword::Fn),
+                    Expected::Keyword(Keyword::Struct),
+                    Expected::Keyword(Keyword::Mod),
+                    Expected::Keyword(Keyword::Use),
+                ],
+                tok,
+                lookahead.next_span()?,
+            )),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{
     ast::{
-        BinaryExpression, BinaryOperator, CallExpression, Expression, Keyword, LiteralExpression,
-        ParenExpression, Punctuated, ReturnExpression, Symbol, Token, UnaryExpression,
-        UnaryOperator,
+        ArrayExpression, ArrayRepeatExpression, AssignExpression, AssignOperator, BinaryExpression,
+        BinaryOperator, BreakExpression, CallExpression, CastExpression, ContinueExpression,
+        ElseBody, ElseBranch, Expression, FieldExpression, FieldInitializer, IfExpression,
+        IndexExpression, Keyword, Label, LiteralExpression, LoopExpression, LoopLabel,
+        ParenExpression, Punctuated, RangeExpression, ReturnExpression, StructExpression, Symbol,
+        Token, UnaryExpression, UnaryOperator, WhileExpression,
     },
     error::{Error, Expected},
 };
@@ -13,9 +16,19 @@ impl Parse for LiteralExpression {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         match parser.peek_token()? {
             Token::Integer(_) => Ok(LiteralExpression::Integer(parser.parse()?)),
+            Token::Float(_) => Ok(LiteralExpression::Float(parser.parse()?)),
             Token::String(_) => Ok(LiteralExpression::String(parser.parse()?)),
+            Token::Keyword(Keyword::True | Keyword::False) => {
+                Ok(LiteralExpression::Bool(parser.parse()?))
+            }
             tok => Err(Error::expected_any(
-                &[Expected::String, Expected::Integer],
+                &[
+                    Expected::String,
+                    Expected::Integer,
+                    Expected::Float,
+                    Expected::Keyword(Keyword::True),
+                    Expected::Keyword(Keyword::False),
+                ],
                 tok,
                 parser.next_span()?,
             )),
@@ -27,23 +40,35 @@ impl Parse for ParenExpression {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         Ok(Self {
             open: parser.parse()?,
-            expression: parser.parse()?,
+            expression: parser.parse_with_struct_literals(true)?,
             close: parser.parse()?,
         })
     }
 }
 
+impl Parse for FieldInitializer {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            ident: parser.parse()?,
+            colon: parser.parse()?,
+            expression: parser.parse()?,
+        })
+    }
+}
+
 impl Parse for UnaryOperator {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         match parser.peek_token()? {
             Token::Symbol(Symbol::And) => Ok(Self::Reference(parser.parse()?)),
             Token::Symbol(Symbol::Asterisk) => Ok(Self::Dereference(parser.parse()?)),
             Token::Symbol(Symbol::Minus) => Ok(Self::Negate(parser.parse()?)),
+            Token::Symbol(Symbol::Bang) => Ok(Self::Not(parser.parse()?)),
             tok => Err(Error::expected_any(
                 &[
                     Expected::Symbol(Symbol::And),
                     Expected::Symbol(Symbol::Asterisk),
                     Expected::Symbol(Symbol::Minus),
+                    Expected::Symbol(Symbol::Bang),
                 ],
                 tok,
                 parser.next_span()?,
@@ -59,24 +84,75 @@ impl Parse for BinaryOperator {
             Token::Symbol(Symbol::Minus) => Ok(Self::Sub(parser.parse()?)),
             Token::Symbol(Symbol::Asterisk) => Ok(Self::Mul(parser.parse()?)),
             Token::Symbol(Symbol::Slash) => Ok(Self::Div(parser.parse()?)),
+            Token::Symbol(Symbol::Percent) => Ok(Self::Rem(parser.parse()?)),
             Token::Symbol(Symbol::AndAnd) => Ok(Self::LogicalAnd(parser.parse()?)),
             Token::Symbol(Symbol::OrOr) => Ok(Self::LogicalOr(parser.parse()?)),
             Token::Symbol(Symbol::And) => Ok(Self::BinaryAnd(parser.parse()?)),
             Token::Symbol(Symbol::Or) => Ok(Self::BinaryOr(parser.parse()?)),
+            Token::Symbol(Symbol::Caret) => Ok(Self::BinaryXor(parser.parse()?)),
             Token::Symbol(Symbol::ShiftRight) => Ok(Self::BitShiftRight(parser.parse()?)),
             Token::Symbol(Symbol::ShiftLeft) => Ok(Self::BitShiftLeft(parser.parse()?)),
+            Token::Symbol(Symbol::EqualEqual) => Ok(Self::Equal(parser.parse()?)),
+            Token::Symbol(Symbol::BangEqual) => Ok(Self::NotEqual(parser.parse()?)),
+            Token::Symbol(Symbol::Less) => Ok(Self::LessThan(parser.parse()?)),
+            Token::Symbol(Symbol::LessEqual) => Ok(Self::LessThanOrEqual(parser.parse()?)),
+            Token::Symbol(Symbol::Greater) => Ok(Self::GreaterThan(parser.parse()?)),
+            Token::Symbol(Symbol::GreaterEqual) => Ok(Self::GreaterThanOrEqual(parser.parse()?)),
             tok => Err(Error::expected_any(
                 &[
                     Expected::Symbol(Symbol::Plus),
                     Expected::Symbol(Symbol::Minus),
                     Expected::Symbol(Symbol::Asterisk),
                     Expected::Symbol(Symbol::Slash),
+                    Expected::Symbol(Symbol::Percent),
                     Expected::Symbol(Symbol::AndAnd),
                     Expected::Symbol(Symbol::OrOr),
                     Expected::Symbol(Symbol::And),
                     Expected::Symbol(Symbol::Or),
+                    Expected::Symbol(Symbol::Caret),
                     Expected::Symbol(Symbol::ShiftRight),
                     Expected::Symbol(Symbol::ShiftLeft),
+                    Expected::Symbol(Symbol::EqualEqual),
+                    Expected::Symbol(Symbol::BangEqual),
+                    Expected::Symbol(Symbol::Less),
+                    Expected::Symbol(Symbol::LessEqual),
+                    Expected::Symbol(Symbol::Greater),
+                    Expected::Symbol(Symbol::GreaterEqual),
+                ],
+                tok,
+                parser.next_span()?,
+            )),
+        }
+    }
+}
+
+impl Parse for AssignOperator {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        match parser.peek_token()? {
+            Token::Symbol(Symbol::Equal) => Ok(Self::Assign(parser.parse()?)),
+            Token::Symbol(Symbol::PlusEqual) => Ok(Self::Add(parser.parse()?)),
+            Token::Symbol(Symbol::MinusEqual) => Ok(Self::Sub(parser.parse()?)),
+            Token::Symbol(Symbol::AsteriskEqual) => Ok(Self::Mul(parser.parse()?)),
+            Token::Symbol(Symbol::SlashEqual) => Ok(Self::Div(parser.parse()?)),
+            Token::Symbol(Symbol::PercentEqual) => Ok(Self::Rem(parser.parse()?)),
+            Token::Symbol(Symbol::AndEqual) => Ok(Self::BinaryAnd(parser.parse()?)),
+            Token::Symbol(Symbol::OrEqual) => Ok(Self::BinaryOr(parser.parse()?)),
+            Token::Symbol(Symbol::CaretEqual) => Ok(Self::BinaryXor(parser.parse()?)),
+            Token::Symbol(Symbol::ShiftLeftEqual) => Ok(Self::BitShiftLeft(parser.parse()?)),
+            Token::Symbol(Symbol::ShiftRightEqual) => Ok(Self::BitShiftRight(parser.parse()?)),
+            tok => Err(Error::expected_any(
+                &[
+                    Expected::Symbol(Symbol::Equal),
+                    Expected::Symbol(Symbol::PlusEqual),
+                    Expected::Symbol(Symbol::MinusEqual),
+                    Expected::Symbol(Symbol::AsteriskEqual),
+                    Expected::Symbol(Symbol::SlashEqual),
+                    Expected::Symbol(Symbol::PercentEqual),
+                    Expected::Symbol(Symbol::AndEqual),
+                    Expected::Symbol(Symbol::OrEqual),
+                    Expected::Symbol(Symbol::CaretEqual),
+                    Expected::Symbol(Symbol::ShiftLeftEqual),
+                    Expected::Symbol(Symbol::ShiftRightEqual),
                 ],
                 tok,
                 parser.next_span()?,
@@ -104,17 +180,239 @@ impl Parse for ReturnExpression {
     }
 }
 
+impl Parse for ElseBody {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        match parser.peek_token()? {
+            Token::Keyword(Keyword::If) => Ok(Self::If(parser.parse()?)),
+            Token::Symbol(Symbol::OpenBrace) => Ok(Self::Block(parser.parse()?)),
+            tok => Err(Error::expected_any(
+                &[
+                    Expected::Keyword(Keyword::If),
+                    Expected::Symbol(Symbol::OpenBrace),
+                ],
+                tok,
+                parser.next_span()?,
+            )),
+        }
+    }
+}
+
+impl Parse for ElseBranch {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            _else: parser.parse()?,
+            body: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for IfExpression {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        let _if = parser.parse()?;
+        let condition = parser.parse_with_struct_literals(false)?;
+        let block = parser.parse()?;
+
+        let else_branch = if let Token::Keyword(Keyword::Else) = parser.peek_token()? {
+            Some(parser.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            _if,
+            condition,
+            block,
+            else_branch,
+        })
+    }
+}
+
+impl Parse for LoopLabel {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            label: parser.parse()?,
+            colon: parser.parse()?,
+        })
+    }
+}
+
+fn parse_loop_label(parser: &mut Parser) -> Result<Option<LoopLabel>, Error> {
+    if let Token::Label(_) = parser.peek_token()? {
+        Ok(Some(parser.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+impl Parse for WhileExpression {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            label: parse_loop_label(parser)?,
+            _while: parser.parse()?,
+            condition: parser.parse_with_struct_literals(false)?,
+            block: parser.parse()?,
+        })
+    }
+}
+
+impl Parse for LoopExpression {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            label: parse_loop_label(parser)?,
+            _loop: parser.parse()?,
+            block: parser.parse()?,
+        })
+    }
+}
+
+fn parse_break_label(parser: &mut Parser) -> Result<Option<Label>, Error> {
+    if let Token::Label(_) = parser.peek_token()? {
+        Ok(Some(parser.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+impl Parse for BreakExpression {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        let _break = parser.parse()?;
+        let label = parse_break_label(parser)?;
+
+        let expression = match parser.peek_token()? {
+            Token::Symbol(Symbol::SemiColon)
+            | Token::Symbol(Symbol::CloseBrace)
+            | Token::Symbol(Symbol::CloseParen)
+            | Token::Symbol(Symbol::Comma)
+            | Token::Eof => None,
+            _ => Some(parser.parse()?),
+        };
+
+        Ok(Self {
+            _break,
+            label,
+            expression,
+        })
+    }
+}
+
+impl Parse for ContinueExpression {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        Ok(Self {
+            _continue: parser.parse()?,
+            label: parse_break_label(parser)?,
+        })
+    }
+}
+
+/// Parses a labeled loop, `'label: loop { .. }` or `'label: while .. { .. }`.
+fn parse_labeled_expression(parser: &mut Parser) -> Result<Expression, Error> {
+    let label = Some(parser.parse::<LoopLabel>()?);
+
+    match parser.peek_token()? {
+        Token::Keyword(Keyword::While) => Ok(Expression::While(WhileExpression {
+            label,
+            _while: parser.parse()?,
+            condition: parser.parse_with_struct_literals(false)?,
+            block: parser.parse()?,
+        })),
+        Token::Keyword(Keyword::Loop) => Ok(Expression::Loop(LoopExpression {
+            label,
+            _loop: parser.parse()?,
+            block: parser.parse()?,
+        })),
+        tok => Err(Error::expected_any(
+            &[
+                Expected::Keyword(Keyword::While),
+                Expected::Keyword(Keyword::Loop),
+            ],
+            tok,
+            parser.next_span()?,
+        )),
+    }
+}
+
+fn parse_path_expression(parser: &mut Parser) -> Result<Expression, Error> {
+    let path = parser.parse()?;
+
+    if parser.struct_literals() && parser.peek_token()? == Token::Symbol(Symbol::OpenBrace) {
+        Ok(Expression::Struct(StructExpression {
+            path,
+            open: parser.parse()?,
+            fields: Punctuated::parse_terminated(parser, Token::Symbol(Symbol::CloseBrace))?,
+            close: parser.parse()?,
+        }))
+    } else {
+        Ok(Expression::Path(path))
+    }
+}
+
+/// Parses `[a, b, c]` or `[x; N]`, which are told apart by the `;` after the first element.
+fn parse_array_expression(parser: &mut Parser) -> Result<Expression, Error> {
+    let open = parser.parse()?;
+    let close = Token::Symbol(Symbol::CloseBracket);
+
+    let mut elements = Punctuated::new();
+
+    if parser.peek_token()? != close {
+        let first = parser.parse_with_struct_literals(true)?;
+
+        if let Token::Symbol(Symbol::SemiColon) = parser.peek_token()? {
+            return Ok(Expression::ArrayRepeat(ArrayRepeatExpression {
+                open,
+                value: Box::new(first),
+                semi_colon: parser.parse()?,
+                length: parser.parse()?,
+                close: parser.parse()?,
+            }));
+        }
+
+        elements.items.push(first);
+
+        while parser.peek_token()? != close {
+            elements.punct.push(parser.parse()?);
+
+            if parser.peek_token()? == close {
+                break;
+            }
+
+            elements
+                .items
+                .push(parser.parse_with_struct_literals(true)?);
+        }
+    }
+
+    Ok(Expression::Array(ArrayExpression {
+        open,
+        elements,
+        close: parser.parse()?,
+    }))
+}
+
 fn parse_term_expression(parser: &mut Parser) -> Result<Expression, Error> {
     match parser.peek_token()? {
-        Token::Integer(_) | Token::String(_) => Ok(Expression::Literal(parser.parse()?)),
+        Token::Integer(_)
+        | Token::Float(_)
+        | Token::String(_)
+        | Token::Keyword(Keyword::True | Keyword::False) => {
+            Ok(Expression::Literal(parser.parse()?))
+        }
         Token::Symbol(Symbol::OpenParen) => Ok(Expression::Paren(parser.parse()?)),
-        Token::Ident(_) => Ok(Expression::Path(parser.parse()?)),
+        Token::Symbol(Symbol::OpenBracket) => parse_array_expression(parser),
+        Token::Ident(_) | Token::Keyword(Keyword::Super) | Token::Symbol(Symbol::ColonColon) => {
+            parse_path_expression(parser)
+        }
         tok => Err(Error::expected_any(
             &[
                 Expected::Ident,
+                Expected::Keyword(Keyword::Super),
+                Expected::Symbol(Symbol::ColonColon),
                 Expected::String,
                 Expected::Integer,
+                Expected::Float,
+                Expected::Keyword(Keyword::True),
+                Expected::Keyword(Keyword::False),
                 Expected::Symbol(Symbol::OpenParen),
+                Expected::Symbol(Symbol::OpenBracket),
             ],
             tok,
             parser.next_span()?,
@@ -122,80 +420,228 @@ fn parse_term_expression(parser: &mut Parser) -> Result<Expression, Error> {
     }
 }
 
-fn parse_call_expression(parser: &mut Parser) -> Result<Expression, Error> {
-    let function = parse_term_expression(parser)?;
+/// How tightly an operator binds, from loosest to tightest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Lowest,
+    Assign,
+    Range,
+    LogicalOr,
+    LogicalAnd,
+    Comparison,
+    BinaryOr,
+    BinaryXor,
+    BinaryAnd,
+    Shift,
+    Sum,
+    Product,
+    Cast,
+    Prefix,
+    Postfix,
+}
 
-    match parser.peek_token()? {
-        Token::Symbol(Symbol::OpenParen) => Ok(Expression::Call(CallExpression {
-            function: Box::new(function),
-            open: parser.parse()?,
-            arguments: Punctuated::parse_terminated(parser, Token::Symbol(Symbol::CloseParen))?,
-            close: parser.parse()?,
-        })),
-        _ => Ok(function),
+impl Precedence {
+    /// The next tighter precedence.
+    const fn next(self) -> Self {
+        match self {
+            Self::Lowest => Self::Assign,
+            Self::Assign => Self::Range,
+            Self::Range => Self::LogicalOr,
+            Self::LogicalOr => Self::LogicalAnd,
+            Self::LogicalAnd => Self::Comparison,
+            Self::Comparison => Self::BinaryOr,
+            Self::BinaryOr => Self::BinaryXor,
+            Self::BinaryXor => Self::BinaryAnd,
+            Self::BinaryAnd => Self::Shift,
+            Self::Shift => Self::Sum,
+            Self::Sum => Self::Product,
+            Self::Product => Self::Cast,
+            Self::Cast => Self::Prefix,
+            Self::Prefix | Self::Postfix => Self::Postfix,
+        }
     }
 }
 
-fn parse_unary_expression(parser: &mut Parser) -> Result<Expression, Error> {
+/// How a chain of operators with the same precedence groups, e.g. `a - b - c`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Associativity {
+    /// `(a - b) - c`.
+    Left,
+    /// `a = (b = c)`.
+    Right,
+    /// `a < b < c` is an error.
+    None,
+}
+
+/// Gets the precedence and associativity of `token` following an expression, or [`None`] if
+/// it doesn't continue the expression.
+fn infix_precedence(token: &Token) -> Option<(Precedence, Associativity)> {
+    let precedence = match token {
+        Token::Symbol(Symbol::OpenParen | Symbol::OpenBracket | Symbol::Dot) => Precedence::Postfix,
+        Token::Keyword(Keyword::As) => Precedence::Cast,
+        Token::Symbol(Symbol::Asterisk | Symbol::Slash | Symbol::Percent) => Precedence::Product,
+        Token::Symbol(Symbol::Plus | Symbol::Minus) => Precedence::Sum,
+        Token::Symbol(Symbol::ShiftLeft | Symbol::ShiftRight) => Precedence::Shift,
+        Token::Symbol(Symbol::And) => Precedence::BinaryAnd,
+        Token::Symbol(Symbol::Caret) => Precedence::BinaryXor,
+        Token::Symbol(Symbol::Or) => Precedence::BinaryOr,
+        Token::Symbol(
+            Symbol::EqualEqual
+            | Symbol::BangEqual
+            | Symbol::Less
+            | Symbol::LessEqual
+            | Symbol::Greater
+            | Symbol::GreaterEqual,
+        ) => return Some((Precedence::Comparison, Associativity::None)),
+        Token::Symbol(Symbol::DotDot) => return Some((Precedence::Range, Associativity::None)),
+        Token::Symbol(Symbol::AndAnd) => Precedence::LogicalAnd,
+        Token::Symbol(Symbol::OrOr) => Precedence::LogicalOr,
+        Token::Symbol(
+            Symbol::Equal
+            | Symbol::PlusEqual
+            | Symbol::MinusEqual
+            | Symbol::AsteriskEqual
+            | Symbol::SlashEqual
+            | Symbol::PercentEqual
+            | Symbol::AndEqual
+            | Symbol::OrEqual
+            | Symbol::CaretEqual
+            | Symbol::ShiftLeftEqual
+            | Symbol::ShiftRightEqual,
+        ) => return Some((Precedence::Assign, Associativity::Right)),
+        _ => return None,
+    };
+
+    Some((precedence, Associativity::Left))
+}
+
+/// Parses a term, a prefix operator applied to an expression, or an expression starting with a
+/// keyword like `if` or `return`.
+fn parse_prefix_expression(parser: &mut Parser) -> Result<Expression, Error> {
     match parser.peek_token()? {
         Token::Symbol(Symbol::And)
         | Token::Symbol(Symbol::Asterisk)
-        | Token::Symbol(Symbol::Minus) => {
-            let operator = parser.parse::<UnaryOperator>()?;
+        | Token::Symbol(Symbol::Minus)
+        | Token::Symbol(Symbol::Bang) => Ok(Expression::Unary(UnaryExpression {
+            operator: parser.parse()?,
+            expression: Box::new(parse_precedence_expression(parser, Precedence::Prefix)?),
+        })),
+        Token::Keyword(
+            Keyword::Return
+            | Keyword::If
+            | Keyword::While
+            | Keyword::Loop
+            | Keyword::Break
+            | Keyword::Continue,
+        )
+        | Token::Label(_) => parser.parse(),
+        Token::Symbol(Symbol::OpenBrace) => Ok(Expression::Block(parser.parse()?)),
+        Token::Symbol(Symbol::DotDot) => Ok(Expression::Range(RangeExpression {
+            start: None,
+            dot_dot: parser.parse()?,
+            end: parse_range_end(parser)?,
+        })),
+        _ => parse_term_expression(parser),
+    }
+}
 
-            Ok(Expression::Unary(UnaryExpression {
-                operator,
-                expression: Box::new(parse_unary_expression(parser)?),
-            }))
+/// Parses the end of a range after the `..`, which is omitted if the range is followed by a
+/// closing delimiter, e.g. `a[b..]`.
+fn parse_range_end(parser: &mut Parser) -> Result<Option<Box<Expression>>, Error> {
+    match parser.peek_token()? {
+        Token::Symbol(
+            Symbol::CloseBracket
+            | Symbol::CloseParen
+            | Symbol::CloseBrace
+            | Symbol::Comma
+            | Symbol::SemiColon,
+        )
+        | Token::Eof => Ok(None),
+        _ => {
+            let end = parse_precedence_expression(parser, Precedence::Range.next())?;
+            Ok(Some(Box::new(end)))
         }
-        _ => parse_call_expression(parser),
     }
 }
 
-fn parse_binary_expression(parser: &mut Parser) -> Result<Expression, Error> {
-    let lhs = parse_unary_expression(parser)?;
+/// Parses an expression containing only operators that bind at least as tightly as
+/// `precedence`.
+fn parse_precedence_expression(
+    parser: &mut Parser,
+    precedence: Precedence,
+) -> Result<Expression, Error> {
+    let mut expression = parse_prefix_expression(parser)?;
+    // the last non-associative operator, which may not be followed by another of the same level
+    let mut non_associative = None;
 
-    match parser.peek_token()? {
-        Token::Symbol(Symbol::Plus)
-        | Token::Symbol(Symbol::Minus)
-        | Token::Symbol(Symbol::Asterisk)
-        | Token::Symbol(Symbol::Slash)
-        | Token::Symbol(Symbol::AndAnd)
-        | Token::Symbol(Symbol::OrOr)
-        | Token::Symbol(Symbol::And)
-        | Token::Symbol(Symbol::Or)
-        | Token::Symbol(Symbol::ShiftRight)
-        | Token::Symbol(Symbol::ShiftLeft) => {
-            let operator = parser.parse::<BinaryOperator>()?;
-            let rhs = parse_binary_expression(parser)?;
-
-            if let Expression::Binary(rhs) = rhs {
-                if operator.precedence() >= rhs.operator.precedence() {
-                    Ok(Expression::Binary(BinaryExpression {
-                        lhs: Box::new(Expression::Binary(BinaryExpression {
-                            lhs: Box::new(lhs),
-                            operator,
-                            rhs: rhs.lhs,
-                        })),
-                        operator: rhs.operator,
-                        rhs: rhs.rhs,
-                    }))
-                } else {
-                    Ok(Expression::Binary(BinaryExpression {
-                        lhs: Box::new(lhs),
-                        operator,
-                        rhs: Box::new(Expression::Binary(rhs)),
-                    }))
-                }
-            } else {
-                Ok(Expression::Binary(BinaryExpression {
-                    lhs: Box::new(lhs),
-                    operator,
-                    rhs: Box::new(rhs),
-                }))
-            }
+    loop {
+        let token = parser.peek_token()?;
+
+        let (operator_precedence, associativity) = match infix_precedence(&token) {
+            Some(infix) if infix.0 >= precedence => infix,
+            _ => return Ok(expression),
+        };
+
+        if non_associative == Some(operator_precedence) {
+            let message = match operator_precedence {
+                Precedence::Range => "Ranges cannot be chained, use parentheses",
+                _ => "Comparison operators cannot be chained, use parentheses",
+            };
+
+            return Err(Error::spanned(message, parser.next_span()?));
         }
-        _ => Ok(lhs),
+
+        let rhs_precedence = match associativity {
+            Associativity::Left | Associativity::None => operator_precedence.next(),
+            Associativity::Right => operator_precedence,
+        };
+
+        non_associative = match associativity {
+            Associativity::None => Some(operator_precedence),
+            _ => None,
+        };
+
+        expression = match token {
+            Token::Symbol(Symbol::OpenParen) => Expression::Call(CallExpression {
+                function: Box::new(expression),
+                open: parser.parse()?,
+                arguments: Punctuated::parse_terminated(parser, Token::Symbol(Symbol::CloseParen))?,
+                close: parser.parse()?,
+            }),
+            Token::Symbol(Symbol::OpenBracket) => Expression::Index(IndexExpression {
+                expression: Box::new(expression),
+                open: parser.parse()?,
+                index: Box::new(parser.parse_with_struct_literals(true)?),
+                close: parser.parse()?,
+            }),
+            Token::Symbol(Symbol::Dot) => Expression::Field(FieldExpression {
+                expression: Box::new(expression),
+                dot: parser.parse()?,
+                ident: parser.parse()?,
+            }),
+            Token::Symbol(Symbol::DotDot) => Expression::Range(RangeExpression {
+                start: Some(Box::new(expression)),
+                dot_dot: parser.parse()?,
+                end: parse_range_end(parser)?,
+            }),
+            Token::Keyword(Keyword::As) => Expression::Cast(CastExpression {
+                expression: Box::new(expression),
+                _as: parser.parse()?,
+                ty: parser.parse()?,
+            }),
+            _ if operator_precedence == Precedence::Assign => {
+                Expression::Assign(AssignExpression {
+                    lhs: Box::new(expression),
+                    operator: parser.parse()?,
+                    rhs: Box::new(parse_precedence_expression(parser, rhs_precedence)?),
+                })
+            }
+            _ => Expression::Binary(BinaryExpression {
+                lhs: Box::new(expression),
+                operator: parser.parse()?,
+                rhs: Box::new(parse_precedence_expression(parser, rhs_precedence)?),
+            }),
+        };
     }
 }
 
@@ -205,21 +651,52 @@ impl Parse for Expression {
             Token::Ident(_)
             | Token::String(_)
             | Token::Integer(_)
+            | Token::Float(_)
+            | Token::Keyword(Keyword::True | Keyword::False)
+            | Token::Keyword(Keyword::Super)
+            | Token::Symbol(Symbol::ColonColon)
             | Token::Symbol(Symbol::OpenParen)
+            | Token::Symbol(Symbol::OpenBracket)
+            | Token::Symbol(Symbol::DotDot)
             | Token::Symbol(Symbol::And)
             | Token::Symbol(Symbol::Asterisk)
-            | Token::Symbol(Symbol::Minus) => parse_binary_expression(parser),
+            | Token::Symbol(Symbol::Minus)
+            | Token::Symbol(Symbol::Bang) => {
+                parse_precedence_expression(parser, Precedence::Lowest)
+            }
+            Token::Symbol(Symbol::OpenBrace) => Ok(Self::Block(parser.parse()?)),
             Token::Keyword(Keyword::Return) => Ok(Self::Return(parser.parse()?)),
+            Token::Keyword(Keyword::If) => Ok(Self::If(parser.parse()?)),
+            Token::Keyword(Keyword::While) => Ok(Self::While(parser.parse()?)),
+            Token::Keyword(Keyword::Loop) => Ok(Self::Loop(parser.parse()?)),
+            Token::Keyword(Keyword::Break) => Ok(Self::Break(parser.parse()?)),
+            Token::Keyword(Keyword::Continue) => Ok(Self::Continue(parser.parse()?)),
+            Token::Label(_) => parse_labeled_expression(parser),
             tok => Err(Error::expected_any(
                 &[
                     Expected::Ident,
+                    Expected::Label,
                     Expected::String,
                     Expected::Integer,
+                    Expected::Float,
+                    Expected::Keyword(Keyword::True),
+                    Expected::Keyword(Keyword::False),
                     Expected::Keyword(Keyword::Return),
+                    Expected::Keyword(Keyword::If),
+                    Expected::Keyword(Keyword::While),
+                    Expected::Keyword(Keyword::Loop),
+                    Expected::Keyword(Keyword::Break),
+                    Expected::Keyword(Keyword::Continue),
+                    Expected::Keyword(Keyword::Super),
+                    Expected::Symbol(Symbol::ColonColon),
                     Expected::Symbol(Symbol::OpenParen),
+                    Expected::Symbol(Symbol::OpenBracket),
+                    Expected::Symbol(Symbol::DotDot),
+                    Expected::Symbol(Symbol::OpenBrace),
                     Expected::Symbol(Symbol::And),
                     Expected::Symbol(Symbol::Asterisk),
                     Expected::Symbol(Symbol::Minus),
+                    Expected::Symbol(Symbol::Bang),
                 ],
                 tok,
                 parser.next_span()?,
@@ -230,6 +707,218 @@ impl Parse for Expression {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        ast::Expression,
+        parse::Parser,
+        source::SourceId,
+        span::{Span, Spanned},
+    };
+
+    fn parse(source: &str) -> Expression {
+        Parser::new(source, SourceId::default()).parse().unwrap()
+    }
+
+    /// Renders `expression` as an s-expression, operators are taken from `source`.
+    fn tree(source: &str, expression: &Expression) -> String {
+        let text = |span: Span| &source[span.index()..span.end()];
+
+        match expression {
+            Expression::Paren(ast) => tree(source, &ast.expression),
+            Expression::Literal(ast) => text(ast.span()).to_string(),
+            Expression::Path(ast) => ast.to_string(),
+            Expression::Call(ast) => {
+                let arguments: Vec<_> = (ast.arguments.iter())
+                    .map(|argument| tree(source, argument))
+                    .collect();
+
+                format!(
+                    "(call {} {})",
+                    tree(source, &ast.function),
+                    arguments.join(" ")
+                )
+            }
+            Expression::Field(ast) => {
+                format!("(. {} {})", tree(source, &ast.expression), ast.ident)
+            }
+            Expression::Index(ast) => format!(
+                "(index {} {})",
+                tree(source, &ast.expression),
+                tree(source, &ast.index)
+            ),
+            Expression::Array(ast) => {
+                let elements: Vec<_> = (ast.elements.iter())
+                    .map(|element| tree(source, element))
+                    .collect();
+
+                format!("[{}]", elements.join(" "))
+            }
+            Expression::ArrayRepeat(ast) => format!(
+                "[{}; {}]",
+                tree(source, &ast.value),
+                text(ast.length.span())
+            ),
+            Expression::Unary(ast) => format!(
+                "({} {})",
+                text(ast.operator.span()),
+                tree(source, &ast.expression)
+            ),
+            Expression::Cast(ast) => format!(
+                "(as {} {})",
+                tree(source, &ast.expression),
+                text(ast.ty.span())
+            ),
+            Expression::Binary(ast) => format!(
+                "({} {} {})",
+                text(ast.operator.span()),
+                tree(source, &ast.lhs),
+                tree(source, &ast.rhs)
+            ),
+            Expression::Assign(ast) => format!(
+                "({} {} {})",
+                text(ast.operator.span()),
+                tree(source, &ast.lhs),
+                tree(source, &ast.rhs)
+            ),
+            Expression::Return(ast) => format!("(return {})", tree(source, &ast.expression)),
+            Expression::Range(ast) => {
+                let bound = |bound: &Option<Box<Expression>>| match bound {
+                    Some(bound) => tree(source, bound),
+                    None => "_".to_string(),
+                };
+
+                format!("(.. {} {})", bound(&ast.start), bound(&ast.end))
+            }
+            ast => panic!("unexpected expression {:?}", ast),
+        }
+    }
+
     #[test]
     fn parse_expression() {}
+
+    #[test]
+    fn operator_precedence() {
+        let cases = [
+            ("a - b - c", "(- (- a b) c)"),
+            ("a / b * c % d", "(% (* (/ a b) c) d)"),
+            ("a + b * c - d / e", "(- (+ a (* b c)) (/ d e))"),
+            ("a | b ^ c & d << e + f", "(| a (^ b (& c (<< d (+ e f)))))"),
+            ("a >> b >> c", "(>> (>> a b) c)"),
+            ("a || b && c == d + 1", "(|| a (&& b (== c (+ d 1))))"),
+            ("a && b || c && d", "(|| (&& a b) (&& c d))"),
+            ("a & b == c | d", "(== (& a b) (| c d))"),
+            ("(a - b) - (c - d)", "(- (- a b) (- c d))"),
+            (
+                "-a.b(c) as i64 * *d",
+                "(* (as (- (call (. a b) c)) i64) (* d))",
+            ),
+            ("!x == -y", "(== (! x) (- y))"),
+            ("&a.b.c", "(& (. (. a b) c))"),
+            ("1 as u8 as i32 + 2", "(+ (as (as 1 u8) i32) 2)"),
+            ("f(a + b, g(c))(d)", "(call (call f (+ a b) (call g c)) d)"),
+            ("a = b = c + 1", "(= a (= b (+ c 1)))"),
+            ("a.b = return c < d", "(= (. a b) (return (< c d)))"),
+            ("*a += b -= c * d", "(+= (* a) (-= b (* c d)))"),
+            ("a <<= b >> c", "(<<= a (>> b c))"),
+            (
+                "-a.b[c + d][e](f)",
+                "(- (call (index (index (. a b) (+ c d)) e) f))",
+            ),
+            (
+                "[a, b + c,][d] = [[e; 2]; 3]",
+                "(= (index [a (+ b c)] d) [[e; 2]; 3])",
+            ),
+            ("*a[b] as u8", "(as (* (index a b)) u8)"),
+            ("a[b..c + 1]", "(index a (.. b (+ c 1)))"),
+            (
+                "a[..b || c][d..]",
+                "(index (index a (.. _ (|| b c))) (.. d _))",
+            ),
+            ("a[..]", "(index a (.. _ _))"),
+            ("a = b..c", "(= a (.. b c))"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(tree(source, &parse(source)), expected, "{}", source);
+        }
+
+        match parse("a = if b { c } else { d } + 1") {
+            Expression::Assign(ast) => assert!(matches!(*ast.rhs, Expression::Binary(_))),
+            ast => panic!("expected assignment, found {:?}", ast),
+        }
+
+        for source in ["a < b < c", "a == b != c", "a < b >= c + 1", "a..b..c"] {
+            let mut parser = Parser::new(source, SourceId::default());
+            assert!(parser.parse::<Expression>().is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn parse_loops() {
+        match parse("'outer: while a { break 'outer; }") {
+            Expression::While(ast) => assert_eq!(ast.label.unwrap().label.string(), "outer"),
+            ast => panic!("expected while, found {:?}", ast),
+        }
+
+        match parse("loop { continue; }") {
+            Expression::Loop(ast) => assert!(ast.label.is_none()),
+            ast => panic!("expected loop, found {:?}", ast),
+        }
+
+        match parse("break 'a x") {
+            Expression::Break(ast) => {
+                assert_eq!(ast.label.unwrap().string(), "a");
+                assert!(ast.expression.is_some());
+            }
+            ast => panic!("expected break, found {:?}", ast),
+        }
+
+        match parse("break") {
+            Expression::Break(ast) => assert!(ast.label.is_none() && ast.expression.is_none()),
+            ast => panic!("expected break, found {:?}", ast),
+        }
+    }
+
+    #[test]
+    fn parse_structs() {
+        match parse("p.a.b = Point { x: 1, y: f(2) }") {
+            Expression::Assign(ast) => {
+                assert!(matches!(*ast.lhs, Expression::Field(_)));
+                assert!(matches!(*ast.rhs, Expression::Struct(ref s) if s.fields.len() == 2));
+            }
+            ast => panic!("expected assignment, found {:?}", ast),
+        }
+
+        match parse("if a { } else { }") {
+            Expression::If(ast) => assert!(matches!(*ast.condition, Expression::Path(_))),
+            ast => panic!("expected if, found {:?}", ast),
+        }
+
+        match parse("while (A {}).b { }") {
+            Expression::While(ast) => assert!(matches!(*ast.condition, Expression::Field(_))),
+            ast => panic!("expected while, found {:?}", ast),
+        }
+    }
+
+    #[test]
+    fn expression_spans() {
+        let text = |source: &str, span: Span| source[span.index()..span.end()].to_string();
+
+        let source = "  return  a.b + f(1, 2)  ";
+        let ast = parse(source);
+        assert_eq!(text(source, ast.span()), "return  a.b + f(1, 2)");
+
+        match ast {
+            Expression::Return(ast) => {
+                assert_eq!(text(source, ast._return.span()), "return");
+                assert_eq!(text(source, ast.expression.span()), "a.b + f(1, 2)");
+            }
+            ast => panic!("expected return, found {:?}", ast),
+        }
+
+        let source = "'a: loop { break 'a ::x::y; }";
+        assert_eq!(text(source, parse(source).span()), source);
+
+        let source = "if a { } else if b { } else { c; }";
+        assert_eq!(text(source, parse(source).span()), source);
+    }
 }
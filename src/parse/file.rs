@@ -0,0 +1,185 @@
+use std::{fs, path::Path};
+
+use crate::{
+    ast::{Declaration, ModuleBody, Program},
+    diagnostic::Diagnostics,
+    error::Error,
+    source::{Source, SourceId, Sources},
+    span::Spanned,
+};
+
+use super::Parser;
+
+/// Parses the file at `path`, and the files of every `mod name;` it declares.
+///
+/// Every file read is added to `sources`. Module files that fail to load are reported to
+/// `diagnostics` and left unloaded, [`None`] is only returned if the file at `path` fails.
+pub fn parse_file(
+    sources: &mut Sources,
+    path: impl AsRef<Path>,
+    diagnostics: &mut Diagnostics,
+) -> Option<Program> {
+    let path = path.as_ref();
+    let source = read_source(sources, path, diagnostics);
+    let (mut program, _) = diagnostics.report(source)?;
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    load_modules(sources, directory, &mut program.declarations, diagnostics);
+
+    Some(program)
+}
+
+fn read_source(
+    sources: &mut Sources,
+    path: &Path,
+    diagnostics: &mut Diagnostics,
+) -> Result<(Program, SourceId), Error> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| Error::new(format!("Failed to read {}: {}", path.display(), error)))?;
+
+    let id = sources.insert(Source::new(path, contents));
+    let contents = sources.get(id).unwrap().contents();
+
+    let mut parser = Parser::new(contents, id);
+    let program = parser.parse()?;
+    diagnostics.extend(parser.take_errors());
+
+    Ok((program, id))
+}
+
+/// Loads the files of every `mod name;` in `declarations`, looking for `name.fn` and
+/// `name/mod.fn` in `directory`.
+fn load_modules(
+    sources: &mut Sources,
+    directory: &Path,
+    declarations: &mut [Declaration],
+    diagnostics: &mut Diagnostics,
+) {
+    for declaration in declarations {
+        let module = match declaration {
+            Declaration::Module(module) => module,
+            _ => continue,
+        };
+
+        let name = module.ident.to_string();
+        let sub_directory = directory.join(&name);
+
+        match module.body {
+            ModuleBody::Inline(ref mut inline) => {
+                load_modules(
+                    sources,
+                    &sub_directory,
+                    &mut inline.declarations,
+                    diagnostics,
+                );
+            }
+            ModuleBody::File(ref mut file) => {
+                let file_path = directory.join(format!("{}.fn", name));
+                let mod_path = sub_directory.join("mod.fn");
+
+                let path = match (file_path.is_file(), mod_path.is_file()) {
+                    (true, false) => file_path,
+                    (false, true) => mod_path,
+                    (true, true) => {
+                        diagnostics.push(Error::spanned(
+                            format!(
+                                "Module {} found at both {} and {}",
+                                name,
+                                file_path.display(),
+                                mod_path.display()
+                            ),
+                            module.ident.span(),
+                        ));
+
+                        continue;
+                    }
+                    (false, false) => {
+                        diagnostics.push(Error::spanned(
+                            format!(
+                                "Module {} not found, create {} or {}",
+                                name,
+                                file_path.display(),
+                                mod_path.display()
+                            ),
+                            module.ident.span(),
+                        ));
+
+                        continue;
+                    }
+                };
+
+                let (program, source) = match read_source(sources, &path, diagnostics) {
+                    Ok(source) => source,
+                    Err(error) => {
+                        diagnostics.push(error);
+                        continue;
+                    }
+                };
+
+                file.source = Some(source);
+                file.declarations = program.declarations;
+
+                load_modules(sources, &sub_directory, &mut file.declarations, diagnostics);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        ast::{Declaration, ModuleBody},
+        diagnostic::Diagnostics,
+        source::Sources,
+    };
+
+    use super::parse_file;
+
+    #[test]
+    fn file_modules() {
+        let directory = std::env::temp_dir().join(format!("fern-modules-{}", std::process::id()));
+        fs::create_dir_all(directory.join("a/c")).unwrap();
+        fs::create_dir_all(directory.join("b")).unwrap();
+
+        fs::write(directory.join("main.fn"), "mod a; mod b; fn main() {}").unwrap();
+        fs::write(directory.join("a.fn"), "mod c { mod d; }").unwrap();
+        fs::write(directory.join("a/c/d.fn"), "fn d() {}").unwrap();
+        fs::write(directory.join("b/mod.fn"), "fn b() {}").unwrap();
+
+        let mut sources = Sources::new();
+        let mut diagnostics = Diagnostics::new();
+        let program = parse_file(&mut sources, directory.join("main.fn"), &mut diagnostics);
+        let program = program.unwrap();
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(sources.iter().count(), 4);
+
+        match program.declarations[0] {
+            Declaration::Module(ref module) => match module.body {
+                ModuleBody::File(ref file) => {
+                    let path = sources.get(file.source.unwrap()).unwrap().path();
+                    assert_eq!(path, directory.join("a.fn"));
+                }
+                ModuleBody::Inline(_) => panic!("expected file module"),
+            },
+            _ => panic!("expected module"),
+        }
+
+        // every missing module is reported
+        fs::write(directory.join("main.fn"), "mod e; mod f; fn main() {}").unwrap();
+
+        let mut diagnostics = Diagnostics::new();
+        let program = parse_file(
+            &mut Sources::new(),
+            directory.join("main.fn"),
+            &mut diagnostics,
+        );
+
+        assert_eq!(program.unwrap().declarations.len(), 3);
+        assert_eq!(diagnostics.errors().count(), 2);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 mod block;
 mod declaration;
 mod expression;
+mod file;
 mod parser;
 mod path;
 mod program;
@@ -8,12 +9,5 @@ mod punctuated;
 mod statement;
 mod ty;
 
-pub use block::*;
-pub use declaration::*;
-pub use expression::*;
+pub use file::*;
 pub use parser::*;
-pub use path::*;
-pub use program::*;
-pub use punctuated::*;
-pub use statement::*;
-pub use ty::*;
@@ -1,7 +1,10 @@
 use std::{hint::unreachable_unchecked, iter::Peekable, str::Chars};
 
 use crate::{
-    ast::{keyword_from_string, symbol_from_chars, Integer, IntegerKind, Token},
+    ast::{
+        keyword_from_string, symbol_from_chars, DocComment, Float, Integer, IntegerKind, Keyword,
+        NumberSuffix, Symbol, Token,
+    },
     error::Error,
     source::SourceId,
     span::Span,
@@ -13,6 +16,14 @@ pub struct Parser<'a> {
     peeked_token: Option<(Token, Span)>,
     source: SourceId,
     index: usize,
+    /// The byte index just past the last token taken by [`Self::next_token`].
+    previous_end: usize,
+    /// The number of `{` taken by [`Self::next_token`] that haven't been closed.
+    depth: usize,
+    struct_literals: bool,
+    errors: Vec<Error>,
+    /// The doc comment before the next token.
+    doc_comment: Option<DocComment>,
 }
 
 impl<'a> Parser<'a> {
@@ -22,6 +33,11 @@ impl<'a> Parser<'a> {
             peeked_token: None,
             source: source_id,
             index: 0,
+            previous_end: 0,
+            depth: 0,
+            struct_literals: true,
+            errors: Vec::new(),
+            doc_comment: None,
         }
     }
 
@@ -56,28 +72,105 @@ impl<'a> Parser<'a> {
         let _ = self.next_char();
     }
 
+    /// Peeks the character after [`Self::peek_char`].
+    fn peek_second_char(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next()
+    }
+
+    /// Skips whitespace and comments, `///` doc comments are collected for
+    /// [`Self::take_doc_comment`].
     pub fn skip_whitespace(&mut self) {
         loop {
-            match self.peek_char() {
-                Some(ch) if ch.is_whitespace() => self.skip_char(),
+            match (self.peek_char(), self.peek_second_char()) {
+                (Some(ch), _) if ch.is_whitespace() => self.skip_char(),
+                (Some('/'), Some('/')) => self.skip_line_comment(),
+                (Some('/'), Some('*')) => self.skip_block_comment(),
                 _ => break,
             }
         }
     }
 
-    /// [`self`] *must* not be [`Self::empty`].
-    fn parse_ident(&mut self) -> Result<String, Error> {
-        let mut ident = String::new();
+    fn skip_line_comment(&mut self) {
+        let mut span = self.span();
+        let mut comment = String::new();
+
+        while let Some(ch) = self.peek_char() {
+            if ch == '\n' {
+                break;
+            }
+
+            self.skip_char();
+            comment.push(ch);
+        }
+
+        span |= self.span();
+
+        // `////` is a regular comment, like in rust
+        let doc = match comment.strip_prefix("///") {
+            Some(doc) if !doc.starts_with('/') => doc,
+            _ => return,
+        };
+
+        let doc = doc.trim_end_matches('\r');
+        let doc = doc.strip_prefix(' ').unwrap_or(doc);
+
+        match self.doc_comment {
+            Some(ref mut comment) => {
+                comment.lines.push(doc.to_string());
+                comment.span |= span;
+            }
+            None => {
+                self.doc_comment = Some(DocComment {
+                    lines: vec![doc.to_string()],
+                    span,
+                });
+            }
+        }
+    }
+
+    /// Skips a `/* */` comment, which can be nested.
+    fn skip_block_comment(&mut self) {
+        let span = self.span();
+        let mut depth = 0usize;
 
         loop {
-            if let Some(ch) = self.peek_char() {
-                if ch == '_' || ch.is_alphabetic() || ch.is_numeric() {
+            match (self.peek_char(), self.peek_second_char()) {
+                (Some('/'), Some('*')) => {
+                    self.skip_char();
+                    self.skip_char();
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
                     self.skip_char();
+                    self.skip_char();
+                    depth -= 1;
 
-                    ident.push(ch);
-                } else {
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                (Some(_), _) => self.skip_char(),
+                (None, _) => {
+                    let span = span | self.span();
+                    self.errors
+                        .push(Error::spanned("Unterminated block comment", span));
                     break;
                 }
+            }
+        }
+    }
+
+    /// [`self`] *must* not be [`Self::empty`].
+    fn parse_ident(&mut self) -> Result<String, Error> {
+        let mut ident = String::new();
+
+        while let Some(ch) = self.peek_char() {
+            if ch == '_' || ch.is_alphabetic() || ch.is_numeric() {
+                self.skip_char();
+
+                ident.push(ch);
             } else {
                 break;
             }
@@ -86,51 +179,151 @@ impl<'a> Parser<'a> {
         Ok(ident)
     }
 
+    /// Parses a string literal, bad escapes are recorded and skipped so the rest of the string
+    /// can still be parsed.
     fn parse_string(&mut self) -> Result<String, Error> {
+        let start = self.span();
         self.skip_char();
         let mut string = String::new();
 
         loop {
-            let ch = self.next_char()?;
+            let span = self.char_span();
 
-            if ch == '"' {
-                break;
-            } else {
-                string.push(ch);
+            let ch = match self.peek_char() {
+                Some(ch) => ch,
+                None => {
+                    let span = start | self.span();
+                    return Err(Error::spanned("Unterminated string literal", span));
+                }
+            };
+
+            self.skip_char();
+
+            match ch {
+                '"' => break,
+                // an escape at the end of the file is reported as an unterminated string
+                '\\' if self.peek_char().is_none() => {}
+                '\\' => match self.parse_escape(span) {
+                    Ok(ch) => string.push(ch),
+                    Err(error) => self.errors.push(error),
+                },
+                ch => string.push(ch),
             }
         }
 
         Ok(string)
     }
 
-    fn parse_number(&mut self) -> Result<Token, Error> {
-        let span = self.span();
-        let mut value: i64 = 0;
+    /// Parses an escape sequence after the `\`, `start` is the span of the `\`.
+    fn parse_escape(&mut self, start: Span) -> Result<char, Error> {
+        let ch = self.next_char()?;
+
+        match ch {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            '\\' => Ok('\\'),
+            '"' => Ok('"'),
+            '\'' => Ok('\''),
+            'x' => {
+                let value = self.parse_hex_digits(2);
+                let span = start | self.span();
+
+                match value {
+                    Some((value, 2)) if value <= 0x7f => Ok(value as u8 as char),
+                    Some((_, 2)) => Err(Error::spanned(
+                        "Hex escapes must be at most \\x7f, use \\u{..} for other characters",
+                        span,
+                    )),
+                    _ => Err(Error::spanned(
+                        "Hex escapes must have exactly two hex digits",
+                        span,
+                    )),
+                }
+            }
+            'u' => {
+                if self.peek_char() != Some('{') {
+                    let span = start | self.span();
+                    return Err(Error::spanned("Expected '{' after \\u", span));
+                }
+
+                self.skip_char();
+                let value = self.parse_hex_digits(6);
+
+                if self.peek_char() != Some('}') {
+                    let span = start | self.span();
+                    return Err(Error::spanned("Unterminated unicode escape", span));
+                }
+
+                self.skip_char();
+                let span = start | self.span();
+
+                match value {
+                    None => Err(Error::spanned("Unicode escapes must have a value", span)),
+                    Some((value, _)) => char::from_u32(value).ok_or_else(|| {
+                        let message = format!("{:#x} is not a valid unicode character", value);
+                        Error::spanned(message, span)
+                    }),
+                }
+            }
+            ch => Err(Error::spanned(
+                format!("Unknown escape sequence '\\{}'", ch),
+                start | self.span(),
+            )),
+        }
+    }
+
+    /// Parses up to `max` hex digits, returning the value and the number of digits, or [`None`]
+    /// if there were no digits.
+    fn parse_hex_digits(&mut self, max: usize) -> Option<(u32, usize)> {
+        let mut value = 0;
         let mut digits = 0;
-        let mut kind = IntegerKind::Decimal;
 
-        loop {
-            if let Some(ch) = self.peek_char() {
-                if value == 0 && digits == 1 && kind == IntegerKind::Decimal && ch == 'b' {
+        while digits < max {
+            match self.peek_char().and_then(|ch| ch.to_digit(16)) {
+                Some(digit) => {
                     self.skip_char();
-                    digits = 0;
-                    kind = IntegerKind::Binary;
-                } else if value == 0 && digits == 1 && kind == IntegerKind::Decimal && ch == 'x' {
-                    self.skip_char();
-                    digits = 0;
-                    kind = IntegerKind::Hex;
-                } else if let Some(digit) = ch.to_digit(kind.radix()) {
-                    self.skip_char();
-                    value *= kind.radix() as i64;
-                    value += digit as i64;
+                    value = value * 16 + digit;
                     digits += 1;
-                } else if ch.is_digit(10) {
-                    let span = span | self.char_span();
-                    return Err(Error::spanned("Integer contains invalid digit", span)
-                        .with_hint("Here", self.char_span()));
-                } else {
-                    break;
                 }
+                None => break,
+            }
+        }
+
+        (digits > 0).then_some((value, digits))
+    }
+
+    fn parse_number(&mut self) -> Result<Token, Error> {
+        let span = self.span();
+        // `None` if the integer is too large, which is only an error if it isn't a float
+        let mut value = Some(0u64);
+        let mut digits = 0;
+        let mut kind = IntegerKind::Decimal;
+        // the digits of a decimal number, in case it turns out to be a float
+        let mut text = String::new();
+
+        while let Some(ch) = self.peek_char() {
+            if value == Some(0) && digits == 1 && kind == IntegerKind::Decimal && ch == 'b' {
+                self.skip_char();
+                digits = 0;
+                kind = IntegerKind::Binary;
+            } else if value == Some(0) && digits == 1 && kind == IntegerKind::Decimal && ch == 'x' {
+                self.skip_char();
+                digits = 0;
+                kind = IntegerKind::Hex;
+            } else if let Some(digit) = ch.to_digit(kind.radix()) {
+                self.skip_char();
+                digits += 1;
+                text.push(ch);
+
+                value = value
+                    .and_then(|value| value.checked_mul(kind.radix() as u64))
+                    .and_then(|value| value.checked_add(digit as u64));
+            } else if ch.is_ascii_digit() {
+                let span = span | self.char_span();
+                return Err(Error::spanned("Integer contains invalid digit", span)
+                    .with_hint("Here", self.char_span()));
             } else {
                 break;
             }
@@ -143,14 +336,141 @@ impl<'a> Parser<'a> {
             ));
         }
 
-        Ok(Token::Integer(Integer::new(value, kind)))
+        let fraction = self.peek_char() == Some('.')
+            && self
+                .peek_second_char()
+                .is_some_and(|ch| ch.is_ascii_digit());
+        let exponent = matches!(self.peek_char(), Some('e' | 'E'))
+            && (self.peek_second_char())
+                .is_some_and(|ch| ch.is_ascii_digit() || ch == '+' || ch == '-');
+
+        if kind == IntegerKind::Decimal && (fraction || exponent) {
+            return self.parse_float(text, span);
+        }
+
+        let suffix = self.parse_number_suffix()?;
+
+        match suffix {
+            Some(suffix) if suffix.is_float() => {
+                if kind != IntegerKind::Decimal {
+                    return Err(Error::spanned(
+                        format!("Only decimal literals can have the suffix {}", suffix),
+                        span | self.span(),
+                    ));
+                }
+
+                let value = text.parse::<f64>().unwrap();
+                Ok(Token::Float(Float::new(value).with_suffix(Some(suffix))))
+            }
+            _ => match value {
+                Some(value) => Ok(Token::Integer(
+                    Integer::new(value, kind).with_suffix(suffix),
+                )),
+                None => Err(Error::spanned(
+                    "Integer literal is too large",
+                    span | self.span(),
+                )),
+            },
+        }
+    }
+
+    /// Parses the type suffix directly following a number literal, e.g. `u8` in `5u8`.
+    fn parse_number_suffix(&mut self) -> Result<Option<NumberSuffix>, Error> {
+        match self.peek_char() {
+            Some(ch) if ch == '_' || ch.is_alphabetic() => {
+                let span = self.span();
+                let suffix = self.parse_ident()?;
+
+                match NumberSuffix::from_string(&suffix) {
+                    Some(suffix) => Ok(Some(suffix)),
+                    None => Err(Error::spanned(
+                        format!("Invalid suffix {} for number literal", suffix),
+                        span | self.span(),
+                    )),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Parses the fraction and exponent of a float, after the integer digits in `text`.
+    fn parse_float(&mut self, mut text: String, span: Span) -> Result<Token, Error> {
+        if self.peek_char() == Some('.') {
+            self.skip_char();
+            text.push('.');
+            self.parse_decimal_digits(&mut text);
+        }
+
+        if let Some('e' | 'E') = self.peek_char() {
+            self.skip_char();
+            text.push('e');
+
+            if let Some(sign @ ('+' | '-')) = self.peek_char() {
+                self.skip_char();
+                text.push(sign);
+            }
+
+            if self.parse_decimal_digits(&mut text) == 0 {
+                return Err(Error::spanned(
+                    "Float exponents must contain at least one digit",
+                    span | self.span(),
+                ));
+            }
+        }
+
+        let suffix_span = self.span();
+        let suffix = self.parse_number_suffix()?;
+
+        if let Some(suffix) = suffix.filter(|suffix| !suffix.is_float()) {
+            return Err(Error::spanned(
+                format!("Invalid suffix {} for float literal", suffix),
+                suffix_span | self.span(),
+            ));
+        }
+
+        // the text is always a valid float at this point
+        let value = text.parse::<f64>().unwrap();
+        Ok(Token::Float(Float::new(value).with_suffix(suffix)))
+    }
+
+    /// Pushes decimal digits to `text`, returning the number of digits.
+    fn parse_decimal_digits(&mut self, text: &mut String) -> usize {
+        let mut digits = 0;
+
+        while let Some(ch) = self.peek_char().filter(char::is_ascii_digit) {
+            self.skip_char();
+            text.push(ch);
+            digits += 1;
+        }
+
+        digits
     }
 
     pub fn next_token(&mut self) -> Result<Token, Error> {
-        if let Some((token, _)) = self.peeked_token.take() {
-            return Ok(token);
+        let token = match self.peeked_token.take() {
+            Some((token, span)) => {
+                self.previous_end = span.end();
+                token
+            }
+            None => {
+                let token = self.read_token()?;
+                self.previous_end = self.index;
+                token
+            }
+        };
+
+        match token {
+            Token::Symbol(Symbol::OpenBrace) => self.depth += 1,
+            Token::Symbol(Symbol::CloseBrace) => self.depth = self.depth.saturating_sub(1),
+            _ => {}
         }
 
+        self.doc_comment = None;
+
+        Ok(token)
+    }
+
+    fn read_token(&mut self) -> Result<Token, Error> {
         if self.is_empty() {
             return Ok(Token::Eof);
         }
@@ -167,8 +487,21 @@ impl<'a> Parser<'a> {
                     Ok(Token::Ident(ident))
                 }
             }
-            Some(ch) if ch.is_digit(10) => self.parse_number(),
-            Some(ch) if ch == '"' => {
+            Some(ch) if ch.is_ascii_digit() => self.parse_number(),
+            Some('\'') => {
+                self.skip_char();
+
+                match self.peek_char() {
+                    Some(ch) if ch == '_' || ch.is_alphabetic() => {
+                        Ok(Token::Label(self.parse_ident()?))
+                    }
+                    _ => Err(Error::spanned(
+                        "Labels must start with a letter or '_'",
+                        span | self.char_span(),
+                    )),
+                }
+            }
+            Some('"') => {
                 let string = self.parse_string()?;
 
                 Ok(Token::String(string))
@@ -176,8 +509,10 @@ impl<'a> Parser<'a> {
             Some(ch) => {
                 self.skip_char();
 
-                if let Some((symbol, should_skip)) = symbol_from_chars(ch, self.peek_char()) {
-                    if should_skip {
+                let (second, third) = (self.peek_char(), self.peek_second_char());
+
+                if let Some((symbol, length)) = symbol_from_chars(ch, second, third) {
+                    for _ in 0..length {
                         self.skip_char();
                     }
 
@@ -197,7 +532,7 @@ impl<'a> Parser<'a> {
     fn peek_next_token(&mut self) -> Result<(), Error> {
         self.skip_whitespace();
         let mut span = self.span();
-        let token = self.next_token()?;
+        let token = self.read_token()?;
         span |= self.span();
 
         self.peeked_token = Some((token, span));
@@ -227,6 +562,125 @@ impl<'a> Parser<'a> {
     pub fn parse<T: Parse>(&mut self) -> Result<T, Error> {
         T::parse(self)
     }
+
+    /// Returns false when a `{` after a path starts a block rather than a struct literal.
+    pub const fn struct_literals(&self) -> bool {
+        self.struct_literals
+    }
+
+    /// Parses `T` with struct literals allowed or not, see [`Self::struct_literals`].
+    pub fn parse_with_struct_literals<T: Parse>(&mut self, allowed: bool) -> Result<T, Error> {
+        let struct_literals = std::mem::replace(&mut self.struct_literals, allowed);
+        let result = self.parse();
+        self.struct_literals = struct_literals;
+
+        result
+    }
+
+    /// Takes the `///` comments before the next token, call before parsing the first token of a
+    /// declaration.
+    pub fn take_doc_comment(&mut self) -> Result<Option<DocComment>, Error> {
+        if self.peeked_token.is_none() {
+            self.peek_next_token()?;
+        }
+
+        Ok(self.doc_comment.take())
+    }
+
+    /// Syntax errors recovered from by [`Self::recover`].
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    pub fn take_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Records `error` and skips tokens until the next `;`, `}` or `fn` keyword, or a `struct`,
+    /// `mod`, `use` or `pub` keyword outside of braces opened while skipping.
+    ///
+    /// A `;` is skipped, `}` and keywords are not. Braces opened while skipping are skipped along
+    /// with their contents.
+    pub fn recover(&mut self, error: Error) {
+        self.recover_to(error, self.depth);
+    }
+
+    /// Like [`Self::recover`], but first skips out of any braces opened since `depth`.
+    fn recover_to(&mut self, error: Error, depth: usize) {
+        self.errors.push(error);
+
+        loop {
+            let index = self.index;
+
+            let token = match self.peek_token() {
+                Ok(token) => token,
+                Err(error) => {
+                    self.errors.push(error);
+
+                    if self.index == index {
+                        self.skip_char();
+                    }
+
+                    continue;
+                }
+            };
+
+            match token {
+                Token::Eof | Token::Keyword(Keyword::Fn) => break,
+                Token::Symbol(Symbol::SemiColon) if self.depth <= depth => {
+                    let _ = self.next_token();
+                    break;
+                }
+                Token::Symbol(Symbol::CloseBrace) if self.depth <= depth => break,
+                // the start of the next declaration
+                Token::Keyword(Keyword::Struct | Keyword::Mod | Keyword::Use | Keyword::Pub)
+                    if self.depth <= depth =>
+                {
+                    break
+                }
+                _ => {
+                    let _ = self.next_token();
+                }
+            }
+        }
+    }
+
+    /// Parses with `parse`, if it fails the error is [recovered](Self::recover) from, and
+    /// `error` is called with the span of the skipped source to create an error node.
+    pub fn recovering<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, Error>,
+        error: impl FnOnce(Span) -> T,
+    ) -> T {
+        let start = self.token_start();
+        let depth = self.depth;
+
+        match parse(self) {
+            Ok(node) => node,
+            Err(err) => {
+                self.recover_to(err, depth);
+
+                // always make progress, so callers parsing in a loop can't get stuck
+                if self.previous_end <= start.index() {
+                    let _ = self.next_token();
+                }
+
+                let length = self.previous_end.saturating_sub(start.index());
+                error(Span::new(self.source, start.index(), length))
+            }
+        }
+    }
+
+    /// The start of the next token, without reading it.
+    fn token_start(&mut self) -> Span {
+        match self.peeked_token {
+            Some((_, span)) => Span::new(self.source, span.index(), 0),
+            None => {
+                self.skip_whitespace();
+                self.span()
+            }
+        }
+    }
 }
 
 pub trait Parse: Sized {
@@ -238,3 +692,142 @@ impl<T: Parse> Parse for Box<T> {
         Ok(Box::new(parser.parse()?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{Float, Integer, IntegerKind, NumberSuffix, Symbol, Token},
+        source::SourceId,
+    };
+
+    use super::Parser;
+
+    fn tokens(parser: &mut Parser) -> Vec<Token> {
+        let mut tokens = Vec::new();
+
+        loop {
+            match parser.next_token().unwrap() {
+                Token::Eof => break tokens,
+                token => tokens.push(token),
+            }
+        }
+    }
+
+    #[test]
+    fn skip_comments() {
+        let source = "a // b\n/* c /* d */ e */ f / g /**/ //";
+        let mut parser = Parser::new(source, SourceId::default());
+
+        let expected = [
+            Token::Ident(String::from("a")),
+            Token::Ident(String::from("f")),
+            Token::Symbol(Symbol::Slash),
+            Token::Ident(String::from("g")),
+        ];
+
+        assert_eq!(tokens(&mut parser), expected);
+        assert!(parser.errors().is_empty());
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        let mut parser = Parser::new("a /* /* */", SourceId::default());
+
+        assert_eq!(tokens(&mut parser), [Token::Ident(String::from("a"))]);
+        assert_eq!(parser.errors().len(), 1);
+    }
+
+    #[test]
+    fn float_literals() {
+        let source = "1.5 2e3 0.25E-2 3.x 100000000000000000000.0";
+        let mut parser = Parser::new(source, SourceId::default());
+
+        let expected = [
+            Token::Float(Float::new(1.5)),
+            Token::Float(Float::new(2e3)),
+            Token::Float(Float::new(0.25e-2)),
+            Token::Integer(Integer::new(3, IntegerKind::Decimal)),
+            Token::Symbol(Symbol::Dot),
+            Token::Ident(String::from("x")),
+            Token::Float(Float::new(1e20)),
+        ];
+
+        assert_eq!(tokens(&mut parser), expected);
+
+        let mut parser = Parser::new("1e+", SourceId::default());
+        assert!(parser.next_token().is_err());
+    }
+
+    #[test]
+    fn number_suffixes() {
+        let source = "5u8 0xffi64 2f32 1.5f64 18446744073709551615";
+        let mut parser = Parser::new(source, SourceId::default());
+
+        let expected = [
+            Token::Integer(
+                Integer::new(5, IntegerKind::Decimal).with_suffix(Some(NumberSuffix::U8)),
+            ),
+            Token::Integer(
+                Integer::new(255, IntegerKind::Hex).with_suffix(Some(NumberSuffix::I64)),
+            ),
+            Token::Float(Float::new(2.0).with_suffix(Some(NumberSuffix::F32))),
+            Token::Float(Float::new(1.5).with_suffix(Some(NumberSuffix::F64))),
+            Token::Integer(Integer::new(u64::MAX, IntegerKind::Decimal)),
+        ];
+
+        assert_eq!(tokens(&mut parser), expected);
+
+        for source in ["5u7", "1.5u8", "0x1f64x", "18446744073709551616", "0b1f32"] {
+            let mut parser = Parser::new(source, SourceId::default());
+            assert!(parser.next_token().is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn compound_symbols() {
+        let source = "<<= >>= << <= < += ->";
+        let mut parser = Parser::new(source, SourceId::default());
+
+        let expected = [
+            Token::Symbol(Symbol::ShiftLeftEqual),
+            Token::Symbol(Symbol::ShiftRightEqual),
+            Token::Symbol(Symbol::ShiftLeft),
+            Token::Symbol(Symbol::LessEqual),
+            Token::Symbol(Symbol::Less),
+            Token::Symbol(Symbol::PlusEqual),
+            Token::Symbol(Symbol::Arrow),
+        ];
+
+        assert_eq!(tokens(&mut parser), expected);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let source = r#""a\n\t\\\"\0\x7f\u{e6}\u{1F600}""#;
+        let mut parser = Parser::new(source, SourceId::default());
+
+        let expected = Token::String(String::from("a\n\t\\\"\0\x7f\u{e6}\u{1F600}"));
+        assert_eq!(tokens(&mut parser), [expected]);
+        assert!(parser.errors().is_empty());
+    }
+
+    #[test]
+    fn bad_escapes() {
+        let source = r#""\q \x80 \x4 \u{110000} \u{} \u{41" b"#;
+        let mut parser = Parser::new(source, SourceId::default());
+
+        assert_eq!(tokens(&mut parser).len(), 2);
+
+        let spans: Vec<_> = (parser.errors().iter())
+            .map(|error| &source[error.span().unwrap().index()..error.span().unwrap().end()])
+            .collect();
+
+        assert_eq!(
+            spans,
+            [r"\q", r"\x80", r"\x4", r"\u{110000}", r"\u{}", r"\u{41"]
+        );
+
+        let mut parser = Parser::new("\"abc", SourceId::default());
+        assert!(parser.next_token().is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    ast::{Path, PathSegment, Punctuated, Symbol, Token},
+    ast::{Keyword, Path, PathSegment, Punctuated, Super, Symbol, Token},
     error::{Error, Expected},
 };
 
@@ -9,8 +9,9 @@ impl Parse for PathSegment {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         match parser.peek_token()? {
             Token::Ident(_) => Ok(Self::Ident(parser.parse()?)),
+            Token::Keyword(Keyword::Super) => Ok(Self::Super(parser.parse::<Super>()?)),
             tok => Err(Error::expected_any(
-                &[Expected::Ident],
+                &[Expected::Ident, Expected::Keyword(Keyword::Super)],
                 tok,
                 parser.next_span()?,
             )),
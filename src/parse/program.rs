@@ -1,5 +1,5 @@
 use crate::{
-    ast::{Program, Token},
+    ast::{Declaration, Program, Token},
     error::Error,
 };
 
@@ -10,13 +10,129 @@ impl Parse for Program {
         let mut declarations = Vec::new();
 
         loop {
-            if let Token::Eof = parser.peek_token()? {
-                break;
-            } else {
-                declarations.push(parser.parse()?);
+            match parser.peek_token() {
+                Ok(Token::Eof) => break,
+                Ok(_) => declarations.push(parser.recovering(Parser::parse, Declaration::Error)),
+                Err(error) => parser.recover(error),
             }
         }
 
         Ok(Self { declarations })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{Declaration, Program, Statement},
+        parse::Parser,
+        source::SourceId,
+    };
+
+    fn parse(source: &str) -> (Program, usize) {
+        let mut parser = Parser::new(source, SourceId::default());
+        let program = parser.parse::<Program>().unwrap();
+
+        (program, parser.errors().len())
+    }
+
+    #[test]
+    fn recover_statements() {
+        let (program, errors) = parse("fn a() { let = 1; b(); c(; } fn d() {}");
+
+        assert_eq!(errors, 2);
+        assert_eq!(program.declarations.len(), 2);
+
+        match program.declarations[0] {
+            Declaration::Function(ref function) => {
+                let statements = &function.block.statements;
+
+                assert_eq!(statements.len(), 3);
+                assert!(matches!(statements[0], Statement::Error(_)));
+                assert!(matches!(statements[1], Statement::Expression(_)));
+                assert!(matches!(statements[2], Statement::Error(_)));
+            }
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn recover_declarations() {
+        let source = "struct A { a i32 } fn b() { return 1; } } fn c() { let x = 1;";
+        let (program, errors) = parse(source);
+
+        assert_eq!(errors, 3);
+
+        let kinds = (program.declarations.iter())
+            .map(|declaration| match declaration {
+                Declaration::Function(_) => "function",
+                Declaration::Error(_) => "error",
+                _ => "other",
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds, ["error", "function", "error", "error"]);
+    }
+
+    #[test]
+    fn recover_before_declarations() {
+        let source = "
+            fn g( -> i32 { 1 }
+            struct S { x: i32,, }
+            pub fn h() {}
+        ";
+        let (program, errors) = parse(source);
+
+        assert_eq!(errors, 2);
+
+        let kinds = (program.declarations.iter())
+            .map(|declaration| match declaration {
+                Declaration::Function(_) => "function",
+                Declaration::Error(_) => "error",
+                _ => "other",
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds, ["error", "error", "function"]);
+    }
+
+    #[test]
+    fn doc_comments() {
+        let source = "
+            /// Adds numbers.
+            ///
+            ///Returns the sum.
+            pub fn add() {
+                /// not attached
+                let x = 1;
+            }
+
+            //// not a doc comment
+            struct A {
+                /// Field.
+                a: i32,
+            }
+        ";
+        let (program, errors) = parse(source);
+
+        assert_eq!(errors, 0);
+
+        match program.declarations[0] {
+            Declaration::Function(ref function) => {
+                let doc = function.doc.as_ref().unwrap();
+                assert_eq!(doc.text(), "Adds numbers.\n\nReturns the sum.");
+            }
+            _ => panic!("expected function"),
+        }
+
+        match program.declarations[1] {
+            Declaration::Struct(ref declaration) => {
+                assert!(declaration.doc.is_none());
+
+                let field = declaration.fields.first().unwrap();
+                assert_eq!(field.doc.as_ref().unwrap().text(), "Field.");
+            }
+            _ => panic!("expected struct"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     ast::{
-        ExpressionStatement, Keyword, LetStatement, LetStatementValue, Statement, Symbol, Token,
+        Expression, ExpressionStatement, Keyword, LetStatement, LetStatementValue, Mut, Statement,
+        Symbol, Token,
     },
     error::{Error, Expected},
 };
@@ -16,9 +17,19 @@ impl Parse for LetStatementValue {
     }
 }
 
+/// Parses an optional `mut`.
+pub(super) fn parse_mutability(parser: &mut Parser) -> Result<Option<Mut>, Error> {
+    if let Token::Keyword(Keyword::Mut) = parser.peek_token()? {
+        Ok(Some(parser.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
 impl Parse for LetStatement {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         let _let = parser.parse()?;
+        let mutability = parse_mutability(parser)?;
         let ident = parser.parse()?;
 
         let ty = if let Token::Symbol(Symbol::Colon) = parser.peek_token()? {
@@ -35,6 +46,7 @@ impl Parse for LetStatement {
 
         Ok(Self {
             _let,
+            mutability,
             ident,
             ty,
             value,
@@ -45,9 +57,19 @@ impl Parse for LetStatement {
 
 impl Parse for ExpressionStatement {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        let expression = parser.parse::<Expression>()?;
+
+        // the tail of a block and block like expressions don't need a `;`
+        let semi_colon = match parser.peek_token()? {
+            Token::Symbol(Symbol::SemiColon) => Some(parser.parse()?),
+            Token::Symbol(Symbol::CloseBrace) => None,
+            _ if expression.is_block_like() => None,
+            _ => Some(parser.parse()?),
+        };
+
         Ok(Self {
-            expression: parser.parse()?,
-            semi_colon: parser.parse()?,
+            expression,
+            semi_colon,
         })
     }
 }
@@ -59,22 +81,49 @@ impl Parse for Statement {
             Token::Ident(_)
             | Token::String(_)
             | Token::Integer(_)
+            | Token::Float(_)
+            | Token::Keyword(Keyword::True | Keyword::False)
             | Token::Keyword(Keyword::Return)
+            | Token::Keyword(Keyword::If)
+            | Token::Keyword(Keyword::While)
+            | Token::Keyword(Keyword::Loop)
+            | Token::Keyword(Keyword::Break)
+            | Token::Keyword(Keyword::Continue)
+            | Token::Label(_)
+            | Token::Keyword(Keyword::Super)
+            | Token::Symbol(Symbol::ColonColon)
             | Token::Symbol(Symbol::OpenParen)
+            | Token::Symbol(Symbol::OpenBrace)
+            | Token::Symbol(Symbol::OpenBracket)
             | Token::Symbol(Symbol::And)
             | Token::Symbol(Symbol::Asterisk)
-            | Token::Symbol(Symbol::Minus) => Ok(Self::Expression(parser.parse()?)),
+            | Token::Symbol(Symbol::Minus)
+            | Token::Symbol(Symbol::Bang) => Ok(Self::Expression(parser.parse()?)),
             Token::Keyword(Keyword::Let) => Ok(Self::Let(parser.parse()?)),
             tok => Err(Error::expected_any(
                 &[
                     Expected::Ident,
+                    Expected::Label,
                     Expected::String,
                     Expected::Integer,
+                    Expected::Float,
+                    Expected::Keyword(Keyword::True),
+                    Expected::Keyword(Keyword::False),
                     Expected::Keyword(Keyword::Return),
+                    Expected::Keyword(Keyword::If),
+                    Expected::Keyword(Keyword::While),
+                    Expected::Keyword(Keyword::Loop),
+                    Expected::Keyword(Keyword::Break),
+                    Expected::Keyword(Keyword::Continue),
+                    Expected::Keyword(Keyword::Super),
+                    Expected::Symbol(Symbol::ColonColon),
                     Expected::Symbol(Symbol::OpenParen),
+                    Expected::Symbol(Symbol::OpenBrace),
+                    Expected::Symbol(Symbol::OpenBracket),
                     Expected::Symbol(Symbol::And),
                     Expected::Symbol(Symbol::Asterisk),
                     Expected::Symbol(Symbol::Minus),
+                    Expected::Symbol(Symbol::Bang),
                 ],
                 tok,
                 parser.next_span()?,
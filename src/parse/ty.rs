@@ -1,5 +1,8 @@
 use crate::{
-    ast::{IntegerType, Keyword, ReferenceType, Symbol, Token, Type, TypeDeclaration},
+    ast::{
+        ArrayType, FloatType, IntegerType, Keyword, ReferenceType, SliceType, Symbol, Token, Type,
+        TypeDeclaration,
+    },
     error::{Error, Expected},
 };
 
@@ -16,6 +19,7 @@ impl Parse for IntegerType {
             Token::Keyword(Keyword::I32) => Ok(Self::I32(parser.parse()?)),
             Token::Keyword(Keyword::U64) => Ok(Self::U64(parser.parse()?)),
             Token::Keyword(Keyword::I64) => Ok(Self::I64(parser.parse()?)),
+            Token::Keyword(Keyword::Usize) => Ok(Self::Usize(parser.parse()?)),
             tok => Err(Error::expected_any(
                 &[
                     Expected::Keyword(Keyword::U8),
@@ -26,6 +30,24 @@ impl Parse for IntegerType {
                     Expected::Keyword(Keyword::I32),
                     Expected::Keyword(Keyword::U64),
                     Expected::Keyword(Keyword::I64),
+                    Expected::Keyword(Keyword::Usize),
+                ],
+                tok,
+                parser.next_span()?,
+            )),
+        }
+    }
+}
+
+impl Parse for FloatType {
+    fn parse(parser: &mut Parser) -> Result<Self, Error> {
+        match parser.peek_token()? {
+            Token::Keyword(Keyword::F32) => Ok(Self::F32(parser.parse()?)),
+            Token::Keyword(Keyword::F64) => Ok(Self::F64(parser.parse()?)),
+            tok => Err(Error::expected_any(
+                &[
+                    Expected::Keyword(Keyword::F32),
+                    Expected::Keyword(Keyword::F64),
                 ],
                 tok,
                 parser.next_span()?,
@@ -43,11 +65,34 @@ impl Parse for ReferenceType {
     }
 }
 
+/// Parses `[T; N]` or `[T]`, which are told apart by the `;` after the element type.
+fn parse_bracket_type(parser: &mut Parser) -> Result<Type, Error> {
+    let open = parser.parse()?;
+    let ty = parser.parse()?;
+
+    if let Token::Symbol(Symbol::SemiColon) = parser.peek_token()? {
+        Ok(Type::Array(Box::new(ArrayType {
+            open,
+            ty,
+            semi_colon: parser.parse()?,
+            length: parser.parse()?,
+            close: parser.parse()?,
+        })))
+    } else {
+        Ok(Type::Slice(SliceType {
+            open,
+            ty,
+            close: parser.parse()?,
+        }))
+    }
+}
+
 impl Parse for Type {
     fn parse(parser: &mut Parser) -> Result<Self, Error> {
         match parser.peek_token()? {
             Token::Keyword(Keyword::Void) => Ok(Self::Void(parser.parse()?)),
             Token::Keyword(Keyword::Bool) => Ok(Self::Boolean(parser.parse()?)),
+            Token::Keyword(Keyword::Str) => Ok(Self::Str(parser.parse()?)),
             Token::Keyword(Keyword::U8)
             | Token::Keyword(Keyword::I8)
             | Token::Keyword(Keyword::U16)
@@ -55,15 +100,26 @@ impl Parse for Type {
             | Token::Keyword(Keyword::U32)
             | Token::Keyword(Keyword::I32)
             | Token::Keyword(Keyword::U64)
-            | Token::Keyword(Keyword::I64) => Ok(Self::Integer(parser.parse()?)),
+            | Token::Keyword(Keyword::I64)
+            | Token::Keyword(Keyword::Usize) => Ok(Self::Integer(parser.parse()?)),
+            Token::Keyword(Keyword::F32) | Token::Keyword(Keyword::F64) => {
+                Ok(Self::Float(parser.parse()?))
+            }
             Token::Symbol(Symbol::And) => Ok(Self::Reference(parser.parse()?)),
-            Token::Ident(_) => Ok(Self::Path(parser.parse()?)),
+            Token::Symbol(Symbol::OpenBracket) => parse_bracket_type(parser),
+            Token::Ident(_)
+            | Token::Keyword(Keyword::Super)
+            | Token::Symbol(Symbol::ColonColon) => Ok(Self::Path(parser.parse()?)),
             tok => Err(Error::expected_any(
                 &[
                     Expected::Ident,
+                    Expected::Keyword(Keyword::Super),
+                    Expected::Symbol(Symbol::ColonColon),
                     Expected::Symbol(Symbol::And),
+                    Expected::Symbol(Symbol::OpenBracket),
                     Expected::Keyword(Keyword::Void),
                     Expected::Keyword(Keyword::Bool),
+                    Expected::Keyword(Keyword::Str),
                     Expected::Keyword(Keyword::U8),
                     Expected::Keyword(Keyword::I8),
                     Expected::Keyword(Keyword::U16),
@@ -72,6 +128,9 @@ impl Parse for Type {
                     Expected::Keyword(Keyword::I32),
                     Expected::Keyword(Keyword::U64),
                     Expected::Keyword(Keyword::I64),
+                    Expected::Keyword(Keyword::Usize),
+                    Expected::Keyword(Keyword::F32),
+                    Expected::Keyword(Keyword::F64),
                 ],
                 tok,
                 parser.next_span()?,
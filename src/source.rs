@@ -1,3 +1,14 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use crate::span::Span;
+
+/// Identifies a [`Source`] in [`Sources`].
+///
+/// [`SourceId::null`] is never handed out by [`Sources`], and is used for code that wasn't read
+/// from a registered source.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct SourceId(usize);
 
@@ -5,8 +16,194 @@ impl SourceId {
     pub const fn null() -> Self {
         Self(0)
     }
+
+    pub const fn is_null(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A line and column in a source, both starting at 1.
+///
+/// Columns are counted in characters, not bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for LineColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Source {
+    path: PathBuf,
+    contents: String,
+    /// The byte index of the start of every line.
+    line_starts: Vec<usize>,
+}
+
+impl Source {
+    pub fn new(path: impl Into<PathBuf>, contents: impl Into<String>) -> Self {
+        let contents = contents.into();
+
+        let line_starts = std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self {
+            path: path.into(),
+            contents,
+            line_starts,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Gets the contents of `line`, starting at 1, without the line ending.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = (self.line_starts.get(line)).map_or(self.contents.len(), |&end| end);
+
+        let line = &self.contents[start..end];
+        Some(line.trim_end_matches(['\n', '\r']))
+    }
+
+    /// Converts the byte `index` to a line and column, `index` is clamped to the end of the
+    /// source.
+    pub fn line_column(&self, index: usize) -> LineColumn {
+        let mut index = index.min(self.contents.len());
+
+        while !self.contents.is_char_boundary(index) {
+            index -= 1;
+        }
+
+        let line = self.line_starts.partition_point(|&start| start <= index);
+        let start = self.line_starts[line - 1];
+        let column = self.contents[start..index].chars().count() + 1;
+
+        LineColumn { line, column }
+    }
 }
 
-pub struct Source {}
+/// The location of a [`Span`] in a [`Source`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceRange<'a> {
+    pub path: &'a Path,
+    pub start: LineColumn,
+    /// The position just past the end of the span.
+    pub end: LineColumn,
+}
 
-pub struct Sources {}
+impl Display for SourceRange<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.path.display(), self.start)
+    }
+}
+
+/// Every source file of a program, indexed by [`SourceId`].
+#[derive(Clone, Debug, Default)]
+pub struct Sources {
+    sources: Vec<Source>,
+}
+
+impl Sources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, source: Source) -> SourceId {
+        self.sources.push(source);
+        SourceId(self.sources.len())
+    }
+
+    pub fn get(&self, id: SourceId) -> Option<&Source> {
+        self.sources.get(id.0.checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SourceId, &Source)> {
+        (self.sources.iter().enumerate()).map(|(i, source)| (SourceId(i + 1), source))
+    }
+
+    /// Gets the text covered by `span`.
+    pub fn text(&self, span: Span) -> Option<&str> {
+        let contents = self.get(span.source())?.contents();
+        contents.get(span.index()..span.end())
+    }
+
+    /// Converts `span` to a file, line and column range, or [`None`] if the source of `span`
+    /// isn't in `self`.
+    pub fn range(&self, span: Span) -> Option<SourceRange<'_>> {
+        let source = self.get(span.source())?;
+
+        Some(SourceRange {
+            path: source.path(),
+            start: source.line_column(span.index()),
+            end: source.line_column(span.end()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_column() {
+        let source = Source::new("main.fn", "fn main() {\r\n    let x = 1;\n}\n");
+
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.line(1), Some("fn main() {"));
+        assert_eq!(source.line(2), Some("    let x = 1;"));
+        assert_eq!(source.line(4), Some(""));
+        assert_eq!(source.line(0), None);
+        assert_eq!(source.line(5), None);
+
+        let position = |line, column| LineColumn { line, column };
+        assert_eq!(source.line_column(0), position(1, 1));
+        assert_eq!(source.line_column(3), position(1, 4));
+        assert_eq!(source.line_column(17), position(2, 5));
+        assert_eq!(source.line_column(1000), position(4, 1));
+    }
+
+    #[test]
+    fn unicode_columns() {
+        let source = Source::new("main.fn", "let æøå = 1;");
+
+        assert_eq!(source.line_column(10).column, 8);
+        // indices inside a character map to the start of it
+        assert_eq!(source.line_column(5).column, 5);
+    }
+
+    #[test]
+    fn span_ranges() {
+        let mut sources = Sources::new();
+        let a = sources.insert(Source::new("a.fn", "fn a() {}"));
+        let b = sources.insert(Source::new("b.fn", "\nfn b() {}"));
+
+        assert_ne!(a, b);
+        assert!(!a.is_null() && !b.is_null());
+        assert!(sources.get(SourceId::null()).is_none());
+
+        let span = Span::new(b, 4, 1);
+        let range = sources.range(span).unwrap();
+
+        assert_eq!(sources.text(span), Some("b"));
+        assert_eq!(range.start, LineColumn { line: 2, column: 4 });
+        assert_eq!(range.end, LineColumn { line: 2, column: 5 });
+        assert_eq!(range.to_string(), "b.fn:2:4");
+        assert!(sources.range(Span::new(SourceId::null(), 0, 1)).is_none());
+    }
+}
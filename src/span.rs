@@ -49,6 +49,7 @@ impl Span {
 impl BitOr for Span {
     type Output = Self;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn bitor(self, rhs: Self) -> Self::Output {
         let index = self.index.min(rhs.index);
         let end = self.end().max(rhs.end());
@@ -63,6 +64,7 @@ impl BitOr for Span {
 }
 
 impl BitOrAssign for Span {
+    #[allow(clippy::suspicious_op_assign_impl)]
     fn bitor_assign(&mut self, rhs: Self) {
         let index = self.index.min(rhs.index);
         let end = self.end().max(rhs.end());
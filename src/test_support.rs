@@ -0,0 +1,60 @@
+//! Helpers shared by the tests of this crate, and of the backends.
+
+use crate::{
+    ast,
+    compiler::{compile_program, CompileOptions},
+    diagnostic::Diagnostics,
+    error::Error,
+    ir::{Interpreter, Program},
+    parse::Parser,
+    source::SourceId,
+};
+
+/// Parses and compiles `source` with `options`, returning the program if there were no errors,
+/// along with every diagnostic.
+pub fn compile_with(source: &str, options: &CompileOptions) -> (Option<Program>, Diagnostics) {
+    let mut parser = Parser::new(source, SourceId::default());
+    let mut diagnostics = Diagnostics::new();
+
+    let program = parser.parse::<ast::Program>().unwrap();
+    diagnostics.extend(parser.take_errors());
+
+    if diagnostics.has_errors() {
+        return (None, diagnostics);
+    }
+
+    let program = compile_program(program, options, &mut diagnostics);
+    (program, diagnostics)
+}
+
+/// Parses and compiles `source`, returning every diagnostic if there are errors.
+pub fn try_compile(source: &str) -> Result<Program, Diagnostics> {
+    match compile_with(source, &CompileOptions::default()) {
+        (Some(program), _) => Ok(program),
+        (None, diagnostics) => Err(diagnostics),
+    }
+}
+
+pub fn compile(source: &str) -> Program {
+    try_compile(source).unwrap()
+}
+
+/// Compiles `source` expecting it to fail, and returns the first error.
+pub fn compile_error(source: &str) -> Error {
+    let diagnostics = try_compile(source).unwrap_err();
+    diagnostics
+        .into_iter()
+        .find(|error| !error.is_warning())
+        .unwrap()
+}
+
+/// Interprets the function labelled `function`.
+pub fn run(program: &Program, function: &str, arguments: &[u64]) -> u64 {
+    try_run(program, function, arguments).unwrap()
+}
+
+/// Interprets the function labelled `function`, returning the error if it fails.
+pub fn try_run(program: &Program, function: &str, arguments: &[u64]) -> Result<u64, Error> {
+    let id = program.functions.get_by_label(function).unwrap();
+    Interpreter::new(program).call(id, arguments)
+}